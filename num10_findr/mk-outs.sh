@@ -0,0 +1,46 @@
+#!/usr/bin/env bash
+
+# GNU findutils の find で期待値を生成する
+# find はディレクトリの読み込み順に出力するため、期待値はソートしておく
+FIND="/usr/bin/find"
+ROOT="tests/inputs"
+OUT_DIR="tests/expected"
+
+[[ ! -d "$OUT_DIR" ]] && mkdir -p "$OUT_DIR"
+rm -f "$OUT_DIR"/*
+
+find_sorted() {
+    local OUT="$1"
+    shift
+    $FIND "$@" | sort > "$OUT_DIR/$OUT"
+}
+
+# -mtime はチェックアウトした時刻に左右されるため、テストで時刻を設定して確かめる
+# ディレクトリの大きさはファイルシステムによって異なるため、-size は -type f と組み合わせる
+find_sorted path1.txt            $ROOT
+find_sorted path_a.txt           $ROOT/a
+find_sorted path_a_b.txt         $ROOT/a/b
+find_sorted path_d.txt           $ROOT/d
+find_sorted path_a_b_d.txt       $ROOT/a/b $ROOT/d
+find_sorted type_f.txt           $ROOT -type f
+find_sorted type_d.txt           $ROOT -type d
+find_sorted type_l.txt           $ROOT -type l
+find_sorted type_f_l.txt         $ROOT -type f,l
+find_sorted name_csv.txt         $ROOT -name "*.csv"
+find_sorted name_csv_mp3.txt     $ROOT \( -name "*.csv" -o -name "*.mp3" \)
+find_sorted name_a.txt           $ROOT -name "a"
+find_sorted name_class.txt       $ROOT -name "[ab].*"
+find_sorted name_question.txt    $ROOT -name "?.txt"
+find_sorted iname_txt.txt        $ROOT -iname "*.TXT"
+find_sorted type_f_name_a.txt    $ROOT -type f -name "a*"
+find_sorted type_l_name_csv.txt  $ROOT -type l -name "*.csv"
+find_sorted maxdepth1.txt        $ROOT -maxdepth 1
+find_sorted mindepth3.txt        $ROOT -mindepth 3
+find_sorted depth2.txt           $ROOT -mindepth 2 -maxdepth 2
+find_sorted maxdepth0.txt        $ROOT -maxdepth 0
+find_sorted size0.txt            $ROOT -type f -size 0
+find_sorted size_plus2.txt       $ROOT -type f -size +2
+find_sorted size_minus5c.txt     $ROOT -type f -size -5c
+find_sorted size_1k.txt          $ROOT -type f -size 1k
+find_sorted size_plus1k.txt      $ROOT -type f -size +1k
+find_sorted size_minus1M.txt     $ROOT -type f -size -1M
@@ -0,0 +1,712 @@
+use std::{
+    borrow::Cow,
+    cmp::Ordering,
+    env,
+    error::Error,
+    ffi::{OsStr, OsString},
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    process::{Command, ExitCode},
+    time::{Duration, SystemTime},
+};
+
+use clap::{Parser, ValueEnum};
+use regex::{Regex, RegexBuilder};
+use walkdir::{DirEntry, WalkDir};
+
+type MyResult<T> = Result<T, Box<dyn Error>>;
+
+/// Options that `find` spells with a single dash, accepted here as well as with two.
+const FIND_OPTIONS: &[&str] = &[
+    "-name",
+    "-iname",
+    "-type",
+    "-maxdepth",
+    "-mindepth",
+    "-size",
+    "-mtime",
+    "-print0",
+    "-exec",
+];
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Start of the characters standing in for bytes that are not UTF-8, in Supplementary Private
+/// Use Area-B.
+const INVALID_BYTE_CHARS: u32 = 0x10_FF00;
+
+/// Bytes of arguments given to one `-exec ... {} +` command: the default command buffer of GNU
+/// xargs, well within `ARG_MAX` on common systems.
+const BATCH_BYTES: usize = 128 * 1024;
+
+#[derive(Debug)]
+pub struct Config {
+    paths: Vec<String>,
+    names: Vec<Regex>,
+    entry_types: Vec<EntryType>,
+    min_depth: Option<usize>,
+    max_depth: Option<usize>,
+    size: Option<SizeTest>,
+    mtime: Option<NumberTest>,
+    print0: bool,
+    exec: Option<Exec>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+enum EntryType {
+    #[value(name = "f")]
+    File,
+    #[value(name = "d")]
+    Dir,
+    #[value(name = "l")]
+    Link,
+}
+
+/// `N`, `+N` or `-N`: equal to, greater than or less than `N`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct NumberTest {
+    ordering: Ordering,
+    value: u64,
+}
+
+impl NumberTest {
+    fn matches(&self, value: u64) -> bool {
+        value.cmp(&self.value) == self.ordering
+    }
+}
+
+/// `-size`: the file size is rounded up to whole units before it is compared, as find does.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct SizeTest {
+    test: NumberTest,
+    unit: u64,
+}
+
+#[derive(Debug, PartialEq)]
+struct Exec {
+    command: Vec<String>,
+    /// `{} +`: run the command once with all paths instead of once per path.
+    batch: bool,
+}
+
+#[derive(Parser, Debug)]
+#[command(version)]
+#[command(about = "Rust find")]
+struct Cli {
+    /// Search paths.
+    #[arg(value_name = "PATH", default_value = ".")]
+    paths: Vec<String>,
+
+    /// Base name matches the glob PATTERN.
+    #[arg(long("name"), value_name = "PATTERN")]
+    names: Vec<String>,
+
+    /// Like --name, but ignoring case.
+    #[arg(long("iname"), value_name = "PATTERN")]
+    inames: Vec<String>,
+
+    /// Entry type: f, d or l.
+    #[arg(long("type"), value_name = "TYPE", value_enum, value_delimiter = ',')]
+    entry_types: Vec<EntryType>,
+
+    /// Descend at most N levels below the paths.
+    #[arg(long("maxdepth"), value_name = "N")]
+    max_depth: Option<usize>,
+
+    /// Ignore entries less than N levels below the paths.
+    #[arg(long("mindepth"), value_name = "N")]
+    min_depth: Option<usize>,
+
+    /// Size is N units of c, w, b (the default), k, M or G, or more with +N or less with -N.
+    #[arg(
+        long,
+        value_name = "[+-]N[cwbkMG]",
+        value_parser = parse_size,
+        allow_hyphen_values = true
+    )]
+    size: Option<SizeTest>,
+
+    /// Modified N days ago, or more with +N or less with -N.
+    #[arg(
+        long,
+        value_name = "[+-]N",
+        value_parser = parse_number_test,
+        allow_hyphen_values = true
+    )]
+    mtime: Option<NumberTest>,
+
+    /// Print paths followed by NUL instead of newline.
+    #[arg(long)]
+    print0: bool,
+
+    /// Run COMMAND with {} replaced by the path, up to ';', or with all paths if it ends in '{} +'.
+    #[arg(
+        long,
+        value_name = "COMMAND",
+        num_args = 1..,
+        value_terminator = ";",
+        allow_hyphen_values = true
+    )]
+    exec: Option<Vec<String>>,
+}
+
+pub fn get_args() -> MyResult<Config> {
+    get_args_from(env::args_os())
+}
+
+pub fn get_args_from<I, T>(args: I) -> MyResult<Config>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<OsString> + Clone,
+{
+    let args = Cli::parse_from(find_style_args(args));
+
+    let mut names = vec![];
+    for (pattern, ignore_case) in args
+        .names
+        .iter()
+        .map(|name| (name, false))
+        .chain(args.inames.iter().map(|name| (name, true)))
+    {
+        let name = RegexBuilder::new(&glob_to_regex(pattern))
+            .case_insensitive(ignore_case)
+            .build()
+            .map_err(|_| format!("Invalid pattern \"{}\"", pattern))?;
+        names.push(name);
+    }
+
+    let exec = match args.exec {
+        None => None,
+        Some(mut command) => {
+            let batch = command.ends_with(&["{}".to_string(), "+".to_string()]);
+            if batch {
+                command.truncate(command.len() - 2);
+            }
+            if command.is_empty() {
+                return Err(From::from("missing argument to `-exec'"));
+            }
+            Some(Exec { command, batch })
+        }
+    };
+
+    Ok(Config {
+        paths: args.paths,
+        names,
+        entry_types: args.entry_types,
+        min_depth: args.min_depth,
+        max_depth: args.max_depth,
+        size: args.size,
+        mtime: args.mtime,
+        print0: args.print0,
+        exec,
+    })
+}
+
+/// Walk the paths. As with GNU find, the exit status is 1 if a directory could not be read or a
+/// `-exec ... {} +` command failed, after the walk is complete.
+pub fn run(config: Config) -> MyResult<ExitCode> {
+    let now = SystemTime::now();
+    let mut failed = false;
+    let mut out = BufWriter::new(io::stdout().lock());
+    // -exec がある場合、-print0 を指定しなければパスは出力しない
+    let print = config.print0 || config.exec.is_none();
+    let mut batch = config
+        .exec
+        .as_ref()
+        .filter(|exec| exec.batch)
+        .map(|exec| Batch::new(exec, BATCH_BYTES));
+
+    for path in &config.paths {
+        let mut walk_dir = WalkDir::new(path).sort_by_file_name();
+        if let Some(min_depth) = config.min_depth {
+            walk_dir = walk_dir.min_depth(min_depth);
+        }
+        if let Some(max_depth) = config.max_depth {
+            walk_dir = walk_dir.max_depth(max_depth);
+        }
+
+        for entry in walk_dir {
+            let entry = match entry {
+                Err(err) => {
+                    eprintln!("{}", err);
+                    failed = true;
+                    continue;
+                }
+                Ok(entry) => entry,
+            };
+            if !matches(&config, &entry, now) {
+                continue;
+            }
+
+            if print {
+                out.write_all(entry.path().as_os_str().as_encoded_bytes())?;
+                out.write_all(if config.print0 { b"\0" } else { b"\n" })?;
+            }
+            match (&mut batch, &config.exec) {
+                (Some(batch), _) => batch.push(entry.path(), &mut out)?,
+                (None, Some(exec)) => {
+                    // コマンドの出力がパスより先に出ないようにする。
+                    // find と同じく、; で区切ったコマンドの失敗は終了ステータスに影響しない
+                    out.flush()?;
+                    let args = exec.command[1..]
+                        .iter()
+                        .map(|arg| replace_braces(arg, entry.path()));
+                    run_command(&exec.command[0], args);
+                }
+                (None, None) => {}
+            }
+        }
+    }
+    out.flush()?;
+
+    if let Some(batch) = &mut batch {
+        batch.run();
+        failed |= batch.failed;
+    }
+    Ok(if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    })
+}
+
+/// Paths collected for `-exec ... {} +`. The command runs whenever the next path would take its
+/// arguments over `limit` bytes, and once more for the rest at the end.
+struct Batch<'a> {
+    exec: &'a Exec,
+    paths: Vec<PathBuf>,
+    /// Size of the command itself, without any paths.
+    command_size: usize,
+    size: usize,
+    limit: usize,
+    /// Whether any run of the command failed.
+    failed: bool,
+}
+
+impl<'a> Batch<'a> {
+    fn new(exec: &'a Exec, limit: usize) -> Self {
+        let command_size = exec.command.iter().map(|arg| arg_size(arg.as_ref())).sum();
+        Batch {
+            exec,
+            paths: vec![],
+            command_size,
+            size: command_size,
+            limit,
+            failed: false,
+        }
+    }
+
+    /// Add `path`, first running the command for the paths so far if it would not fit. `out` is
+    /// flushed before that so that printed paths come first.
+    fn push(&mut self, path: &Path, out: &mut impl Write) -> io::Result<()> {
+        let size = arg_size(path.as_os_str());
+        if !self.paths.is_empty() && self.size + size > self.limit {
+            out.flush()?;
+            self.run();
+        }
+        self.paths.push(path.to_path_buf());
+        self.size += size;
+        Ok(())
+    }
+
+    /// Run the command with the paths collected so far, if there are any.
+    fn run(&mut self) {
+        if self.paths.is_empty() {
+            return;
+        }
+        let args = self.exec.command[1..]
+            .iter()
+            .map(OsString::from)
+            .chain(self.paths.drain(..).map(OsString::from));
+        if !run_command(&self.exec.command[0], args) {
+            self.failed = true;
+        }
+        self.size = self.command_size;
+    }
+}
+
+/// Bytes an argument takes from `ARG_MAX`: the string, its NUL and its `argv` pointer.
+fn arg_size(arg: &OsStr) -> usize {
+    arg.len() + 1 + size_of::<usize>()
+}
+
+/// Turn find's single-dash options such as `-name` into `--name`, leaving the command of
+/// `-exec` as it is. A command ending in `{} +` is followed by a `;` so that clap stops there too.
+fn find_style_args<I, T>(args: I) -> Vec<OsString>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<OsString> + Clone,
+{
+    let mut in_exec = false;
+    let mut converted = vec![];
+    for arg in args {
+        let arg: OsString = arg.into();
+        if in_exec {
+            if arg == "+" && converted.last().is_some_and(|last| last == "{}") {
+                converted.extend([arg, ";".into()]);
+                in_exec = false;
+                continue;
+            }
+            in_exec = arg != ";";
+        } else if let Some(option) = arg.to_str().filter(|arg| FIND_OPTIONS.contains(arg)) {
+            in_exec = option == "-exec";
+            converted.push(format!("-{}", option).into());
+            continue;
+        } else {
+            in_exec = arg == "--exec";
+        }
+        converted.push(arg);
+    }
+    converted
+}
+
+fn matches(config: &Config, entry: &DirEntry, now: SystemTime) -> bool {
+    let file_type = entry.file_type();
+    let type_matches = config.entry_types.is_empty()
+        || config
+            .entry_types
+            .iter()
+            .any(|entry_type| match entry_type {
+                EntryType::File => file_type.is_file(),
+                EntryType::Dir => file_type.is_dir(),
+                EntryType::Link => file_type.is_symlink(),
+            });
+    let name_matches = config.names.is_empty()
+        || config
+            .names
+            .iter()
+            .any(|name| name.is_match(&glob_subject(entry.file_name())));
+    if !type_matches || !name_matches {
+        return false;
+    }
+    if config.size.is_none() && config.mtime.is_none() {
+        return true;
+    }
+
+    // シンボリックリンクはリンク先ではなくリンク自体の情報で判定する
+    let meta = match entry.metadata() {
+        Err(err) => {
+            eprintln!("{}", err);
+            return false;
+        }
+        Ok(meta) => meta,
+    };
+    if let Some(size) = config.size
+        && !size.test.matches(meta.len().div_ceil(size.unit))
+    {
+        return false;
+    }
+    if let Some(mtime) = config.mtime {
+        // 未来の時刻は 0 日前として扱う
+        let age = meta
+            .modified()
+            .ok()
+            .and_then(|modified| now.duration_since(modified).ok())
+            .unwrap_or(Duration::ZERO);
+        if !mtime.matches(age.as_secs() / SECONDS_PER_DAY) {
+            return false;
+        }
+    }
+    true
+}
+
+/// `arg` with each `{}` replaced by `path`, which is copied as it is even if it is not UTF-8.
+fn replace_braces(arg: &str, path: &Path) -> OsString {
+    let mut replaced = OsString::new();
+    for (i, part) in arg.split("{}").enumerate() {
+        if i > 0 {
+            replaced.push(path);
+        }
+        replaced.push(part);
+    }
+    replaced
+}
+
+/// `name` as matched against `-name` globs. Each byte that is not UTF-8 becomes a character of
+/// its own from a private use area, so that `?` matches it and distinct bytes stay distinct.
+fn glob_subject(name: &OsStr) -> Cow<'_, str> {
+    let bytes = name.as_encoded_bytes();
+    if let Ok(name) = str::from_utf8(bytes) {
+        return Cow::Borrowed(name);
+    }
+    let mut subject = String::new();
+    for chunk in bytes.utf8_chunks() {
+        subject.push_str(chunk.valid());
+        subject.extend(
+            chunk
+                .invalid()
+                .iter()
+                .filter_map(|&b| char::from_u32(INVALID_BYTE_CHARS + u32::from(b))),
+        );
+    }
+    Cow::Owned(subject)
+}
+
+/// Run `program` and wait for it. Returns whether it exited successfully.
+fn run_command(program: &str, args: impl Iterator<Item = OsString>) -> bool {
+    match Command::new(program).args(args).status() {
+        Err(err) => {
+            eprintln!("{}: {}", program, err);
+            false
+        }
+        Ok(status) => status.success(),
+    }
+}
+
+/// Translate a shell glob into an anchored regex. `*` and `?` match any character, including a
+/// leading dot, and `[...]` is a character class that `!` or `^` negates.
+fn glob_to_regex(glob: &str) -> String {
+    let mut regex = String::from("(?s)^");
+    let chars: Vec<char> = glob.chars().collect();
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '*' => regex.push_str(".*"),
+            '?' => regex.push('.'),
+            '\\' if i + 1 < chars.len() => {
+                i += 1;
+                regex.push_str(&regex::escape(&chars[i].to_string()));
+            }
+            '[' => match class_end(&chars, i) {
+                Some(end) => {
+                    regex.push('[');
+                    let mut j = i + 1;
+                    if matches!(chars[j], '!' | '^') {
+                        regex.push('^');
+                        j += 1;
+                    }
+                    for c in &chars[j..end] {
+                        // クラスの中で特別な意味を持つ文字はエスケープする
+                        if matches!(c, '\\' | '[' | ']' | '&' | '~') {
+                            regex.push('\\');
+                        }
+                        regex.push(*c);
+                    }
+                    regex.push(']');
+                    i = end;
+                }
+                None => regex.push_str(r"\["),
+            },
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+        i += 1;
+    }
+    regex.push('$');
+    regex
+}
+
+/// Index of the `]` closing the class opened at `start`. A `]` right after `[` or `[!` is part
+/// of the class.
+fn class_end(chars: &[char], start: usize) -> Option<usize> {
+    let mut i = start + 1;
+    if matches!(chars.get(i), Some('!' | '^')) {
+        i += 1;
+    }
+    if chars.get(i) == Some(&']') {
+        i += 1;
+    }
+    chars[i.min(chars.len())..]
+        .iter()
+        .position(|c| *c == ']')
+        .map(|offset| i + offset)
+}
+
+fn parse_number_test(value: &str) -> Result<NumberTest, String> {
+    let (ordering, number) = match value.as_bytes().first() {
+        Some(b'+') => (Ordering::Greater, &value[1..]),
+        Some(b'-') => (Ordering::Less, &value[1..]),
+        _ => (Ordering::Equal, value),
+    };
+    // u64 の parse は先頭の + を受け付けるため、数字だけかを先に確認する
+    if number.is_empty() || !number.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(format!("invalid argument `{}'", value));
+    }
+    let value = number
+        .parse()
+        .map_err(|_| format!("invalid argument `{}'", value))?;
+    Ok(NumberTest { ordering, value })
+}
+
+fn parse_size(value: &str) -> Result<SizeTest, String> {
+    let (number, unit) = match value.chars().last() {
+        Some('c') => (&value[..value.len() - 1], 1),
+        Some('w') => (&value[..value.len() - 1], 2),
+        Some('b') => (&value[..value.len() - 1], 512),
+        Some('k') => (&value[..value.len() - 1], 1024),
+        Some('M') => (&value[..value.len() - 1], 1024 * 1024),
+        Some('G') => (&value[..value.len() - 1], 1024 * 1024 * 1024),
+        _ => (value, 512),
+    };
+    let test = parse_number_test(number).map_err(|_| format!("invalid argument `{}'", value))?;
+    Ok(SizeTest { test, unit })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_glob_to_regex() {
+        let glob =
+            |glob: &str, name: &str| Regex::new(&glob_to_regex(glob)).unwrap().is_match(name);
+
+        assert!(glob("*.csv", "b.csv"));
+        assert!(glob("*.csv", ".csv"));
+        assert!(!glob("*.csv", "b.csv.bak"));
+        assert!(!glob("*.csv", "b_csv"));
+        assert!(glob("?.txt", "a.txt"));
+        assert!(!glob("?.txt", "ab.txt"));
+        assert!(glob("[ab].*", "b.csv"));
+        assert!(!glob("[!ab].*", "b.csv"));
+        assert!(glob("[^ab].*", "d.txt"));
+        assert!(glob("[]x]", "]"));
+        assert!(glob("[a-c]", "b"));
+        assert!(glob("[", "["));
+        assert!(glob(r"\*", "*"));
+        assert!(!glob(r"\*", "a"));
+        assert!(glob("a+(b)", "a+(b)"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_non_utf8_names() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let path = Path::new(OsStr::from_bytes(b"d/caf\xe9"));
+        assert_eq!(replace_braces("{}", path).as_bytes(), b"d/caf\xe9");
+        assert_eq!(
+            replace_braces("<{}>{}", path).as_bytes(),
+            b"<d/caf\xe9>d/caf\xe9"
+        );
+        assert_eq!(replace_braces("x", path).as_bytes(), b"x");
+
+        let glob = |glob: &str, name: &[u8]| {
+            Regex::new(&glob_to_regex(glob))
+                .unwrap()
+                .is_match(&glob_subject(OsStr::from_bytes(name)))
+        };
+        assert!(glob("caf?", b"caf\xe9"));
+        assert!(glob("caf*", b"caf\xe9\xe8"));
+        assert!(!glob("caf?", b"caf\xe9\xe8"));
+        assert!(!glob("caf\u{FFFD}", b"caf\xe9"));
+        assert!(glob("caf?", "café".as_bytes()));
+        assert!(!glob("caf??", "café".as_bytes()));
+    }
+
+    #[test]
+    fn test_parse_number_test() {
+        let test = |ordering, value| Ok(NumberTest { ordering, value });
+        assert_eq!(parse_number_test("3"), test(Ordering::Equal, 3));
+        assert_eq!(parse_number_test("+3"), test(Ordering::Greater, 3));
+        assert_eq!(parse_number_test("-0"), test(Ordering::Less, 0));
+        for bad in ["", "+", "a", "1.5", "++1", "+-1"] {
+            assert_eq!(
+                parse_number_test(bad),
+                Err(format!("invalid argument `{}'", bad))
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_size() {
+        let size = parse_size("+2k").unwrap();
+        assert_eq!(size.unit, 1024);
+        assert!(size.test.matches(3));
+        assert!(!size.test.matches(2));
+
+        assert_eq!(parse_size("10").unwrap().unit, 512);
+        assert_eq!(parse_size("-1M").unwrap().unit, 1024 * 1024);
+        assert_eq!(parse_size("4c").unwrap().unit, 1);
+        assert!(parse_size("k").is_err());
+        assert!(parse_size("1x").is_err());
+    }
+
+    #[test]
+    fn test_find_style_args() {
+        let args = find_style_args([
+            "findr", "-name", "*.txt", "-type", "f", "-exec", "grep", "-name", "{}", ";", "-print0",
+        ]);
+        assert_eq!(
+            args,
+            [
+                "findr", "--name", "*.txt", "--type", "f", "--exec", "grep", "-name", "{}", ";",
+                "--print0",
+            ]
+        );
+
+        // {} + でもコマンドは終わるが、+ だけや {} だけでは終わらない
+        let args = find_style_args([
+            "findr", "-exec", "echo", "+", "{}", "x", "{}", "+", "-type", "f",
+        ]);
+        assert_eq!(
+            args,
+            [
+                "findr", "--exec", "echo", "+", "{}", "x", "{}", "+", ";", "--type", "f",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_batch() {
+        let exec = Exec {
+            command: vec!["true".to_string()],
+            batch: true,
+        };
+        let path_size = arg_size(OsStr::new("a"));
+        let mut batch = Batch::new(&exec, arg_size(OsStr::new("true")) + 2 * path_size);
+        let mut out = Vec::new();
+
+        // 2 つ分の大きさに収まらなくなったところで実行する
+        for (path, pending) in [("a", 1), ("b", 2), ("c", 1), ("d", 2), ("e", 1)] {
+            batch.push(Path::new(path), &mut out).unwrap();
+            assert_eq!(batch.paths.len(), pending, "{}", path);
+        }
+        assert_eq!(batch.paths, [PathBuf::from("e")]);
+        batch.run();
+        assert!(batch.paths.is_empty());
+        assert!(!batch.failed);
+
+        // 1 つでも収まらないパスはそのまま渡す
+        let mut batch = Batch::new(&exec, 0);
+        batch.push(Path::new("a"), &mut out).unwrap();
+        assert_eq!(batch.paths.len(), 1);
+        batch.push(Path::new("b"), &mut out).unwrap();
+        assert_eq!(batch.paths.len(), 1);
+
+        let exec = Exec {
+            command: vec!["false".to_string()],
+            batch: true,
+        };
+        let mut batch = Batch::new(&exec, BATCH_BYTES);
+        batch.push(Path::new("a"), &mut out).unwrap();
+        batch.run();
+        assert!(batch.failed);
+    }
+
+    #[test]
+    fn test_get_args_from_exec() {
+        let exec = |args: &[&str]| {
+            get_args_from(["findr"].iter().chain(args))
+                .map(|config| config.exec)
+                .map_err(|err| err.to_string())
+        };
+        assert_eq!(
+            exec(&["-exec", "echo", "{}", "+", "-type", "f"]),
+            Ok(Some(Exec {
+                command: vec!["echo".to_string()],
+                batch: true,
+            }))
+        );
+        assert_eq!(
+            exec(&["-exec", "echo", "{}", ";"]),
+            Ok(Some(Exec {
+                command: vec!["echo".to_string(), "{}".to_string()],
+                batch: false,
+            }))
+        );
+        assert_eq!(
+            exec(&["-exec", "{}", "+"]),
+            Err("missing argument to `-exec'".to_string())
+        );
+    }
+}
@@ -0,0 +1,321 @@
+use anyhow::Result;
+use assert_cmd::Command;
+use predicates::prelude::*;
+use pretty_assertions::assert_eq;
+use rand::{distr::Alphanumeric, Rng};
+use std::{
+    fs::{self, File},
+    time::{Duration, SystemTime},
+};
+
+const PRG: &str = "num10_findr";
+const ROOT: &str = "tests/inputs";
+
+// --------------------------------------------------
+fn gen_bad_file() -> String {
+    loop {
+        let filename: String = rand::rng()
+            .sample_iter(&Alphanumeric)
+            .take(7)
+            .map(char::from)
+            .collect();
+
+        if fs::metadata(&filename).is_err() {
+            return filename;
+        }
+    }
+}
+
+// --------------------------------------------------
+#[test]
+fn skips_bad_dir() -> Result<()> {
+    let bad = gen_bad_file();
+    let expected = format!("{bad}: .* [(]os error 2[)]");
+    Command::cargo_bin(PRG)?
+        .args([&bad, "tests/inputs/f"])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::is_match(expected)?)
+        .stdout("tests/inputs/f\ntests/inputs/f/f.txt\n");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_type() -> Result<()> {
+    Command::cargo_bin(PRG)?
+        .args(["-type", "x"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid value 'x'"));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_size_and_mtime() -> Result<()> {
+    for (option, value) in [("-size", "1x"), ("-size", "+k"), ("-mtime", "a")] {
+        Command::cargo_bin(PRG)?
+            .args([option, value])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(format!(
+                "invalid argument `{value}'"
+            )));
+    }
+    Ok(())
+}
+
+// --------------------------------------------------
+fn run(args: &[&str], expected_file: &str) -> Result<()> {
+    let expected = fs::read_to_string(expected_file)?;
+    let output = Command::cargo_bin(PRG)?.args(args).output()?;
+    assert!(output.status.success());
+
+    // 期待値は GNU find の出力をソートしたもの
+    let stdout = String::from_utf8(output.stdout)?;
+    let mut lines: Vec<&str> = stdout.lines().collect();
+    lines.sort();
+    assert_eq!(lines, expected.lines().collect::<Vec<_>>(), "{args:?}");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn paths() -> Result<()> {
+    run(&[ROOT], "tests/expected/path1.txt")?;
+    run(&["tests/inputs/a"], "tests/expected/path_a.txt")?;
+    run(&["tests/inputs/a/b"], "tests/expected/path_a_b.txt")?;
+    run(&["tests/inputs/d"], "tests/expected/path_d.txt")?;
+    run(
+        &["tests/inputs/a/b", "tests/inputs/d"],
+        "tests/expected/path_a_b_d.txt",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn expected() -> Result<()> {
+    let cases: &[(&[&str], &str)] = &[
+        (&["-type", "f"], "type_f"),
+        (&["-type", "d"], "type_d"),
+        (&["--type", "l"], "type_l"),
+        (&["-type", "f,l"], "type_f_l"),
+        (&["-type", "f", "-type", "l"], "type_f_l"),
+        (&["-name", "*.csv"], "name_csv"),
+        (&["-name", "*.csv", "-name", "*.mp3"], "name_csv_mp3"),
+        (&["--name", "a"], "name_a"),
+        (&["-name", "[ab].*"], "name_class"),
+        (&["-name", "?.txt"], "name_question"),
+        (&["-iname", "*.TXT"], "iname_txt"),
+        (&["-type", "f", "-name", "a*"], "type_f_name_a"),
+        (&["-type", "l", "-name", "*.csv"], "type_l_name_csv"),
+        (&["-maxdepth", "1"], "maxdepth1"),
+        (&["-mindepth", "3"], "mindepth3"),
+        (&["-mindepth", "2", "--maxdepth", "2"], "depth2"),
+        (&["-maxdepth", "0"], "maxdepth0"),
+        (&["-type", "f", "-size", "0"], "size0"),
+        (&["-type", "f", "-size", "+2"], "size_plus2"),
+        (&["-type", "f", "-size", "-5c"], "size_minus5c"),
+        (&["-type", "f", "-size", "1k"], "size_1k"),
+        (&["-type", "f", "--size", "+1k"], "size_plus1k"),
+        (&["-type", "f", "-size", "-1M"], "size_minus1M"),
+    ];
+    for (args, name) in cases {
+        run(
+            &[&[ROOT], *args].concat(),
+            &format!("tests/expected/{name}.txt"),
+        )?;
+    }
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn mtime() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let now = SystemTime::now();
+    for (name, days) in [("today", 0), ("yesterday", 1), ("old", 10)] {
+        let file = File::create(dir.path().join(name))?;
+        file.set_modified(now - Duration::from_secs(days * 86400 + 60))?;
+    }
+    let dir = dir.path().display().to_string();
+
+    let cases = [
+        ("0", "today"),
+        ("1", "yesterday"),
+        ("+1", "old"),
+        ("-2", "today\nyesterday"),
+        ("+0", "old\nyesterday"),
+    ];
+    for (mtime, expected) in cases {
+        let expected: String = expected
+            .lines()
+            .map(|name| format!("{dir}/{name}\n"))
+            .collect();
+        Command::cargo_bin(PRG)?
+            .args([&dir, "-type", "f", "-mtime", mtime])
+            .assert()
+            .success()
+            .stdout(expected);
+    }
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn print0() -> Result<()> {
+    Command::cargo_bin(PRG)?
+        .args([ROOT, "-name", "*.txt", "-print0"])
+        .assert()
+        .success()
+        .stdout(
+            "tests/inputs/a/a.txt\0tests/inputs/d/d.txt\0tests/inputs/f/f.txt\0",
+        );
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn exec_each() -> Result<()> {
+    // -exec の後ろの -name はコマンドの引数として渡す
+    Command::cargo_bin(PRG)?
+        .args([
+            ROOT, "-name", "*.txt", "-exec", "echo", "-name", "<{}>", ";",
+        ])
+        .assert()
+        .success()
+        .stdout(
+            "-name <tests/inputs/a/a.txt>\n\
+             -name <tests/inputs/d/d.txt>\n\
+             -name <tests/inputs/f/f.txt>\n",
+        );
+
+    // -print0 を指定すればパスも出力する
+    Command::cargo_bin(PRG)?
+        .args(["tests/inputs/f", "-type", "f", "-exec", "cat", "{}", ";"])
+        .arg("-print0")
+        .assert()
+        .success()
+        .stdout("tests/inputs/f/f.txt\0fox\n");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn exec_batch() -> Result<()> {
+    Command::cargo_bin(PRG)?
+        .args([ROOT, "-type", "f", "-name", "*.csv"])
+        .args(["-exec", "echo", "found:", "{}", "+"])
+        .assert()
+        .success()
+        .stdout("found: tests/inputs/a/b/b.csv tests/inputs/g.csv\n");
+
+    // {} + でコマンドは終わり、後ろの -type はシンボリックリンクの d/b.csv を除く
+    Command::cargo_bin(PRG)?
+        .args([ROOT, "-name", "*.csv"])
+        .args(["-exec", "echo", "found:", "{}", "+", "-type", "f"])
+        .assert()
+        .success()
+        .stdout("found: tests/inputs/a/b/b.csv tests/inputs/g.csv\n");
+
+    // 一致するものがなければコマンドは実行しない
+    Command::cargo_bin(PRG)?
+        .args([
+            ROOT, "-name", "nothing", "-exec", "echo", "found", "{}", "+",
+        ])
+        .assert()
+        .success()
+        .stdout("");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn exec_batch_splits_long_lists() -> Result<()> {
+    // 引数が長くなりすぎないよう、{} + のコマンドは何回かに分けて実行する
+    let dir = tempfile::tempdir()?;
+    let count = 1000;
+    for i in 0..count {
+        File::create(dir.path().join(format!("{i:04}-{}", "x".repeat(200))))?;
+    }
+    let output = Command::cargo_bin(PRG)?
+        .arg(dir.path())
+        .args(["-type", "f", "-exec", "echo", "{}", "+"])
+        .output()?;
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout)?;
+    assert!(stdout.lines().count() > 1);
+    assert_eq!(stdout.split_whitespace().count(), count);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[cfg(unix)]
+#[test]
+fn exec_non_utf8_path() -> Result<()> {
+    use std::os::unix::ffi::OsStrExt;
+
+    // UTF-8 でないファイル名もそのままコマンドに渡す
+    let dir = tempfile::tempdir()?;
+    let file = dir.path().join(std::ffi::OsStr::from_bytes(b"caf\xe9.txt"));
+    File::create(&file)?;
+    let output = Command::cargo_bin(PRG)?
+        .arg(dir.path())
+        .args(["-name", "caf?.txt", "-exec", "echo", "<{}>", ";"])
+        .output()?;
+    assert!(output.status.success());
+    assert_eq!(
+        output.stdout,
+        [b"<", file.as_os_str().as_bytes(), b">\n"].concat()
+    );
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_empty_exec() -> Result<()> {
+    Command::cargo_bin(PRG)?
+        .args([ROOT, "-exec", "{}", "+"])
+        .assert()
+        .failure()
+        .stderr("missing argument to `-exec'\n")
+        .stdout("");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn exec_bad_command() -> Result<()> {
+    let bad = gen_bad_file();
+    Command::cargo_bin(PRG)?
+        .args(["tests/inputs/f", "-type", "f", "-exec", &bad, "{}", ";"])
+        .assert()
+        .success()
+        .stderr(predicate::str::starts_with(format!("{bad}: ")))
+        .stdout("");
+
+    // {} + のコマンドが失敗した場合は 1 で終了する
+    Command::cargo_bin(PRG)?
+        .args(["tests/inputs/f", "-type", "f", "-exec", &bad, "{}", "+"])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::starts_with(format!("{bad}: ")));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn exec_failure() -> Result<()> {
+    // find と同じく、; のコマンドの失敗は終了ステータスに影響しない
+    Command::cargo_bin(PRG)?
+        .args([ROOT, "-exec", "false", "{}", ";"])
+        .assert()
+        .success();
+    Command::cargo_bin(PRG)?
+        .args([ROOT, "-exec", "false", "{}", "+"])
+        .assert()
+        .code(1);
+    Ok(())
+}
@@ -0,0 +1,44 @@
+#!/usr/bin/env bash
+
+# GNU coreutils の comm で期待値を生成する
+# GNU comm に -i はないため、その期待値はテストに直接書く
+export LC_ALL=C
+COMM="/usr/bin/comm"
+ROOT="tests/inputs"
+OUT_DIR="tests/expected"
+
+[[ ! -d "$OUT_DIR" ]] && mkdir -p "$OUT_DIR"
+rm -f "$OUT_DIR"/*
+
+EMPTY="$ROOT/empty.txt"
+FILE1="$ROOT/file1.txt"
+FILE2="$ROOT/file2.txt"
+BLANK="$ROOT/blank.txt"
+NOEOL="$ROOT/noeol.txt"
+UNSORTED="$ROOT/unsorted.txt"
+
+$COMM $FILE1 $FILE1                    > "$OUT_DIR/file1_file1.out"
+$COMM $FILE1 $FILE2                    > "$OUT_DIR/file1_file2.out"
+$COMM $FILE1 $EMPTY                    > "$OUT_DIR/file1_empty.out"
+$COMM $EMPTY $FILE2                    > "$OUT_DIR/empty_file2.out"
+$COMM -1 $FILE1 $FILE2                 > "$OUT_DIR/file1_file2.1.out"
+$COMM -2 $FILE1 $FILE2                 > "$OUT_DIR/file1_file2.2.out"
+$COMM -3 $FILE1 $FILE2                 > "$OUT_DIR/file1_file2.3.out"
+$COMM -12 $FILE1 $FILE2                > "$OUT_DIR/file1_file2.12.out"
+$COMM -23 $FILE1 $FILE2                > "$OUT_DIR/file1_file2.23.out"
+$COMM -13 $FILE1 $FILE2                > "$OUT_DIR/file1_file2.13.out"
+$COMM -123 $FILE1 $FILE2               > "$OUT_DIR/file1_file2.123.out"
+$COMM --output-delimiter=:: $FILE1 $FILE2 > "$OUT_DIR/file1_file2.delim.out"
+$COMM --output-delimiter= $FILE1 $FILE2 > "$OUT_DIR/file1_file2.nul.out"
+$COMM --output-delimiter=:: -2 $FILE1 $FILE2 > "$OUT_DIR/file1_file2.delim.2.out"
+$COMM --total $FILE1 $FILE2            > "$OUT_DIR/file1_file2.total.out"
+$COMM --total -123 --output-delimiter=, $FILE1 $FILE2 > "$OUT_DIR/file1_file2.total.123.out"
+$COMM $BLANK $FILE2                    > "$OUT_DIR/blank_file2.out"
+$COMM $NOEOL $FILE1                    > "$OUT_DIR/noeol_file1.out"
+
+# 並び順の誤りは標準エラー出力に出るため、標準出力だけを期待値にする
+$COMM $FILE1 $UNSORTED                 > "$OUT_DIR/file1_unsorted.out" 2>/dev/null
+$COMM --check-order $FILE1 $UNSORTED   > "$OUT_DIR/file1_unsorted.check.out" 2>/dev/null
+$COMM $UNSORTED $UNSORTED              > "$OUT_DIR/unsorted_unsorted.out"
+$COMM --check-order $UNSORTED $UNSORTED > "$OUT_DIR/unsorted_unsorted.check.out" 2>/dev/null
+$COMM --nocheck-order $FILE1 $UNSORTED > "$OUT_DIR/file1_unsorted.nocheck.out"
@@ -0,0 +1,370 @@
+use std::{
+    cmp::Ordering,
+    env,
+    error::Error,
+    ffi::OsString,
+    fs::File,
+    io::{self, BufRead, BufReader, BufWriter, Write},
+    mem,
+};
+
+use clap::Parser;
+
+type MyResult<T> = Result<T, Box<dyn Error>>;
+
+#[derive(Debug)]
+pub struct Config {
+    file1: String,
+    file2: String,
+    show_col1: bool,
+    show_col2: bool,
+    show_col3: bool,
+    insensitive: bool,
+    delimiter: Vec<u8>,
+    order_check: OrderCheck,
+    total: bool,
+}
+
+/// When unsorted input is reported, as chosen with `--check-order` and `--nocheck-order`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum OrderCheck {
+    /// Warn once a line has been found in only one file, and fail at the end.
+    Default,
+    /// Fail at the first unsorted line.
+    Check,
+    Off,
+}
+
+#[derive(Parser, Debug)]
+#[command(version)]
+#[command(about = "Rust comm")]
+struct Cli {
+    /// Input file 1.
+    file1: String,
+
+    /// Input file 2.
+    file2: String,
+
+    /// Suppress printing of column 1.
+    #[arg(short('1'))]
+    suppress_col1: bool,
+
+    /// Suppress printing of column 2.
+    #[arg(short('2'))]
+    suppress_col2: bool,
+
+    /// Suppress printing of column 3.
+    #[arg(short('3'))]
+    suppress_col3: bool,
+
+    /// Case-insensitive comparison of lines.
+    #[arg(short)]
+    insensitive: bool,
+
+    /// Separate columns with STR; an empty STR means NUL.
+    #[arg(
+        long,
+        value_name = "STR",
+        default_value = "\t",
+        hide_default_value = true
+    )]
+    output_delimiter: String,
+
+    /// Check that the input is correctly sorted, even if all input lines are pairable.
+    #[arg(long, overrides_with = "nocheck_order")]
+    check_order: bool,
+
+    /// Do not check that the input is correctly sorted.
+    #[arg(long, overrides_with = "check_order")]
+    nocheck_order: bool,
+
+    /// Output a summary.
+    #[arg(long)]
+    total: bool,
+}
+
+pub fn get_args() -> MyResult<Config> {
+    get_args_from(env::args_os())
+}
+
+pub fn get_args_from<I, T>(args: I) -> MyResult<Config>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<OsString> + Clone,
+{
+    let args = Cli::parse_from(args);
+    let order_check = match (args.check_order, args.nocheck_order) {
+        (true, _) => OrderCheck::Check,
+        (_, true) => OrderCheck::Off,
+        _ => OrderCheck::Default,
+    };
+    let delimiter = match args.output_delimiter.as_str() {
+        "" => vec![b'\0'],
+        delimiter => delimiter.as_bytes().to_vec(),
+    };
+
+    Ok(Config {
+        file1: args.file1,
+        file2: args.file2,
+        show_col1: !args.suppress_col1,
+        show_col2: !args.suppress_col2,
+        show_col3: !args.suppress_col3,
+        insensitive: args.insensitive,
+        delimiter,
+        order_check,
+        total: args.total,
+    })
+}
+
+pub fn run(config: Config) -> MyResult<()> {
+    if config.file1 == "-" && config.file2 == "-" {
+        return Err(From::from(r#"Both input files cannot be STDIN ("-")"#));
+    }
+    let mut comm = Comm {
+        config: &config,
+        inputs: [
+            Input::new(open(&config.file1).map_err(|e| format!("{}: {}", config.file1, e))?),
+            Input::new(open(&config.file2).map_err(|e| format!("{}: {}", config.file2, e))?),
+        ],
+        out: BufWriter::new(io::stdout().lock()),
+        seen_unpairable: false,
+        warned: [false; 2],
+        totals: [0; 3],
+    };
+    let result = comm.compare();
+    comm.out.flush()?;
+    result
+}
+
+fn open(file_name: &str) -> MyResult<Box<dyn BufRead>> {
+    match file_name {
+        "-" => Ok(Box::new(BufReader::new(io::stdin()))),
+        _ => Ok(Box::new(BufReader::new(File::open(file_name)?))),
+    }
+}
+
+/// One of the files, read a line at a time so that neither is loaded fully.
+struct Input {
+    read_buf: Box<dyn BufRead>,
+    line: Vec<u8>,
+    previous: Vec<u8>,
+    eof: bool,
+}
+
+impl Input {
+    fn new(read_buf: Box<dyn BufRead>) -> Self {
+        Input {
+            read_buf,
+            line: Vec::new(),
+            previous: Vec::new(),
+            eof: false,
+        }
+    }
+
+    /// The current line without its newline, or `None` at EOF.
+    fn current(&self) -> Option<&[u8]> {
+        if self.eof {
+            return None;
+        }
+        Some(self.line.strip_suffix(b"\n").unwrap_or(&self.line))
+    }
+
+    fn advance(&mut self) -> io::Result<()> {
+        mem::swap(&mut self.previous, &mut self.line);
+        self.line.clear();
+        self.eof = self.read_buf.read_until(b'\n', &mut self.line)? == 0;
+        Ok(())
+    }
+}
+
+struct Comm<'a, W> {
+    config: &'a Config,
+    inputs: [Input; 2],
+    out: W,
+    seen_unpairable: bool,
+    warned: [bool; 2],
+    totals: [u64; 3],
+}
+
+impl<W: Write> Comm<'_, W> {
+    fn compare(&mut self) -> MyResult<()> {
+        for index in 0..2 {
+            self.inputs[index].advance()?;
+        }
+
+        loop {
+            // 出力した行のファイルだけを読み進める
+            let (column, advance): (usize, &[usize]) =
+                match (self.inputs[0].current(), self.inputs[1].current()) {
+                    (None, None) => break,
+                    (Some(_), None) => (0, &[0]),
+                    (None, Some(_)) => (1, &[1]),
+                    (Some(line1), Some(line2)) => match self.compare_lines(line1, line2) {
+                        Ordering::Less => (0, &[0]),
+                        Ordering::Greater => (1, &[1]),
+                        Ordering::Equal => (2, &[0, 1]),
+                    },
+                };
+            self.write_column(column)?;
+            for &index in advance {
+                self.inputs[index].advance()?;
+                self.check_order(index)?;
+            }
+        }
+
+        if self.config.total {
+            let delimiter = &self.config.delimiter;
+            for total in self.totals {
+                write!(self.out, "{}", total)?;
+                self.out.write_all(delimiter)?;
+            }
+            self.out.write_all(b"total\n")?;
+        }
+        if self.warned.contains(&true) {
+            return Err(From::from("input is not in sorted order"));
+        }
+        Ok(())
+    }
+
+    fn compare_lines(&self, line1: &[u8], line2: &[u8]) -> Ordering {
+        if self.config.insensitive {
+            // GNU の大文字小文字を無視した比較と同じく、大文字にそろえて比べる
+            let upper1 = line1.iter().map(u8::to_ascii_uppercase);
+            let upper2 = line2.iter().map(u8::to_ascii_uppercase);
+            upper1.cmp(upper2)
+        } else {
+            line1.cmp(line2)
+        }
+    }
+
+    /// Report a line of file `index + 1` that sorts before the one preceding it.
+    fn check_order(&mut self, index: usize) -> MyResult<()> {
+        let check = match self.config.order_check {
+            OrderCheck::Check => true,
+            OrderCheck::Default => self.seen_unpairable && !self.warned[index],
+            OrderCheck::Off => false,
+        };
+        let input = &self.inputs[index];
+        let Some(line) = input.current() else {
+            return Ok(());
+        };
+        let previous = input
+            .previous
+            .strip_suffix(b"\n")
+            .unwrap_or(&input.previous);
+        if !check || self.compare_lines(previous, line) != Ordering::Greater {
+            return Ok(());
+        }
+
+        let message = format!("file {} is not in sorted order", index + 1);
+        if self.config.order_check == OrderCheck::Check {
+            return Err(message.into());
+        }
+        eprintln!("{}", message);
+        self.warned[index] = true;
+        Ok(())
+    }
+
+    /// Print the current line in `column` (0 to 2), indented by the delimiters of the columns
+    /// shown before it. Lines common to both files are printed as they are in file 1.
+    fn write_column(&mut self, column: usize) -> io::Result<()> {
+        self.totals[column] += 1;
+        if column < 2 {
+            self.seen_unpairable = true;
+        }
+        let shown = [
+            self.config.show_col1,
+            self.config.show_col2,
+            self.config.show_col3,
+        ];
+        if !shown[column] {
+            return Ok(());
+        }
+        for _ in shown[..column].iter().filter(|shown| **shown) {
+            self.out.write_all(&self.config.delimiter)?;
+        }
+        let input = if column == 1 {
+            &self.inputs[1]
+        } else {
+            &self.inputs[0]
+        };
+        self.out.write_all(input.current().unwrap_or_default())?;
+        self.out.write_all(b"\n")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn comm(config: &Config, input1: &'static str, input2: &'static str) -> (String, bool) {
+        let mut comm = Comm {
+            config,
+            inputs: [
+                Input::new(Box::new(input1.as_bytes())),
+                Input::new(Box::new(input2.as_bytes())),
+            ],
+            out: Vec::new(),
+            seen_unpairable: false,
+            warned: [false; 2],
+            totals: [0; 3],
+        };
+        let result = comm.compare();
+        (String::from_utf8(comm.out).unwrap(), result.is_ok())
+    }
+
+    fn config(order_check: OrderCheck) -> Config {
+        Config {
+            file1: "-".to_string(),
+            file2: "-".to_string(),
+            show_col1: true,
+            show_col2: true,
+            show_col3: true,
+            insensitive: false,
+            delimiter: b"\t".to_vec(),
+            order_check,
+            total: false,
+        }
+    }
+
+    #[test]
+    fn test_compare() {
+        let config = config(OrderCheck::Default);
+        assert_eq!(
+            comm(&config, "a\nb\nc\n", "b\nd\n"),
+            ("a\n\t\tb\nc\n\td\n".to_string(), true)
+        );
+        // 最終行に改行がなくても同じ行として扱う
+        assert_eq!(
+            comm(&config, "a\nb", "b\n"),
+            ("a\n\t\tb\n".to_string(), true)
+        );
+        assert_eq!(comm(&config, "", ""), (String::new(), true));
+    }
+
+    #[test]
+    fn test_order_check() {
+        // すべての行が対になっていれば、既定では並び順を確かめない
+        let default = config(OrderCheck::Default);
+        assert_eq!(
+            comm(&default, "b\na\n", "b\na\n"),
+            ("\t\tb\n\t\ta\n".to_string(), true)
+        );
+        assert_eq!(
+            comm(&default, "x\nb\na\n", "b\na\n"),
+            ("\tb\n\ta\nx\nb\na\n".to_string(), false)
+        );
+
+        let check = config(OrderCheck::Check);
+        assert_eq!(
+            comm(&check, "b\na\n", "b\na\n"),
+            ("\t\tb\n".to_string(), false)
+        );
+
+        let off = config(OrderCheck::Off);
+        assert_eq!(
+            comm(&off, "x\nb\na\n", "b\na\n"),
+            ("\tb\n\ta\nx\nb\na\n".to_string(), true)
+        );
+    }
+}
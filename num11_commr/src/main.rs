@@ -0,0 +1,6 @@
+fn main() {
+    if let Err(e) = num11_commr::get_args().and_then(num11_commr::run) {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+}
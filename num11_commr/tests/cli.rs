@@ -0,0 +1,222 @@
+use anyhow::Result;
+use assert_cmd::Command;
+use predicates::prelude::*;
+use pretty_assertions::assert_eq;
+use rand::{distr::Alphanumeric, Rng};
+use std::fs;
+
+const PRG: &str = "num11_commr";
+const EMPTY: &str = "tests/inputs/empty.txt";
+const FILE1: &str = "tests/inputs/file1.txt";
+const FILE2: &str = "tests/inputs/file2.txt";
+const BLANK: &str = "tests/inputs/blank.txt";
+const NOEOL: &str = "tests/inputs/noeol.txt";
+const UNSORTED: &str = "tests/inputs/unsorted.txt";
+
+// --------------------------------------------------
+fn gen_bad_file() -> String {
+    loop {
+        let filename: String = rand::rng()
+            .sample_iter(&Alphanumeric)
+            .take(7)
+            .map(char::from)
+            .collect();
+
+        if fs::metadata(&filename).is_err() {
+            return filename;
+        }
+    }
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_no_args() -> Result<()> {
+    Command::cargo_bin(PRG)?
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Usage"));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_file1() -> Result<()> {
+    let bad = gen_bad_file();
+    let expected = format!("{bad}: .* [(]os error 2[)]");
+    Command::cargo_bin(PRG)?
+        .args([&bad, FILE1])
+        .assert()
+        .failure()
+        .stderr(predicate::str::is_match(expected)?);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_file2() -> Result<()> {
+    let bad = gen_bad_file();
+    let expected = format!("{bad}: .* [(]os error 2[)]");
+    Command::cargo_bin(PRG)?
+        .args([FILE1, &bad])
+        .assert()
+        .failure()
+        .stderr(predicate::str::is_match(expected)?);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_both_stdin() -> Result<()> {
+    let expected = r#"Both input files cannot be STDIN ("-")"#;
+    Command::cargo_bin(PRG)?
+        .args(["-", "-"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(expected));
+    Ok(())
+}
+
+// --------------------------------------------------
+fn run(args: &[&str], expected_file: &str) -> Result<()> {
+    let expected = fs::read(expected_file)?;
+    let output = Command::cargo_bin(PRG)?.args(args).output()?;
+    assert!(output.status.success());
+    assert_eq!(output.stdout, expected, "{args:?}");
+    Ok(())
+}
+
+// --------------------------------------------------
+fn run_stdin(
+    input_file: &str,
+    args: &[&str],
+    expected_file: &str,
+) -> Result<()> {
+    let input = fs::read(input_file)?;
+    let expected = fs::read(expected_file)?;
+    let output = Command::cargo_bin(PRG)?
+        .write_stdin(input)
+        .args(args)
+        .output()?;
+    assert!(output.status.success());
+    assert_eq!(output.stdout, expected, "{args:?} < {input_file}");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn expected() -> Result<()> {
+    let cases: &[(&[&str], &str)] = &[
+        (&[FILE1, FILE1], "file1_file1"),
+        (&[FILE1, FILE2], "file1_file2"),
+        (&[FILE1, EMPTY], "file1_empty"),
+        (&[EMPTY, FILE2], "empty_file2"),
+        (&["-1", FILE1, FILE2], "file1_file2.1"),
+        (&["-2", FILE1, FILE2], "file1_file2.2"),
+        (&["-3", FILE1, FILE2], "file1_file2.3"),
+        (&["-12", FILE1, FILE2], "file1_file2.12"),
+        (&["-2", "-3", FILE1, FILE2], "file1_file2.23"),
+        (&["-13", FILE1, FILE2], "file1_file2.13"),
+        (&["-123", FILE1, FILE2], "file1_file2.123"),
+        (
+            &["--output-delimiter=::", FILE1, FILE2],
+            "file1_file2.delim",
+        ),
+        (&["--output-delimiter=", FILE1, FILE2], "file1_file2.nul"),
+        (
+            &["--output-delimiter", "::", "-2", FILE1, FILE2],
+            "file1_file2.delim.2",
+        ),
+        (&["--total", FILE1, FILE2], "file1_file2.total"),
+        (
+            &["--total", "-123", "--output-delimiter=,", FILE1, FILE2],
+            "file1_file2.total.123",
+        ),
+        (&[BLANK, FILE2], "blank_file2"),
+        (&[NOEOL, FILE1], "noeol_file1"),
+        (&[UNSORTED, UNSORTED], "unsorted_unsorted"),
+        (
+            &["--nocheck-order", FILE1, UNSORTED],
+            "file1_unsorted.nocheck",
+        ),
+        (
+            &["--check-order", "--nocheck-order", FILE1, UNSORTED],
+            "file1_unsorted.nocheck",
+        ),
+    ];
+    for (args, name) in cases {
+        run(args, &format!("tests/expected/{name}.out"))?;
+    }
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn stdin() -> Result<()> {
+    run_stdin(FILE1, &["-", FILE2], "tests/expected/file1_file2.out")?;
+    run_stdin(FILE2, &[FILE1, "-"], "tests/expected/file1_file2.out")?;
+    run_stdin(
+        FILE2,
+        &["-1", FILE1, "-"],
+        "tests/expected/file1_file2.1.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn insensitive() -> Result<()> {
+    // 大文字小文字を無視すると b と B は共通の行になり、ファイル 1 の行を出力する
+    let cases: &[(&[&str], &str)] = &[
+        (&["-i", FILE1, FILE2], "a\n\t\tb\n\t\tc\n\t\td\n\te\n"),
+        (&["-i", "-1", FILE1, FILE2], "\tb\n\tc\n\td\ne\n"),
+        (
+            &["-i", "--total", "-3", FILE1, FILE2],
+            "a\n\te\n1\t1\t3\ttotal\n",
+        ),
+    ];
+    for (args, expected) in cases {
+        Command::cargo_bin(PRG)?
+            .args(*args)
+            .assert()
+            .success()
+            .stdout(*expected);
+    }
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn unsorted() -> Result<()> {
+    // 既定では警告して最後まで比較し、終了時に失敗する
+    let output = Command::cargo_bin(PRG)?.args([FILE1, UNSORTED]).output()?;
+    assert!(!output.status.success());
+    assert_eq!(
+        output.stdout,
+        fs::read("tests/expected/file1_unsorted.out")?
+    );
+    assert_eq!(
+        String::from_utf8(output.stderr)?,
+        "file 2 is not in sorted order\ninput is not in sorted order\n"
+    );
+
+    // --check-order では最初の誤りで終了する
+    let cases = [
+        ([FILE1, UNSORTED], "file1_unsorted.check", 2),
+        ([UNSORTED, UNSORTED], "unsorted_unsorted.check", 1),
+    ];
+    for (files, name, file) in cases {
+        let output = Command::cargo_bin(PRG)?
+            .arg("--check-order")
+            .args(files)
+            .output()?;
+        assert!(!output.status.success());
+        assert_eq!(
+            output.stdout,
+            fs::read(format!("tests/expected/{name}.out"))?
+        );
+        assert_eq!(
+            String::from_utf8(output.stderr)?,
+            format!("file {file} is not in sorted order\n")
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,47 @@
+#!/usr/bin/env bash
+
+# GNU coreutils の sort で期待値を生成する
+export LC_ALL=C
+SORT="/usr/bin/sort"
+ROOT="tests/inputs"
+OUT_DIR="tests/expected"
+
+[[ ! -d "$OUT_DIR" ]] && mkdir -p "$OUT_DIR"
+rm -f "$OUT_DIR"/*
+
+CITIES="$ROOT/cities.txt"
+EMPTY="$ROOT/empty.txt"
+HUMAN="$ROOT/human.txt"
+NOEOL="$ROOT/noeol.txt"
+NUMBERS="$ROOT/numbers.txt"
+USERS="$ROOT/users.txt"
+VERSIONS="$ROOT/versions.txt"
+WORDS="$ROOT/words.txt"
+
+$SORT $EMPTY                           > "$OUT_DIR/empty.out"
+$SORT $NOEOL                           > "$OUT_DIR/noeol.out"
+$SORT $WORDS                           > "$OUT_DIR/words.out"
+$SORT -r $WORDS                        > "$OUT_DIR/words.r.out"
+$SORT -u $WORDS                        > "$OUT_DIR/words.u.out"
+$SORT -f $WORDS                        > "$OUT_DIR/words.f.out"
+$SORT -f -u $WORDS                     > "$OUT_DIR/words.f.u.out"
+$SORT -f -s $WORDS                     > "$OUT_DIR/words.f.s.out"
+$SORT $WORDS $NOEOL                    > "$OUT_DIR/words_noeol.out"
+$SORT $NUMBERS                         > "$OUT_DIR/numbers.out"
+$SORT -n $NUMBERS                      > "$OUT_DIR/numbers.n.out"
+$SORT -n -r $NUMBERS                   > "$OUT_DIR/numbers.n.r.out"
+$SORT -n -u $NUMBERS                   > "$OUT_DIR/numbers.n.u.out"
+$SORT -h $HUMAN                        > "$OUT_DIR/human.h.out"
+$SORT -h -r $HUMAN                     > "$OUT_DIR/human.h.r.out"
+$SORT -V $VERSIONS                     > "$OUT_DIR/versions.V.out"
+$SORT -t : -k 2n $USERS                > "$OUT_DIR/users.k2n.out"
+$SORT -t : -k 2,2n -s $USERS           > "$OUT_DIR/users.k2n.s.out"
+$SORT -t : -k 3,3 -k 2,2nr $USERS      > "$OUT_DIR/users.k3.k2nr.out"
+$SORT -t : -k 4,4 -u $USERS            > "$OUT_DIR/users.k4.u.out"
+$SORT -t : -k 1.2,1.2 $USERS           > "$OUT_DIR/users.k1.2.out"
+$SORT -k 2 $CITIES                     > "$OUT_DIR/cities.k2.out"
+$SORT -k 2b $CITIES                    > "$OUT_DIR/cities.k2b.out"
+$SORT -b -k 2,2 $CITIES                > "$OUT_DIR/cities.b.k2.out"
+$SORT -k 2n -k 1 $CITIES               > "$OUT_DIR/cities.k2n.k1.out"
+$SORT -k 3h -r $CITIES                 > "$OUT_DIR/cities.k3h.r.out"
+$SORT -k 3,3hr $CITIES                 > "$OUT_DIR/cities.k3hr.out"
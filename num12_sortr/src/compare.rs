@@ -0,0 +1,291 @@
+use std::cmp::Ordering;
+
+/// A decimal number split into its parts, so that numbers of any length compare exactly.
+struct Number<'a> {
+    negative: bool,
+    integer: &'a [u8],
+    fraction: &'a [u8],
+    /// The bytes following the number.
+    rest: &'a [u8],
+}
+
+impl<'a> Number<'a> {
+    /// Parse a leading `-?[0-9]*(\.[0-9]*)?` after blanks. Anything else counts as zero.
+    fn parse(text: &'a [u8]) -> Self {
+        let text = trim_blanks(text);
+        let (negative, text) = match text.strip_prefix(b"-") {
+            Some(rest) => (true, rest),
+            None => (false, text),
+        };
+        let (integer, text) = split_digits(text);
+        let (fraction, rest) = match text.strip_prefix(b".") {
+            Some(text) => split_digits(text),
+            None => (&text[..0], text),
+        };
+
+        // 0 の並びは大小に影響しないので取り除いておく
+        let integer = &integer[integer.iter().take_while(|&&b| b == b'0').count()..];
+        let fraction =
+            &fraction[..fraction.len() - fraction.iter().rev().take_while(|&&b| b == b'0').count()];
+        Number {
+            negative: negative && !(integer.is_empty() && fraction.is_empty()),
+            integer,
+            fraction,
+            rest,
+        }
+    }
+
+    fn is_zero(&self) -> bool {
+        self.integer.is_empty() && self.fraction.is_empty()
+    }
+}
+
+fn trim_blanks(text: &[u8]) -> &[u8] {
+    let blanks = text.iter().take_while(|&&b| is_blank(b)).count();
+    &text[blanks..]
+}
+
+fn split_digits(text: &[u8]) -> (&[u8], &[u8]) {
+    text.split_at(text.iter().take_while(|b| b.is_ascii_digit()).count())
+}
+
+pub(crate) fn is_blank(b: u8) -> bool {
+    b == b' ' || b == b'\t'
+}
+
+/// Compare the leading numbers of `a` and `b`, as `sort -n` does in the C locale.
+pub(crate) fn numeric_cmp(a: &[u8], b: &[u8]) -> Ordering {
+    compare_numbers(&Number::parse(a), &Number::parse(b))
+}
+
+fn compare_numbers(a: &Number, b: &Number) -> Ordering {
+    let magnitude = || {
+        a.integer
+            .len()
+            .cmp(&b.integer.len())
+            .then_with(|| a.integer.cmp(b.integer))
+            .then_with(|| a.fraction.cmp(b.fraction))
+    };
+    match (a.negative, b.negative) {
+        (false, true) => Ordering::Greater,
+        (true, false) => Ordering::Less,
+        (false, false) => magnitude(),
+        (true, true) => magnitude().reverse(),
+    }
+}
+
+/// Compare numbers with an optional SI suffix such as `2K` or `1G`, as `sort -h` does.
+///
+/// Like GNU sort, the suffix is compared before the number, so `2000` sorts before `1K`.
+pub(crate) fn human_cmp(a: &[u8], b: &[u8]) -> Ordering {
+    let (a, b) = (Number::parse(a), Number::parse(b));
+    unit_order(&a)
+        .cmp(&unit_order(&b))
+        .then_with(|| compare_numbers(&a, &b))
+}
+
+fn unit_order(number: &Number) -> i32 {
+    if number.is_zero() {
+        return 0;
+    }
+    let order = match number.rest.first() {
+        Some(b'K' | b'k') => 1,
+        Some(b'M') => 2,
+        Some(b'G') => 3,
+        Some(b'T') => 4,
+        Some(b'P') => 5,
+        Some(b'E') => 6,
+        Some(b'Z') => 7,
+        Some(b'Y') => 8,
+        Some(b'R') => 9,
+        Some(b'Q') => 10,
+        _ => 0,
+    };
+    if number.negative { -order } else { order }
+}
+
+/// Compare version strings such as `file-1.10.txt`, following GNU `filevercmp`.
+pub(crate) fn version_cmp(a: &[u8], b: &[u8]) -> Ordering {
+    // 空文字列、"."、".."、"." で始まる名前の順に、それ以外の名前より前に並べる
+    match (a.is_empty(), b.is_empty()) {
+        (true, true) => return Ordering::Equal,
+        (true, false) => return Ordering::Less,
+        (false, true) => return Ordering::Greater,
+        _ => {}
+    }
+    match (a[0] == b'.', b[0] == b'.') {
+        (true, false) => return Ordering::Less,
+        (false, true) => return Ordering::Greater,
+        (true, true) => {
+            for special in [&b"."[..], b".."] {
+                match (a == special, b == special) {
+                    (true, true) => return Ordering::Equal,
+                    (true, false) => return Ordering::Less,
+                    (false, true) => return Ordering::Greater,
+                    _ => {}
+                }
+            }
+        }
+        _ => {}
+    }
+
+    // 拡張子を除いて比べ、同じなら拡張子を含めて比べなおす
+    let (a_prefix, b_prefix) = (suffix_start(a), suffix_start(b));
+    let result = compare_versions(&a[..a_prefix], &b[..b_prefix]);
+    if result.is_ne() || (a_prefix == a.len() && b_prefix == b.len()) {
+        return result;
+    }
+    compare_versions(a, b)
+}
+
+/// Where the file suffix, matching `(\.[A-Za-z~][A-Za-z0-9~]*)*$`, starts in `name`.
+fn suffix_start(name: &[u8]) -> usize {
+    let mut prefix = 0;
+    let mut i = 0;
+    while i < name.len() {
+        i += 1;
+        prefix = i;
+        while i + 1 < name.len()
+            && name[i] == b'.'
+            && (name[i + 1].is_ascii_alphabetic() || name[i + 1] == b'~')
+        {
+            i += 2;
+            while i < name.len() && (name[i].is_ascii_alphanumeric() || name[i] == b'~') {
+                i += 1;
+            }
+        }
+    }
+    prefix
+}
+
+/// The weight of the byte at `pos` in the non-digit parts: `~` sorts even before the end of the
+/// string, and letters sort before other bytes.
+fn order(text: &[u8], pos: usize) -> i32 {
+    match text.get(pos) {
+        None => -1,
+        Some(b) if b.is_ascii_digit() => 0,
+        Some(b) if b.is_ascii_alphabetic() => i32::from(*b),
+        Some(b'~') => -2,
+        Some(b) => i32::from(*b) + 256,
+    }
+}
+
+fn compare_versions(a: &[u8], b: &[u8]) -> Ordering {
+    let is_digit = |text: &[u8], pos: usize| text.get(pos).is_some_and(u8::is_ascii_digit);
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        while (i < a.len() && !a[i].is_ascii_digit()) || (j < b.len() && !b[j].is_ascii_digit()) {
+            let result = order(a, i).cmp(&order(b, j));
+            if result.is_ne() {
+                return result;
+            }
+            i += 1;
+            j += 1;
+        }
+
+        while a.get(i) == Some(&b'0') {
+            i += 1;
+        }
+        while b.get(j) == Some(&b'0') {
+            j += 1;
+        }
+        // 桁数の多いほうが大きく、同じ桁数なら最初に異なる数字で決まる
+        let mut first_diff = Ordering::Equal;
+        while is_digit(a, i) && is_digit(b, j) {
+            if first_diff.is_eq() {
+                first_diff = a[i].cmp(&b[j]);
+            }
+            i += 1;
+            j += 1;
+        }
+        if is_digit(a, i) {
+            return Ordering::Greater;
+        }
+        if is_digit(b, j) {
+            return Ordering::Less;
+        }
+        if first_diff.is_ne() {
+            return first_diff;
+        }
+    }
+    Ordering::Equal
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn sorted(cmp: fn(&[u8], &[u8]) -> Ordering, lines: &[&str]) -> Vec<String> {
+        let mut lines = lines.to_vec();
+        lines.sort_by(|a, b| cmp(a.as_bytes(), b.as_bytes()));
+        lines.iter().map(|line| line.to_string()).collect()
+    }
+
+    #[test]
+    fn test_numeric_cmp() {
+        assert_eq!(
+            sorted(
+                numeric_cmp,
+                &[
+                    "10", "9", "-1.5", " 2", "-10", "0.25", "x", "-0", "007", "1e3"
+                ]
+            ),
+            [
+                "-10", "-1.5", "x", "-0", "0.25", "1e3", " 2", "007", "9", "10"
+            ]
+        );
+        assert_eq!(numeric_cmp(b"1.50", b"1.5"), Ordering::Equal);
+        assert_eq!(numeric_cmp(b"+5", b"0"), Ordering::Equal);
+        assert_eq!(
+            numeric_cmp(
+                b"123456789012345678901234567890",
+                b"123456789012345678901234567891"
+            ),
+            Ordering::Less
+        );
+    }
+
+    #[test]
+    fn test_human_cmp() {
+        assert_eq!(
+            sorted(
+                human_cmp,
+                &["1G", "2000", "1K", "-1M", "10K", "0K", "2k", "512M", "-5"]
+            ),
+            ["-1M", "-5", "0K", "2000", "1K", "2k", "10K", "512M", "1G"]
+        );
+    }
+
+    #[test]
+    fn test_version_cmp() {
+        assert_eq!(
+            sorted(
+                version_cmp,
+                &[
+                    "a-1.10",
+                    "a-1.2",
+                    "a-1.2~rc1",
+                    "a-1.2a",
+                    ".hidden",
+                    "a-01.2",
+                    "b",
+                    "a",
+                    "."
+                ]
+            ),
+            [
+                ".",
+                ".hidden",
+                "a",
+                "a-1.2~rc1",
+                "a-1.2",
+                "a-01.2",
+                "a-1.2a",
+                "a-1.10",
+                "b"
+            ]
+        );
+        // 拡張子を除いた部分で先に比べる
+        assert_eq!(version_cmp(b"x-1.9.tar.gz", b"x-1.10.gz"), Ordering::Less);
+    }
+}
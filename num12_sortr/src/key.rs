@@ -0,0 +1,241 @@
+use std::cmp::Ordering;
+
+use crate::compare::{self, is_blank};
+
+/// How the text of a key is compared.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub(crate) enum SortMode {
+    #[default]
+    Text,
+    Numeric,
+    HumanNumeric,
+    Version,
+}
+
+/// The ordering options that can be given globally or to a single key.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub(crate) struct KeyOptions {
+    pub(crate) mode: SortMode,
+    pub(crate) ignore_case: bool,
+    pub(crate) reverse: bool,
+    pub(crate) skip_start_blanks: bool,
+    pub(crate) skip_end_blanks: bool,
+}
+
+/// A sort key given with `-k START[,END]`, where each position is `FIELD[.CHAR][OPTIONS]`.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Key {
+    /// Fields to skip before the key starts.
+    start_field: usize,
+    /// Characters to skip within the start field.
+    start_char: usize,
+    /// Fields to skip before the end field and characters to take from it, where `0` characters
+    /// means the whole field. `None` runs to the end of the line.
+    end: Option<(usize, usize)>,
+    pub(crate) options: KeyOptions,
+}
+
+impl Key {
+    /// The whole line, as used when no `-k` is given.
+    pub(crate) fn line(options: KeyOptions) -> Self {
+        Key {
+            start_field: 0,
+            start_char: 0,
+            end: None,
+            options,
+        }
+    }
+
+    /// Use the global `options` if this key has none of its own, as GNU sort does.
+    pub(crate) fn inherit(self, options: KeyOptions) -> Self {
+        if self.options != KeyOptions::default() {
+            return self;
+        }
+        Key { options, ..self }
+    }
+
+    /// Parse a `-k` argument.
+    pub(crate) fn parse(spec: &str) -> Result<Self, String> {
+        let invalid = |reason: &str| format!("{reason}invalid field specification '{spec}'");
+        let (start, end) = match spec.split_once(',') {
+            Some((start, end)) => (start, Some(end)),
+            None => (spec, None),
+        };
+
+        let mut options = KeyOptions::default();
+        let (field, char, flags) = parse_position(start).ok_or_else(|| invalid(""))?;
+        let start_field = field
+            .checked_sub(1)
+            .ok_or_else(|| invalid("field number is zero: "))?;
+        let start_char = match char {
+            Some(char) => char
+                .checked_sub(1)
+                .ok_or_else(|| invalid("character offset is zero: "))?,
+            None => 0,
+        };
+        parse_flags(flags, &mut options, |options| {
+            options.skip_start_blanks = true
+        })
+        .ok_or_else(|| invalid(""))?;
+
+        let end = match end {
+            Some(end) => {
+                let (field, char, flags) = parse_position(end).ok_or_else(|| invalid(""))?;
+                let end_field = field
+                    .checked_sub(1)
+                    .ok_or_else(|| invalid("field number is zero: "))?;
+                parse_flags(flags, &mut options, |options| {
+                    options.skip_end_blanks = true
+                })
+                .ok_or_else(|| invalid(""))?;
+                Some((end_field, char.unwrap_or(0)))
+            }
+            None => None,
+        };
+
+        Ok(Key {
+            start_field,
+            start_char,
+            end,
+            options,
+        })
+    }
+
+    /// The part of `line` that this key covers.
+    pub(crate) fn extract<'a>(&self, line: &'a [u8], separator: Option<u8>) -> &'a [u8] {
+        let start = self.start(line, separator);
+        let end = self.end(line, separator).max(start);
+        &line[start..end]
+    }
+
+    fn start(&self, line: &[u8], separator: Option<u8>) -> usize {
+        let mut pos = skip_fields(line, 0, self.start_field, separator, true);
+        if self.options.skip_start_blanks {
+            pos = skip_blanks(line, pos);
+        }
+        line.len().min(pos + self.start_char)
+    }
+
+    fn end(&self, line: &[u8], separator: Option<u8>) -> usize {
+        let Some((end_field, end_char)) = self.end else {
+            return line.len();
+        };
+        if end_char == 0 {
+            // 文字位置がなければ、区切り文字を含めずにフィールドの末尾までを使う
+            return skip_fields(line, 0, end_field + 1, separator, false);
+        }
+        let mut pos = skip_fields(line, 0, end_field, separator, true);
+        if self.options.skip_end_blanks {
+            pos = skip_blanks(line, pos);
+        }
+        line.len().min(pos + end_char)
+    }
+
+    pub(crate) fn compare(&self, a: &[u8], b: &[u8], separator: Option<u8>) -> Ordering {
+        let (a, b) = (self.extract(a, separator), self.extract(b, separator));
+        let ordering = match self.options.mode {
+            SortMode::Numeric => compare::numeric_cmp(a, b),
+            SortMode::HumanNumeric => compare::human_cmp(a, b),
+            SortMode::Version => compare::version_cmp(a, b),
+            SortMode::Text if self.options.ignore_case => {
+                let upper =
+                    |text: &[u8]| text.iter().map(u8::to_ascii_uppercase).collect::<Vec<_>>();
+                upper(a).cmp(&upper(b))
+            }
+            SortMode::Text => a.cmp(b),
+        };
+        if self.options.reverse {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    }
+}
+
+/// Split `FIELD[.CHAR]OPTIONS` into its numbers and the option letters.
+fn parse_position(position: &str) -> Option<(usize, Option<usize>, &str)> {
+    let digits = |text: &str| {
+        text.find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(text.len())
+    };
+    let (field, rest) = position.split_at(digits(position));
+    let field = field.parse().ok()?;
+    match rest.strip_prefix('.') {
+        Some(rest) => {
+            let (char, flags) = rest.split_at(digits(rest));
+            Some((field, Some(char.parse().ok()?), flags))
+        }
+        None => Some((field, None, rest)),
+    }
+}
+
+/// Apply the option letters of a key position. `b` means something different for each end.
+fn parse_flags(
+    flags: &str,
+    options: &mut KeyOptions,
+    skip_blanks: impl Fn(&mut KeyOptions),
+) -> Option<()> {
+    for flag in flags.chars() {
+        let mode = match flag {
+            'b' => {
+                skip_blanks(options);
+                continue;
+            }
+            'f' => {
+                options.ignore_case = true;
+                continue;
+            }
+            'r' => {
+                options.reverse = true;
+                continue;
+            }
+            'n' => SortMode::Numeric,
+            'h' => SortMode::HumanNumeric,
+            'V' => SortMode::Version,
+            _ => return None,
+        };
+        if options.mode != SortMode::Text && options.mode != mode {
+            return None;
+        }
+        options.mode = mode;
+    }
+    Some(())
+}
+
+/// Skip `count` fields from `pos`. Without a separator a field is its leading blanks followed by
+/// non-blanks. With one, the separator after the last skipped field is also skipped when
+/// `past_separator` is set.
+fn skip_fields(
+    line: &[u8],
+    mut pos: usize,
+    count: usize,
+    separator: Option<u8>,
+    past_separator: bool,
+) -> usize {
+    for skipped in 1..=count {
+        if pos >= line.len() {
+            break;
+        }
+        match separator {
+            Some(separator) => {
+                while pos < line.len() && line[pos] != separator {
+                    pos += 1;
+                }
+                if pos < line.len() && (skipped < count || past_separator) {
+                    pos += 1;
+                }
+            }
+            None => {
+                pos = skip_blanks(line, pos);
+                while pos < line.len() && !is_blank(line[pos]) {
+                    pos += 1;
+                }
+            }
+        }
+    }
+    pos
+}
+
+fn skip_blanks(line: &[u8], pos: usize) -> usize {
+    pos + line[pos..].iter().take_while(|&&b| is_blank(b)).count()
+}
@@ -0,0 +1,537 @@
+use std::{
+    cmp::Ordering,
+    env,
+    error::Error,
+    ffi::OsString,
+    fs::File,
+    io::{self, BufRead, BufReader, BufWriter, Seek, Write},
+    mem,
+    path::PathBuf,
+    thread,
+};
+
+use clap::{ArgAction, Args, Parser, value_parser};
+
+mod compare;
+mod key;
+
+use key::{Key, KeyOptions, SortMode};
+
+type MyResult<T> = Result<T, Box<dyn Error>>;
+
+/// How many sorted runs are merged at once, which bounds the number of open temporary files.
+const MERGE_FAN_IN: usize = 16;
+
+#[derive(Debug)]
+pub struct Config {
+    files: Vec<String>,
+    order: LineOrder,
+    unique: bool,
+    buffer_size: usize,
+    parallel: usize,
+    temp_dir: PathBuf,
+}
+
+/// How two lines compare: by each key in turn, then by the whole line as a last resort.
+#[derive(Debug)]
+struct LineOrder {
+    keys: Vec<Key>,
+    separator: Option<u8>,
+    /// Compare the whole lines byte by byte when all keys are equal, unless `-s` or `-u` is given.
+    last_resort: bool,
+    reverse: bool,
+}
+
+impl LineOrder {
+    fn compare(&self, a: &[u8], b: &[u8]) -> Ordering {
+        let ordering = self.compare_keys(a, b);
+        if ordering.is_ne() || !self.last_resort {
+            return ordering;
+        }
+        if self.reverse { b.cmp(a) } else { a.cmp(b) }
+    }
+
+    fn compare_keys(&self, a: &[u8], b: &[u8]) -> Ordering {
+        self.keys
+            .iter()
+            .map(|key| key.compare(a, b, self.separator))
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(Ordering::Equal)
+    }
+}
+
+#[derive(Parser, Debug)]
+#[command(version, disable_help_flag = true, disable_version_flag = true)]
+#[command(about = "Rust sort")]
+struct Cli {
+    /// Input file(s).
+    #[arg(default_value = "-")]
+    files: Vec<String>,
+
+    #[command(flatten)]
+    mode: ModeArgs,
+
+    /// Ignore leading blanks.
+    #[arg(short('b'), long)]
+    ignore_leading_blanks: bool,
+
+    /// Fold lower case to upper case characters.
+    #[arg(short('f'), long)]
+    ignore_case: bool,
+
+    /// Reverse the result of comparisons.
+    #[arg(short, long)]
+    reverse: bool,
+
+    /// Sort via a key; KEYDEF is F[.C][OPTS][,F[.C][OPTS]] with OPTS from "bfhnrV".
+    #[arg(short, long, value_name = "KEYDEF", value_parser = Key::parse)]
+    key: Vec<Key>,
+
+    /// Use SEP instead of the transition from non-blank to blank as the field separator.
+    #[arg(
+        short('t'),
+        long,
+        value_name = "SEP",
+        value_parser = parse_separator,
+        allow_hyphen_values = true
+    )]
+    field_separator: Option<u8>,
+
+    /// Output only the first of an equal run.
+    #[arg(short, long)]
+    unique: bool,
+
+    /// Stabilize sort by disabling last-resort comparison.
+    #[arg(short, long)]
+    stable: bool,
+
+    /// Use SIZE bytes for the main memory buffer; suffixes b, K (default), M, G and T.
+    #[arg(
+        short('S'),
+        long,
+        value_name = "SIZE",
+        default_value = "64M",
+        value_parser = parse_buffer_size
+    )]
+    buffer_size: usize,
+
+    /// Use DIR for temporary files instead of $TMPDIR or /tmp.
+    #[arg(short('T'), long, value_name = "DIR")]
+    temporary_directory: Option<PathBuf>,
+
+    /// Change the number of sorts run concurrently to N.
+    #[arg(long, value_name = "N", value_parser = value_parser!(u16).range(1..))]
+    parallel: Option<u16>,
+
+    /// Print help.
+    #[arg(long, action = ArgAction::Help)]
+    help: Option<bool>,
+
+    /// Print version.
+    #[arg(long, action = ArgAction::Version)]
+    version: Option<bool>,
+}
+
+#[derive(Args, Debug)]
+#[group(multiple = false)]
+struct ModeArgs {
+    /// Compare according to string numerical value.
+    #[arg(short, long)]
+    numeric_sort: bool,
+
+    /// Compare human readable numbers (e.g., 2K 1G).
+    #[arg(short('h'), long)]
+    human_numeric_sort: bool,
+
+    /// Natural sort of (version) numbers within text.
+    #[arg(short('V'), long)]
+    version_sort: bool,
+}
+
+pub fn get_args() -> MyResult<Config> {
+    get_args_from(env::args_os())
+}
+
+pub fn get_args_from<I, T>(args: I) -> MyResult<Config>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<OsString> + Clone,
+{
+    let args = Cli::parse_from(args);
+    let mode = match (
+        args.mode.numeric_sort,
+        args.mode.human_numeric_sort,
+        args.mode.version_sort,
+    ) {
+        (true, _, _) => SortMode::Numeric,
+        (_, true, _) => SortMode::HumanNumeric,
+        (_, _, true) => SortMode::Version,
+        _ => SortMode::Text,
+    };
+    let global = KeyOptions {
+        mode,
+        ignore_case: args.ignore_case,
+        reverse: args.reverse,
+        skip_start_blanks: args.ignore_leading_blanks,
+        skip_end_blanks: args.ignore_leading_blanks,
+    };
+
+    let keys = if args.key.is_empty() {
+        vec![Key::line(global)]
+    } else {
+        args.key
+            .into_iter()
+            .map(|key| key.inherit(global))
+            .collect()
+    };
+    let parallel = match args.parallel {
+        Some(parallel) => usize::from(parallel),
+        None => thread::available_parallelism().map_or(1, |n| n.get().min(8)),
+    };
+
+    Ok(Config {
+        files: args.files,
+        order: LineOrder {
+            keys,
+            separator: args.field_separator,
+            last_resort: !args.stable && !args.unique,
+            reverse: args.reverse,
+        },
+        unique: args.unique,
+        buffer_size: args.buffer_size,
+        parallel,
+        temp_dir: args.temporary_directory.unwrap_or_else(env::temp_dir),
+    })
+}
+
+fn parse_separator(separator: &str) -> Result<u8, String> {
+    match separator.as_bytes() {
+        [b] => Ok(*b),
+        b"\\0" => Ok(b'\0'),
+        [] => Err("empty tab".to_string()),
+        _ => Err(format!("multi-character tab '{separator}'")),
+    }
+}
+
+fn parse_buffer_size(size: &str) -> Result<usize, String> {
+    let invalid = || format!("invalid -S argument '{size}'");
+    let digits = size
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(size.len());
+    let (number, suffix) = size.split_at(digits);
+    let shift = match suffix {
+        "b" => 0,
+        "" | "K" | "k" => 10,
+        "M" | "m" => 20,
+        "G" | "g" => 30,
+        "T" | "t" => 40,
+        _ => return Err(invalid()),
+    };
+    number
+        .parse::<usize>()
+        .ok()
+        .filter(|&number| number > 0)
+        .and_then(|number| number.checked_mul(1 << shift))
+        .ok_or_else(invalid)
+}
+
+pub fn run(config: Config) -> MyResult<()> {
+    let mut sorter = Sorter::new(&config);
+    for file_name in &config.files {
+        let mut file = open(file_name).map_err(|e| format!("{}: {}", file_name, e))?;
+        let mut line = Vec::new();
+        while file.read_until(b'\n', &mut line)? > 0 {
+            if line.ends_with(b"\n") {
+                line.pop();
+            }
+            sorter.push(mem::take(&mut line))?;
+        }
+    }
+    sorter.finish(BufWriter::new(io::stdout().lock()))
+}
+
+fn open(file_name: &str) -> MyResult<Box<dyn BufRead>> {
+    match file_name {
+        "-" => Ok(Box::new(BufReader::new(io::stdin()))),
+        _ => Ok(Box::new(BufReader::new(File::open(file_name)?))),
+    }
+}
+
+/// Sorts lines a buffer at a time. Once the buffer is full it is sorted and spilled to a temporary
+/// file, and the spilled runs are merged at the end, so inputs larger than memory can be sorted.
+struct Sorter<'a> {
+    config: &'a Config,
+    buffer: Vec<Vec<u8>>,
+    buffered_bytes: usize,
+    /// Spilled runs in input order, with how many merges each has been through.
+    runs: Vec<(usize, File)>,
+}
+
+impl<'a> Sorter<'a> {
+    fn new(config: &'a Config) -> Self {
+        Sorter {
+            config,
+            buffer: Vec::new(),
+            buffered_bytes: 0,
+            runs: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, line: Vec<u8>) -> MyResult<()> {
+        self.buffered_bytes += line.len() + mem::size_of::<Vec<u8>>();
+        self.buffer.push(line);
+        if self.buffered_bytes >= self.config.buffer_size {
+            self.spill()?;
+        }
+        Ok(())
+    }
+
+    fn finish(mut self, out: impl Write) -> MyResult<()> {
+        if self.runs.is_empty() {
+            self.sort_buffer();
+            let mut output = Output::new(self.config, out);
+            for line in &self.buffer {
+                output.write_line(line)?;
+            }
+            return output.finish();
+        }
+
+        if !self.buffer.is_empty() {
+            self.spill()?;
+        }
+        let runs = self.runs.into_iter().map(|(_, run)| run).collect();
+        merge(self.config, runs, out)
+    }
+
+    fn spill(&mut self) -> MyResult<()> {
+        self.sort_buffer();
+        let mut output = Output::new(
+            self.config,
+            BufWriter::new(tempfile::tempfile_in(&self.config.temp_dir)?),
+        );
+        for line in self.buffer.drain(..) {
+            output.write_line(&line)?;
+        }
+        output.finish()?;
+        let run = output.out.into_inner().map_err(|e| e.into_error())?;
+        self.buffered_bytes = 0;
+        self.runs.push((0, run));
+
+        // マージした回数が同じ一時ファイルが揃ったら 1 つにまとめ、開いたままのファイルを抑える
+        while let Some(tail) = self.runs.len().checked_sub(MERGE_FAN_IN)
+            && self.runs[tail..]
+                .iter()
+                .all(|(level, _)| *level == self.runs[tail].0)
+        {
+            let level = self.runs[tail].0;
+            let runs = self.runs.drain(tail..).map(|(_, run)| run).collect();
+            let mut merged = tempfile::tempfile_in(&self.config.temp_dir)?;
+            merge(self.config, runs, BufWriter::new(&mut merged))?;
+            self.runs.push((level + 1, merged));
+        }
+        Ok(())
+    }
+
+    /// Sort the buffer, splitting it between `--parallel` threads.
+    fn sort_buffer(&mut self) {
+        let order = &self.config.order;
+        let compare = |a: &Vec<u8>, b: &Vec<u8>| order.compare(a, b);
+        let chunk_size = self.buffer.len().div_ceil(self.config.parallel).max(1);
+        if self.config.parallel > 1 && self.buffer.len() > chunk_size {
+            thread::scope(|scope| {
+                for chunk in self.buffer.chunks_mut(chunk_size) {
+                    scope.spawn(move || chunk.sort_by(compare));
+                }
+            });
+        }
+        // 標準の安定ソートはソート済みの区間を検出するので、スレッドで並べた区間のマージになる
+        self.buffer.sort_by(compare);
+    }
+}
+
+/// Writes sorted lines, dropping lines equal to the previous one under `-u`.
+struct Output<'a, W> {
+    config: &'a Config,
+    out: W,
+    previous: Option<Vec<u8>>,
+}
+
+impl<'a, W: Write> Output<'a, W> {
+    fn new(config: &'a Config, out: W) -> Self {
+        Output {
+            config,
+            out,
+            previous: None,
+        }
+    }
+
+    fn write_line(&mut self, line: &[u8]) -> io::Result<()> {
+        if self.config.unique {
+            if let Some(previous) = &self.previous
+                && self.config.order.compare_keys(previous, line).is_eq()
+            {
+                return Ok(());
+            }
+            self.previous = Some(line.to_vec());
+        }
+        self.out.write_all(line)?;
+        self.out.write_all(b"\n")
+    }
+
+    fn finish(&mut self) -> MyResult<()> {
+        self.out.flush()?;
+        Ok(())
+    }
+}
+
+/// Merge sorted `runs` into `out`. Equal lines are taken from the earlier run, which keeps the
+/// sort stable.
+fn merge(config: &Config, runs: Vec<File>, out: impl Write) -> MyResult<()> {
+    let mut readers = Vec::new();
+    let mut heads = Vec::new();
+    for mut run in runs {
+        run.rewind()?;
+        let mut reader = BufReader::new(run);
+        heads.push(read_line(&mut reader)?);
+        readers.push(reader);
+    }
+
+    let mut output = Output::new(config, out);
+    loop {
+        let mut smallest: Option<usize> = None;
+        for (index, head) in heads.iter().enumerate() {
+            let Some(line) = head else {
+                continue;
+            };
+            if smallest.is_none_or(|smallest| {
+                let current = heads[smallest].as_deref().unwrap_or_default();
+                config.order.compare(line, current).is_lt()
+            }) {
+                smallest = Some(index);
+            }
+        }
+        let Some(index) = smallest else {
+            break;
+        };
+        let next = read_line(&mut readers[index])?;
+        let line = mem::replace(&mut heads[index], next).unwrap_or_default();
+        output.write_line(&line)?;
+    }
+    output.finish()
+}
+
+fn read_line(reader: &mut impl BufRead) -> io::Result<Option<Vec<u8>>> {
+    let mut line = Vec::new();
+    if reader.read_until(b'\n', &mut line)? == 0 {
+        return Ok(None);
+    }
+    line.pop();
+    Ok(Some(line))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn config(args: &[&str]) -> Config {
+        get_args_from([&["sortr"], args].concat()).unwrap()
+    }
+
+    fn sort(config: &Config, input: &str) -> String {
+        let mut sorter = Sorter::new(config);
+        for line in input.lines() {
+            sorter.push(line.as_bytes().to_vec()).unwrap();
+        }
+        let mut out = Vec::new();
+        sorter.finish(&mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn args_verify() {
+        use clap::CommandFactory;
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_parse_buffer_size() {
+        assert_eq!(parse_buffer_size("10"), Ok(10 << 10));
+        assert_eq!(parse_buffer_size("1b"), Ok(1));
+        assert_eq!(parse_buffer_size("2M"), Ok(2 << 20));
+        assert!(parse_buffer_size("0").is_err());
+        assert!(parse_buffer_size("1X").is_err());
+        assert!(parse_buffer_size("M").is_err());
+    }
+
+    #[test]
+    fn test_key_parse() {
+        assert!(Key::parse("2,3n").is_ok());
+        assert!(Key::parse("1.2b,1.5").is_ok());
+        assert_eq!(
+            Key::parse("0"),
+            Err("field number is zero: invalid field specification '0'".to_string())
+        );
+        assert_eq!(
+            Key::parse("1.0"),
+            Err("character offset is zero: invalid field specification '1.0'".to_string())
+        );
+        assert!(Key::parse("1x").is_err());
+        assert!(Key::parse("1nh").is_err());
+        assert!(Key::parse("a").is_err());
+    }
+
+    #[test]
+    fn test_key_extract() {
+        let key = |spec| Key::parse(spec).unwrap();
+        assert_eq!(key("2").extract(b"a  b c", None), b"  b c");
+        assert_eq!(key("2b").extract(b"a  b c", None), b"b c");
+        assert_eq!(key("2,2").extract(b"a  b c", None), b"  b");
+        assert_eq!(key("1.2,1.3").extract(b"abcd e", None), b"bc");
+        assert_eq!(key("2,2").extract(b"a:b:c", Some(b':')), b"b");
+        assert_eq!(key("2,3").extract(b"a:b:c:d", Some(b':')), b"b:c");
+        assert_eq!(key("3").extract(b"a:b", Some(b':')), b"");
+        assert_eq!(key("2.2,2.1").extract(b"a bcd", None), b"");
+    }
+
+    #[test]
+    fn test_sort() {
+        assert_eq!(sort(&config(&[]), "b\na\nc\na\n"), "a\na\nb\nc\n");
+        assert_eq!(sort(&config(&["-r"]), "b\na\nc\n"), "c\nb\na\n");
+        assert_eq!(sort(&config(&["-u"]), "b\na\nb\na\n"), "a\nb\n");
+        assert_eq!(sort(&config(&["-n", "-u"]), "1\n01\n2\n"), "1\n2\n");
+        // 数値が等しければ行全体で比べ、-s なら入力の順を保つ
+        assert_eq!(sort(&config(&["-n"]), "01\n1\n"), "01\n1\n");
+        assert_eq!(sort(&config(&["-n", "-s"]), "1\n01\n"), "1\n01\n");
+        assert_eq!(
+            sort(
+                &config(&["-t", ":", "-k", "2n", "-k", "1r"]),
+                "a:2\nb:1\nc:2\n"
+            ),
+            "b:1\nc:2\na:2\n"
+        );
+    }
+
+    #[test]
+    fn test_sort_spill() {
+        // 1 行ごとにファイルへ書き出しても、メモリ上でのソートと同じ結果になる
+        let input: String = (0..100).map(|i| format!("{}\n", (i * 37) % 50)).collect();
+        for args in [
+            &["-n"][..],
+            &["-n", "-u"],
+            &["-r", "--parallel", "3"],
+            &["-n", "-s", "-k", "1.1,1.1"],
+        ] {
+            let expected = sort(&config(args), &input);
+            let spilled = config(&[args, &["-S", "1b"]].concat());
+            assert_eq!(sort(&spilled, &input), expected, "{args:?}");
+        }
+
+        let spilled = config(&["-S", "1b"]);
+        let mut sorter = Sorter::new(&spilled);
+        for line in input.lines() {
+            sorter.push(line.as_bytes().to_vec()).unwrap();
+        }
+        // 16 個ごとにマージするので、100 個の一時ファイルは 6 + 4 個にまとまる
+        assert_eq!(sorter.runs.len(), 10);
+    }
+}
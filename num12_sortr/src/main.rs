@@ -0,0 +1,6 @@
+fn main() {
+    if let Err(e) = num12_sortr::get_args().and_then(num12_sortr::run) {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+}
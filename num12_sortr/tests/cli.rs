@@ -0,0 +1,229 @@
+use anyhow::Result;
+use assert_cmd::Command;
+use predicates::prelude::*;
+use pretty_assertions::assert_eq;
+use rand::{distr::Alphanumeric, Rng};
+use std::fs;
+
+const PRG: &str = "num12_sortr";
+const CITIES: &str = "tests/inputs/cities.txt";
+const EMPTY: &str = "tests/inputs/empty.txt";
+const HUMAN: &str = "tests/inputs/human.txt";
+const NOEOL: &str = "tests/inputs/noeol.txt";
+const NUMBERS: &str = "tests/inputs/numbers.txt";
+const USERS: &str = "tests/inputs/users.txt";
+const VERSIONS: &str = "tests/inputs/versions.txt";
+const WORDS: &str = "tests/inputs/words.txt";
+
+// --------------------------------------------------
+fn gen_bad_file() -> String {
+    loop {
+        let filename: String = rand::rng()
+            .sample_iter(&Alphanumeric)
+            .take(7)
+            .map(char::from)
+            .collect();
+
+        if fs::metadata(&filename).is_err() {
+            return filename;
+        }
+    }
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_file() -> Result<()> {
+    let bad = gen_bad_file();
+    let expected = format!("{bad}: .* [(]os error 2[)]");
+    Command::cargo_bin(PRG)?
+        .args([WORDS, &bad])
+        .assert()
+        .failure()
+        .stderr(predicate::str::is_match(expected)?);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_key() -> Result<()> {
+    let cases = [
+        ("0", "field number is zero: invalid field specification '0'"),
+        (
+            "1.0",
+            "character offset is zero: invalid field specification '1.0'",
+        ),
+        ("2x", "invalid field specification '2x'"),
+        ("1nV", "invalid field specification '1nV'"),
+    ];
+    for (key, expected) in cases {
+        Command::cargo_bin(PRG)?
+            .args(["-k", key, WORDS])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(expected));
+    }
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_options() -> Result<()> {
+    let cases: &[(&[&str], &str)] = &[
+        (&["-S", "1X"], "invalid -S argument '1X'"),
+        (&["-S", "0"], "invalid -S argument '0'"),
+        (&["-t", "ab"], "multi-character tab 'ab'"),
+        (&["--parallel", "0"], "invalid value '0'"),
+        (&["-n", "-h"], "cannot be used with"),
+    ];
+    for (args, expected) in cases {
+        Command::cargo_bin(PRG)?
+            .args(*args)
+            .arg(WORDS)
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(*expected));
+    }
+    Ok(())
+}
+
+// --------------------------------------------------
+fn run(args: &[&str], expected_file: &str) -> Result<()> {
+    let expected = fs::read(expected_file)?;
+    let output = Command::cargo_bin(PRG)?.args(args).output()?;
+    assert!(output.status.success());
+    assert_eq!(output.stdout, expected, "{args:?}");
+    Ok(())
+}
+
+// --------------------------------------------------
+fn cases() -> Vec<(Vec<&'static str>, &'static str)> {
+    let cases: &[(&[&str], &str)] = &[
+        (&[EMPTY], "empty"),
+        (&[NOEOL], "noeol"),
+        (&[WORDS], "words"),
+        (&["-r", WORDS], "words.r"),
+        (&["--unique", WORDS], "words.u"),
+        (&["-f", WORDS], "words.f"),
+        (&["-f", "-u", WORDS], "words.f.u"),
+        (&["--ignore-case", "--stable", WORDS], "words.f.s"),
+        (&[WORDS, NOEOL], "words_noeol"),
+        (&[NUMBERS], "numbers"),
+        (&["-n", NUMBERS], "numbers.n"),
+        (&["--numeric-sort", "--reverse", NUMBERS], "numbers.n.r"),
+        (&["-n", "-u", NUMBERS], "numbers.n.u"),
+        (&["-h", HUMAN], "human.h"),
+        (&["--human-numeric-sort", "-r", HUMAN], "human.h.r"),
+        (&["-V", VERSIONS], "versions.V"),
+        (&["-t", ":", "-k", "2n", USERS], "users.k2n"),
+        (&["-t:", "-k", "2,2n", "-s", USERS], "users.k2n.s"),
+        (
+            &["-t", ":", "-k", "3,3", "-k", "2,2nr", USERS],
+            "users.k3.k2nr",
+        ),
+        (
+            &["--field-separator=:", "--key=4,4", "-u", USERS],
+            "users.k4.u",
+        ),
+        (&["-t", ":", "-k", "1.2,1.2", USERS], "users.k1.2"),
+        (&["-k", "2", CITIES], "cities.k2"),
+        (&["-k", "2b", CITIES], "cities.k2b"),
+        (&["-b", "-k", "2,2", CITIES], "cities.b.k2"),
+        (&["-k", "2n", "-k", "1", CITIES], "cities.k2n.k1"),
+        (&["-k", "3h", "-r", CITIES], "cities.k3h.r"),
+        (&["-k", "3,3hr", CITIES], "cities.k3hr"),
+    ];
+    cases
+        .iter()
+        .map(|(args, name)| (args.to_vec(), *name))
+        .collect()
+}
+
+// --------------------------------------------------
+#[test]
+fn expected() -> Result<()> {
+    for (args, name) in cases() {
+        run(&args, &format!("tests/expected/{name}.out"))?;
+    }
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn external_sort() -> Result<()> {
+    // バッファを小さくして一時ファイルに書き出しても、結果は変わらない
+    let dir = tempfile::tempdir()?;
+    let temp_dir = dir.path().to_str().unwrap();
+    for options in [
+        &["-S", "1b"][..],
+        &["-S", "100b", "--parallel", "2"],
+        &["--buffer-size=1b", "--parallel=4", "-T", temp_dir],
+    ] {
+        for (args, name) in cases() {
+            run(
+                &[options, &args].concat(),
+                &format!("tests/expected/{name}.out"),
+            )?;
+        }
+    }
+    // 一時ファイルは終了時に消える
+    assert_eq!(fs::read_dir(dir.path())?.count(), 0);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn external_sort_large() -> Result<()> {
+    let numbers: Vec<u32> = (0..20_000).map(|i| (i * 7919) % 10_007).collect();
+    let input: String = numbers.iter().map(|n| format!("{n}\n")).collect();
+    let mut sorted = numbers.clone();
+    sorted.sort_unstable_by(|a, b| b.cmp(a));
+    sorted.dedup();
+    let expected: String = sorted.iter().map(|n| format!("{n}\n")).collect();
+
+    Command::cargo_bin(PRG)?
+        .args(["-n", "-r", "-u", "-S", "4K", "--parallel", "3"])
+        .write_stdin(input)
+        .assert()
+        .success()
+        .stdout(expected);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn stdin() -> Result<()> {
+    let input = fs::read_to_string(NUMBERS)?;
+    let expected = fs::read_to_string("tests/expected/numbers.n.out")?;
+    Command::cargo_bin(PRG)?
+        .arg("-n")
+        .write_stdin(input)
+        .assert()
+        .success()
+        .stdout(expected);
+
+    let expected = fs::read_to_string("tests/expected/words_noeol.out")?;
+    Command::cargo_bin(PRG)?
+        .args([WORDS, "-"])
+        .write_stdin(fs::read(NOEOL)?)
+        .assert()
+        .success()
+        .stdout(expected);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn help_and_version() -> Result<()> {
+    // -h と -V は並べ替えの種類に使うので、ヘルプとバージョンは長いオプションだけ
+    Command::cargo_bin(PRG)?
+        .arg("--help")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("--human-numeric-sort"));
+    Command::cargo_bin(PRG)?
+        .arg("--version")
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(PRG));
+    Ok(())
+}
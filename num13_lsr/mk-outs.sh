@@ -0,0 +1,35 @@
+#!/usr/bin/env bash
+
+# GNU coreutils の ls で期待値を生成する
+# 列の間は空白で埋めるため -T 0 を付ける。所有者や時刻に依存する -l と -t はテストで確かめる
+export LC_ALL=C
+LS="/usr/bin/ls"
+ROOT="tests/inputs"
+OUT_DIR="tests/expected"
+
+[[ ! -d "$OUT_DIR" ]] && mkdir -p "$OUT_DIR"
+rm -f "$OUT_DIR"/*
+
+COLS="$ROOT/cols"
+DIR="$ROOT/dir"
+# ディレクトリのサイズはファイルシステムによって異なるので、-S はファイルだけで確かめる
+FILES="$ROOT/bustle.txt $ROOT/empty.txt $ROOT/fox.txt $ROOT/link.txt $DIR/spiders.txt"
+
+$LS $ROOT                              > "$OUT_DIR/inputs.out"
+$LS -a $ROOT                           > "$OUT_DIR/inputs.a.out"
+$LS -r $ROOT                           > "$OUT_DIR/inputs.r.out"
+$LS -S $FILES                          > "$OUT_DIR/files.S.out"
+$LS -S -r $FILES                       > "$OUT_DIR/files.S.r.out"
+$LS -R $ROOT                           > "$OUT_DIR/inputs.R.out"
+$LS -R -a $DIR                         > "$OUT_DIR/dir.R.a.out"
+$LS $ROOT/fox.txt                      > "$OUT_DIR/fox.out"
+$LS $ROOT/link.txt $ROOT/bustle.txt    > "$OUT_DIR/link_bustle.out"
+$LS $DIR $ROOT/fox.txt $ROOT/empty.txt > "$OUT_DIR/dir_fox_empty.out"
+$LS $DIR $COLS                         > "$OUT_DIR/dir_cols.out"
+$LS -C -T 0 -w 80 $COLS                > "$OUT_DIR/cols.C80.out"
+$LS -C -T 0 -w 40 $COLS                > "$OUT_DIR/cols.C40.out"
+$LS -C -T 0 -w 20 $COLS                > "$OUT_DIR/cols.C20.out"
+$LS -C -T 0 -w 10 $COLS                > "$OUT_DIR/cols.C10.out"
+$LS -C -T 0 -w 40 -r $COLS             > "$OUT_DIR/cols.C40.r.out"
+$LS -C -T 0 -w 30 $DIR $COLS           > "$OUT_DIR/dir_cols.C30.out"
+$LS -C -1 $COLS                        > "$OUT_DIR/cols.1.out"
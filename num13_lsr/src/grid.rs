@@ -0,0 +1,70 @@
+/// The narrowest a column can be: one character and the two spaces separating it from the next.
+const MIN_COLUMN_WIDTH: usize = 3;
+
+/// Lay out `names` in as many columns as fit in `width`, filled top to bottom like `ls -C`.
+///
+/// GNU ls pads with tabs by default; this always pads with spaces, as `ls -T 0` does.
+pub(crate) fn grid(names: &[String], width: usize) -> String {
+    let lengths: Vec<usize> = names.iter().map(|name| name.chars().count()).collect();
+    let max_columns = (width / MIN_COLUMN_WIDTH).clamp(1, names.len().max(1));
+    let (rows, widths) = (1..=max_columns)
+        .rev()
+        .map(|columns| column_widths(&lengths, columns))
+        .find(|(_, widths)| widths.len() == 1 || widths.iter().sum::<usize>() < width)
+        .unwrap_or_default();
+
+    let mut out = String::new();
+    for row in 0..rows {
+        for (column, width) in widths.iter().enumerate() {
+            let index = column * rows + row;
+            let Some(name) = names.get(index) else {
+                break;
+            };
+            out.push_str(name);
+            if index + rows < names.len() {
+                out.push_str(&" ".repeat(width - lengths[index]));
+            }
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// The number of rows and the width of each column when `lengths` are split into `columns`. Every
+/// column but the last possible one includes the two spaces after it, as GNU ls counts them.
+fn column_widths(lengths: &[usize], columns: usize) -> (usize, Vec<usize>) {
+    let rows = lengths.len().div_ceil(columns);
+    let mut widths = vec![0; columns];
+    for (index, length) in lengths.iter().enumerate() {
+        let column = index / rows;
+        let separator = if column == columns - 1 { 0 } else { 2 };
+        widths[column] = widths[column].max(length + separator);
+    }
+    (rows, widths)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn test_grid() {
+        let names = names(&["a", "bb", "ccc", "dddd", "eeeeeeeeee", "f"]);
+        assert_eq!(grid(&names, 80), "a  bb  ccc  dddd  eeeeeeeeee  f\n");
+        assert_eq!(grid(&names, 20), "a    dddd\nbb   eeeeeeeeee\nccc  f\n");
+        assert_eq!(grid(&names, 1), "a\nbb\nccc\ndddd\neeeeeeeeee\nf\n");
+        assert_eq!(grid(&[], 80), "");
+    }
+
+    #[test]
+    fn test_grid_width_is_exclusive() {
+        // 行の長さが幅と同じになる並べ方は使わない
+        let names = names(&["aaaa", "bbbb"]);
+        assert_eq!(grid(&names, 11), "aaaa  bbbb\n");
+        assert_eq!(grid(&names, 10), "aaaa\nbbbb\n");
+    }
+}
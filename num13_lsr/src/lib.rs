@@ -0,0 +1,414 @@
+use std::{
+    cmp::Reverse,
+    env,
+    error::Error,
+    ffi::OsString,
+    fs::{self, Metadata},
+    io::{self, IsTerminal, Write},
+    os::unix::fs::MetadataExt,
+    path::{Path, PathBuf},
+    process::ExitCode,
+    time::SystemTime,
+};
+
+use clap::{ArgAction, Parser, ValueEnum, value_parser};
+use serde::Serialize;
+
+mod grid;
+mod long;
+
+use long::{Details, human_size, long_lines};
+
+type MyResult<T> = Result<T, Box<dyn Error>>;
+
+#[derive(Debug)]
+pub struct Config {
+    paths: Vec<String>,
+    show_hidden: bool,
+    layout: Layout,
+    human: bool,
+    recursive: bool,
+    sort: SortBy,
+    reverse: bool,
+    format: Format,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Layout {
+    Long,
+    SingleColumn,
+    /// Columns fitting in this many characters.
+    Columns(usize),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum SortBy {
+    Name,
+    Time,
+    Size,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+enum Format {
+    Text,
+    Json,
+}
+
+#[derive(Parser, Debug)]
+#[command(version, disable_help_flag = true)]
+#[command(about = "Rust ls")]
+struct Cli {
+    /// Files and/or directories.
+    #[arg(default_value = ".")]
+    paths: Vec<String>,
+
+    /// Show all files, including hidden ones and . and ..
+    #[arg(short, long)]
+    all: bool,
+
+    /// Long listing.
+    #[arg(short, long, overrides_with_all = ["single_column", "columns"])]
+    long: bool,
+
+    /// With -l, print sizes like 1.5K and 234M.
+    #[arg(short('h'), long)]
+    human_readable: bool,
+
+    /// List subdirectories recursively.
+    #[arg(short('R'), long)]
+    recursive: bool,
+
+    /// Sort by modification time, newest first.
+    #[arg(short('t'), overrides_with = "sort_size")]
+    sort_time: bool,
+
+    /// Sort by file size, largest first.
+    #[arg(short('S'), overrides_with = "sort_time")]
+    sort_size: bool,
+
+    /// Reverse order while sorting.
+    #[arg(short, long)]
+    reverse: bool,
+
+    /// List one file per line.
+    #[arg(short('1'), overrides_with_all = ["long", "columns"])]
+    single_column: bool,
+
+    /// List entries by columns, even when not writing to a terminal.
+    #[arg(short('C'), overrides_with_all = ["long", "single_column"])]
+    columns: bool,
+
+    /// Fit columns in COLS characters instead of the terminal width.
+    #[arg(
+        short,
+        long,
+        value_name = "COLS",
+        value_parser = value_parser!(u16).range(1..)
+    )]
+    width: Option<u16>,
+
+    /// Output format.
+    #[arg(long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+
+    /// Print help.
+    #[arg(long, action = ArgAction::Help)]
+    help: Option<bool>,
+}
+
+pub fn get_args() -> MyResult<Config> {
+    get_args_from(env::args_os())
+}
+
+pub fn get_args_from<I, T>(args: I) -> MyResult<Config>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<OsString> + Clone,
+{
+    let args = Cli::parse_from(args);
+    let layout = if args.long {
+        Layout::Long
+    } else if args.single_column {
+        Layout::SingleColumn
+    } else if args.columns || io::stdout().is_terminal() {
+        Layout::Columns(args.width.map_or_else(terminal_width, usize::from))
+    } else {
+        Layout::SingleColumn
+    };
+    let sort = match (args.sort_time, args.sort_size) {
+        (true, _) => SortBy::Time,
+        (_, true) => SortBy::Size,
+        _ => SortBy::Name,
+    };
+
+    Ok(Config {
+        paths: args.paths,
+        show_hidden: args.all,
+        layout,
+        human: args.human_readable,
+        recursive: args.recursive,
+        sort,
+        reverse: args.reverse,
+        format: args.format,
+    })
+}
+
+/// The width of the terminal, or of `$COLUMNS` when not writing to one.
+fn terminal_width() -> usize {
+    if let Some((terminal_size::Width(width), _)) = terminal_size::terminal_size() {
+        return usize::from(width);
+    }
+    env::var("COLUMNS")
+        .ok()
+        .and_then(|columns| columns.parse().ok())
+        .filter(|&columns| columns > 0)
+        .unwrap_or(80)
+}
+
+/// List the paths. As with GNU ls, the exit status is 2 if an operand is missing or a directory
+/// cannot be read, after listing everything else.
+pub fn run(config: Config) -> MyResult<ExitCode> {
+    let mut failed = false;
+    let mut files = vec![];
+    let mut dirs = vec![];
+    for path in &config.paths {
+        match Entry::operand(path, config.layout) {
+            Err(err) => {
+                eprintln!("{}: {}", path, err);
+                failed = true;
+            }
+            Ok(entry) if entry.metadata.is_dir() => dirs.push(entry),
+            Ok(entry) => files.push(entry),
+        }
+    }
+    sort_entries(&mut files, &config);
+    sort_entries(&mut dirs, &config);
+
+    // 複数のパスを指定した場合と -R の場合は、ディレクトリの名前を見出しにする
+    let show_headers = config.recursive || config.paths.len() > 1;
+    let mut sections = vec![];
+    if !files.is_empty() {
+        sections.push(Section {
+            header: None,
+            entries: files,
+            is_dir: false,
+        });
+    }
+    for dir in dirs {
+        let header = show_headers.then(|| dir.name.clone());
+        if !list_dir(&dir.path, header, &config, &mut sections) {
+            failed = true;
+        }
+    }
+
+    let mut out = io::stdout().lock();
+    match config.format {
+        Format::Text => {
+            for (index, section) in sections.iter().enumerate() {
+                if index > 0 {
+                    writeln!(out)?;
+                }
+                write!(out, "{}", format_section(section, &config))?;
+            }
+        }
+        Format::Json => {
+            let records: Vec<EntryRecord> = sections
+                .iter()
+                .flat_map(|section| &section.entries)
+                .map(EntryRecord::new)
+                .collect();
+            writeln!(out, "{}", serde_json::to_string(&records)?)?;
+        }
+    }
+    Ok(if failed {
+        ExitCode::from(2)
+    } else {
+        ExitCode::SUCCESS
+    })
+}
+
+/// A file or directory entry to list.
+#[derive(Debug)]
+struct Entry {
+    /// The name as listed: the argument itself for operands, the file name within a directory.
+    name: String,
+    path: PathBuf,
+    metadata: Metadata,
+}
+
+impl Entry {
+    /// A path given on the command line. Symbolic links are followed unless listed with `-l`.
+    fn operand(path: &str, layout: Layout) -> io::Result<Self> {
+        let mut metadata = fs::symlink_metadata(path)?;
+        if metadata.is_symlink() && layout != Layout::Long {
+            // リンク切れの場合はリンク自体を表示する
+            metadata = fs::metadata(path).unwrap_or(metadata);
+        }
+        Ok(Entry {
+            name: path.to_string(),
+            path: PathBuf::from(path),
+            metadata,
+        })
+    }
+
+    fn new(name: &str, path: PathBuf) -> io::Result<Self> {
+        Ok(Entry {
+            name: name.to_string(),
+            metadata: fs::symlink_metadata(&path)?,
+            path,
+        })
+    }
+
+    fn modified(&self) -> SystemTime {
+        self.metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH)
+    }
+}
+
+/// The entries listed together, under a `dir:` header when there is one.
+#[derive(Debug)]
+struct Section {
+    header: Option<String>,
+    entries: Vec<Entry>,
+    is_dir: bool,
+}
+
+/// Add a section for the directory at `path`, and with `-R` for those below it. Returns whether
+/// every directory could be read.
+fn list_dir(
+    path: &Path,
+    header: Option<String>,
+    config: &Config,
+    sections: &mut Vec<Section>,
+) -> bool {
+    let mut entries = vec![];
+    if config.show_hidden {
+        for name in [".", ".."] {
+            match Entry::new(name, path.join(name)) {
+                Err(err) => eprintln!("{}: {}", path.join(name).display(), err),
+                Ok(entry) => entries.push(entry),
+            }
+        }
+    }
+    let read_dir = match fs::read_dir(path) {
+        Err(err) => {
+            eprintln!("{}: {}", path.display(), err);
+            return false;
+        }
+        Ok(read_dir) => read_dir,
+    };
+    for dir_entry in read_dir {
+        let result = dir_entry.and_then(|dir_entry| {
+            let name = dir_entry.file_name().to_string_lossy().into_owned();
+            Entry::new(&name, dir_entry.path())
+        });
+        match result {
+            Err(err) => eprintln!("{}: {}", path.display(), err),
+            Ok(entry) if entry.name.starts_with('.') && !config.show_hidden => {}
+            Ok(entry) => entries.push(entry),
+        }
+    }
+    sort_entries(&mut entries, config);
+
+    let subdirs: Vec<PathBuf> = match config.recursive {
+        true => entries
+            .iter()
+            .filter(|entry| entry.metadata.is_dir() && entry.name != "." && entry.name != "..")
+            .map(|entry| entry.path.clone())
+            .collect(),
+        false => vec![],
+    };
+    sections.push(Section {
+        header,
+        entries,
+        is_dir: true,
+    });
+    let mut read_all = true;
+    for subdir in subdirs {
+        let header = subdir.display().to_string();
+        read_all &= list_dir(&subdir, Some(header), config, sections);
+    }
+    read_all
+}
+
+fn sort_entries(entries: &mut [Entry], config: &Config) {
+    // 名前はバイト順に比べ、時刻やサイズが同じものは名前の順にする
+    entries.sort_by(|a, b| a.name.cmp(&b.name));
+    match config.sort {
+        SortBy::Name => {}
+        SortBy::Time => entries.sort_by_key(|entry| Reverse(entry.modified())),
+        SortBy::Size => entries.sort_by_key(|entry| Reverse(entry.metadata.len())),
+    }
+    if config.reverse {
+        entries.reverse();
+    }
+}
+
+fn format_section(section: &Section, config: &Config) -> String {
+    let mut out = String::new();
+    if let Some(header) = &section.header {
+        out.push_str(&format!("{}:\n", header));
+    }
+    let names: Vec<String> = section
+        .entries
+        .iter()
+        .map(|entry| entry.name.clone())
+        .collect();
+    match config.layout {
+        Layout::Long => {
+            if section.is_dir {
+                // st_blocks は 512 バイト単位なので、1K 単位に切り上げて合計する
+                let blocks: u64 = section
+                    .entries
+                    .iter()
+                    .map(|entry| entry.metadata.blocks())
+                    .sum();
+                let total = match config.human {
+                    true => human_size(blocks * 512),
+                    false => blocks.div_ceil(2).to_string(),
+                };
+                out.push_str(&format!("total {}\n", total));
+            }
+            let entries: Vec<(String, Details)> = section
+                .entries
+                .iter()
+                .map(|entry| {
+                    (
+                        entry.name.clone(),
+                        Details::new(&entry.path, &entry.metadata),
+                    )
+                })
+                .collect();
+            for line in long_lines(&entries, config.human) {
+                out.push_str(&line);
+                out.push('\n');
+            }
+        }
+        Layout::SingleColumn => {
+            for name in names {
+                out.push_str(&name);
+                out.push('\n');
+            }
+        }
+        Layout::Columns(width) => out.push_str(&grid::grid(&names, width)),
+    }
+    out
+}
+
+/// An entry as written by `--format json`.
+#[derive(Debug, Serialize)]
+struct EntryRecord {
+    path: String,
+    name: String,
+    #[serde(flatten)]
+    details: Details,
+}
+
+impl EntryRecord {
+    fn new(entry: &Entry) -> Self {
+        EntryRecord {
+            path: entry.path.display().to_string(),
+            name: entry.name.clone(),
+            details: Details::new(&entry.path, &entry.metadata),
+        }
+    }
+}
@@ -0,0 +1,236 @@
+use std::{
+    fs::{self, FileType, Metadata},
+    os::unix::fs::{FileTypeExt, MetadataExt, PermissionsExt},
+    path::Path,
+};
+
+use chrono::{DateTime, Duration, Local};
+use serde::Serialize;
+use uzers::{get_group_by_gid, get_user_by_uid};
+
+/// What `-l` and `--format json` show about an entry.
+#[derive(Debug, Serialize)]
+pub(crate) struct Details {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    permissions: String,
+    links: u64,
+    owner: String,
+    group: String,
+    size: u64,
+    modified: DateTime<Local>,
+    target: Option<String>,
+}
+
+impl Details {
+    pub(crate) fn new(path: &Path, metadata: &Metadata) -> Self {
+        let owner = get_user_by_uid(metadata.uid())
+            .map(|user| user.name().to_string_lossy().into_owned())
+            .unwrap_or_else(|| metadata.uid().to_string());
+        let group = get_group_by_gid(metadata.gid())
+            .map(|group| group.name().to_string_lossy().into_owned())
+            .unwrap_or_else(|| metadata.gid().to_string());
+        let target = match metadata.is_symlink() {
+            true => fs::read_link(path)
+                .ok()
+                .map(|target| target.display().to_string()),
+            false => None,
+        };
+        let file_type = metadata.file_type();
+
+        Details {
+            kind: kind(&file_type),
+            permissions: format!(
+                "{}{}",
+                type_char(&file_type),
+                format_mode(metadata.permissions().mode())
+            ),
+            links: metadata.nlink(),
+            owner,
+            group,
+            size: metadata.len(),
+            modified: DateTime::from(metadata.modified().unwrap_or(std::time::UNIX_EPOCH)),
+            target,
+        }
+    }
+}
+
+fn kind(file_type: &FileType) -> &'static str {
+    if file_type.is_dir() {
+        "directory"
+    } else if file_type.is_symlink() {
+        "symlink"
+    } else if file_type.is_file() {
+        "file"
+    } else {
+        "other"
+    }
+}
+
+fn type_char(file_type: &FileType) -> char {
+    if file_type.is_dir() {
+        'd'
+    } else if file_type.is_symlink() {
+        'l'
+    } else if file_type.is_fifo() {
+        'p'
+    } else if file_type.is_socket() {
+        's'
+    } else if file_type.is_char_device() {
+        'c'
+    } else if file_type.is_block_device() {
+        'b'
+    } else {
+        '-'
+    }
+}
+
+/// Format the permission bits of `mode` like `rwxr-sr-t`.
+pub(crate) fn format_mode(mode: u32) -> String {
+    // 所有者、グループ、その他の順に、特殊なビットは実行権の位置に表示する
+    let classes = [
+        (0o700, 6, 0o4000, 's'),
+        (0o070, 3, 0o2000, 's'),
+        (0o007, 0, 0o1000, 't'),
+    ];
+    let mut out = String::new();
+    for (mask, shift, special, letter) in classes {
+        let bits = (mode & mask) >> shift;
+        out.push(if bits & 0o4 != 0 { 'r' } else { '-' });
+        out.push(if bits & 0o2 != 0 { 'w' } else { '-' });
+        out.push(match (bits & 0o1 != 0, mode & special != 0) {
+            (true, true) => letter,
+            (false, true) => letter.to_ascii_uppercase(),
+            (true, false) => 'x',
+            (false, false) => '-',
+        });
+    }
+    out
+}
+
+/// Format `size` bytes like `ls -h`, rounding up to one decimal below 10 and to an integer above.
+pub(crate) fn human_size(size: u64) -> String {
+    const UNITS: [&str; 6] = ["K", "M", "G", "T", "P", "E"];
+    if size < 1024 {
+        return size.to_string();
+    }
+    let mut value = size as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    let tenths = (value * 10.0).ceil() / 10.0;
+    if tenths < 10.0 {
+        return format!("{:.1}{}", tenths, UNITS[unit]);
+    }
+    let value = value.ceil();
+    // 切り上げで 1024 に達したら次の単位にする
+    if value >= 1024.0 && unit < UNITS.len() - 1 {
+        return format!("1.0{}", UNITS[unit + 1]);
+    }
+    format!("{}{}", value, UNITS[unit])
+}
+
+/// Format the modification time like GNU ls: the time of day for the past six months, the year
+/// for anything older or in the future.
+fn format_time(modified: DateTime<Local>, now: DateTime<Local>) -> String {
+    let six_months_ago = now - Duration::seconds(31_556_952 / 2);
+    if six_months_ago < modified && modified <= now {
+        modified.format("%b %e %H:%M").to_string()
+    } else {
+        modified.format("%b %e  %Y").to_string()
+    }
+}
+
+/// The lines of a long listing, with each column as wide as its widest value.
+pub(crate) fn long_lines(entries: &[(String, Details)], human: bool) -> Vec<String> {
+    let now = Local::now();
+    let sizes: Vec<String> = entries
+        .iter()
+        .map(|(_, details)| match human {
+            true => human_size(details.size),
+            false => details.size.to_string(),
+        })
+        .collect();
+    let max_width = |widths: &mut dyn Iterator<Item = usize>| widths.max().unwrap_or(0);
+    let links_width = max_width(
+        &mut entries
+            .iter()
+            .map(|(_, details)| details.links.to_string().len()),
+    );
+    let owner_width = max_width(
+        &mut entries
+            .iter()
+            .map(|(_, details)| details.owner.chars().count()),
+    );
+    let group_width = max_width(
+        &mut entries
+            .iter()
+            .map(|(_, details)| details.group.chars().count()),
+    );
+    let size_width = max_width(&mut sizes.iter().map(String::len));
+
+    entries
+        .iter()
+        .zip(sizes)
+        .map(|((name, details), size)| {
+            let mut line = format!(
+                "{} {:>links_width$} {:<owner_width$} {:<group_width$} {:>size_width$} {} {}",
+                details.permissions,
+                details.links,
+                details.owner,
+                details.group,
+                size,
+                format_time(details.modified, now),
+                name,
+            );
+            if let Some(target) = &details.target {
+                line.push_str(" -> ");
+                line.push_str(target);
+            }
+            line
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_format_mode() {
+        assert_eq!(format_mode(0o755), "rwxr-xr-x");
+        assert_eq!(format_mode(0o640), "rw-r-----");
+        assert_eq!(format_mode(0o4755), "rwsr-xr-x");
+        assert_eq!(format_mode(0o2745), "rwxr-Sr-x");
+        assert_eq!(format_mode(0o1777), "rwxrwxrwt");
+        assert_eq!(format_mode(0o1776), "rwxrwxrwT");
+    }
+
+    #[test]
+    fn test_human_size() {
+        assert_eq!(human_size(0), "0");
+        assert_eq!(human_size(1023), "1023");
+        assert_eq!(human_size(1024), "1.0K");
+        assert_eq!(human_size(1500), "1.5K");
+        assert_eq!(human_size(3000), "3.0K");
+        assert_eq!(human_size(10 * 1024 - 1), "10K");
+        assert_eq!(human_size(123 * 1024 + 1), "124K");
+        assert_eq!(human_size(1024 * 1024 - 1), "1.0M");
+        assert_eq!(human_size(5 * 1024 * 1024 * 1024), "5.0G");
+    }
+
+    #[test]
+    fn test_format_time() {
+        let now = Local.with_ymd_and_hms(2024, 6, 15, 12, 0, 0).unwrap();
+        let recent = Local.with_ymd_and_hms(2024, 3, 5, 9, 7, 0).unwrap();
+        let old = Local.with_ymd_and_hms(2023, 11, 20, 9, 7, 0).unwrap();
+        let future = Local.with_ymd_and_hms(2024, 6, 16, 9, 7, 0).unwrap();
+        assert_eq!(format_time(recent, now), "Mar  5 09:07");
+        assert_eq!(format_time(old, now), "Nov 20  2023");
+        assert_eq!(format_time(future, now), "Jun 16  2024");
+    }
+}
@@ -0,0 +1,312 @@
+use anyhow::Result;
+use assert_cmd::Command;
+use predicates::prelude::*;
+use pretty_assertions::assert_eq;
+use rand::{distr::Alphanumeric, Rng};
+use std::{
+    fs::{self, File},
+    os::unix::fs::{symlink, PermissionsExt},
+    path::Path,
+    time::{Duration, SystemTime},
+};
+
+const PRG: &str = "num13_lsr";
+const ROOT: &str = "tests/inputs";
+const COLS: &str = "tests/inputs/cols";
+const DIR: &str = "tests/inputs/dir";
+const FILES: &[&str] = &[
+    "tests/inputs/bustle.txt",
+    "tests/inputs/empty.txt",
+    "tests/inputs/fox.txt",
+    "tests/inputs/link.txt",
+    "tests/inputs/dir/spiders.txt",
+];
+
+// --------------------------------------------------
+fn gen_bad_file() -> String {
+    loop {
+        let filename: String = rand::rng()
+            .sample_iter(&Alphanumeric)
+            .take(7)
+            .map(char::from)
+            .collect();
+
+        if fs::metadata(&filename).is_err() {
+            return filename;
+        }
+    }
+}
+
+// --------------------------------------------------
+#[test]
+fn skips_bad_file() -> Result<()> {
+    let bad = gen_bad_file();
+    let expected = format!("{bad}: .* [(]os error 2[)]");
+    Command::cargo_bin(PRG)?
+        .args([&bad, "tests/inputs/fox.txt"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::is_match(expected)?)
+        .stdout("tests/inputs/fox.txt\n");
+
+    // JSON でも一部しか出力できなかったことが終了ステータスでわかる
+    Command::cargo_bin(PRG)?
+        .args(["--format", "json", &bad, DIR])
+        .assert()
+        .code(2)
+        .stdout(predicate::str::contains("\"name\":\"spiders.txt\""));
+    Ok(())
+}
+
+// --------------------------------------------------
+fn run(args: &[&str], expected_file: &str) -> Result<()> {
+    let expected = fs::read_to_string(expected_file)?;
+    let output = Command::cargo_bin(PRG)?.args(args).output()?;
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout)?, expected, "{args:?}");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn expected() -> Result<()> {
+    let cases: &[(&[&str], &str)] = &[
+        (&[ROOT], "inputs"),
+        (&["-a", ROOT], "inputs.a"),
+        (&["--reverse", ROOT], "inputs.r"),
+        (&["-R", ROOT], "inputs.R"),
+        (&["--recursive", "--all", DIR], "dir.R.a"),
+        (&["tests/inputs/fox.txt"], "fox"),
+        (
+            &["tests/inputs/link.txt", "tests/inputs/bustle.txt"],
+            "link_bustle",
+        ),
+        (
+            &[DIR, "tests/inputs/fox.txt", "tests/inputs/empty.txt"],
+            "dir_fox_empty",
+        ),
+        (&[DIR, COLS], "dir_cols"),
+        (&["-C", "-w", "80", COLS], "cols.C80"),
+        (&["-C", "--width", "40", COLS], "cols.C40"),
+        (&["-C", "-w", "20", COLS], "cols.C20"),
+        (&["-C", "-w", "10", COLS], "cols.C10"),
+        (&["-C", "-w", "40", "-r", COLS], "cols.C40.r"),
+        (&["-C", "-w", "30", DIR, COLS], "dir_cols.C30"),
+        // 後から指定したものが優先する
+        (&["-C", "-1", COLS], "cols.1"),
+        (&["-1", "-l", "-C", "-w", "40", COLS], "cols.C40"),
+    ];
+    for (args, name) in cases {
+        run(args, &format!("tests/expected/{name}.out"))?;
+    }
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn sort_size() -> Result<()> {
+    run(&[&["-S"], FILES].concat(), "tests/expected/files.S.out")?;
+    run(
+        &[&["-S", "-r"], FILES].concat(),
+        "tests/expected/files.S.r.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn sort_time() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let now = SystemTime::now();
+    for (name, hours) in [("old", 48), ("new", 1), ("middle", 24), ("same", 24)]
+    {
+        let file = File::create(dir.path().join(name))?;
+        file.set_modified(now - Duration::from_secs(hours * 3600))?;
+    }
+
+    // 時刻が同じものは名前の順にする
+    let dir = dir.path().to_str().unwrap();
+    Command::cargo_bin(PRG)?
+        .args(["-t", dir])
+        .assert()
+        .success()
+        .stdout("new\nmiddle\nsame\nold\n");
+    Command::cargo_bin(PRG)?
+        .args(["-t", "-r", dir])
+        .assert()
+        .success()
+        .stdout("old\nsame\nmiddle\nnew\n");
+    // -S と -t は後から指定したものが優先する
+    Command::cargo_bin(PRG)?
+        .args(["-S", "-t", dir])
+        .assert()
+        .success()
+        .stdout("new\nmiddle\nsame\nold\n");
+    Ok(())
+}
+
+// --------------------------------------------------
+/// Create `a.txt` (5 bytes, mode 640, modified on 2020-01-02), `big.bin`
+/// (3000 bytes) and `b.txt`, a link to `a.txt`.
+fn long_fixture(dir: &Path) -> Result<()> {
+    let file = dir.join("a.txt");
+    fs::write(&file, "hello")?;
+    fs::set_permissions(&file, fs::Permissions::from_mode(0o640))?;
+    File::options().write(true).open(&file)?.set_modified(
+        SystemTime::UNIX_EPOCH + Duration::from_secs(1_577_934_245),
+    )?;
+    File::create(dir.join("big.bin"))?.set_len(3000)?;
+    symlink("a.txt", dir.join("b.txt"))?;
+    Ok(())
+}
+
+fn user_and_group() -> (String, String) {
+    let user = uzers::get_effective_username().unwrap();
+    let group = uzers::get_effective_groupname().unwrap();
+    (
+        user.to_string_lossy().into_owned(),
+        group.to_string_lossy().into_owned(),
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn long() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    long_fixture(dir.path())?;
+    let (user, group) = user_and_group();
+
+    // 半年より前の時刻は年を表示する
+    let file = dir.path().join("a.txt");
+    Command::cargo_bin(PRG)?
+        .env("TZ", "UTC")
+        .args(["-l", file.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(format!(
+            "-rw-r----- 1 {user} {group} 5 Jan  2  2020 {}\n",
+            file.display()
+        ));
+
+    let output = Command::cargo_bin(PRG)?
+        .env("TZ", "UTC")
+        .args(["-l", dir.path().to_str().unwrap()])
+        .output()?;
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout)?;
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines.len(), 4);
+    assert!(lines[0].starts_with("total "));
+    assert_eq!(
+        lines[1],
+        format!("-rw-r----- 1 {user} {group}    5 Jan  2  2020 a.txt")
+    );
+    let link = format!(
+        r"^lrwxrwxrwx 1 {user} {group}    5 \w{{3}} [ \d]\d \d\d:\d\d b\.txt -> a\.txt$"
+    );
+    assert!(
+        predicate::str::is_match(link)?.eval(lines[2]),
+        "{}",
+        lines[2]
+    );
+    assert!(lines[3].contains(" 3000 ") && lines[3].ends_with(" big.bin"));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn long_human() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    long_fixture(dir.path())?;
+
+    let output = Command::cargo_bin(PRG)?
+        .args(["-l", "-h", "-S", dir.path().to_str().unwrap()])
+        .output()?;
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout)?;
+    let sizes: Vec<&str> = stdout
+        .lines()
+        .skip(1)
+        .map(|line| line.split_whitespace().nth(4).unwrap_or_default())
+        .collect();
+    assert_eq!(sizes, ["3.0K", "5", "5"]);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn json() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    long_fixture(dir.path())?;
+    let (user, group) = user_and_group();
+
+    let output = Command::cargo_bin(PRG)?
+        .env("TZ", "UTC")
+        .args(["--format", "json", dir.path().to_str().unwrap()])
+        .output()?;
+    assert!(output.status.success());
+    let records: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    let records = records.as_array().unwrap();
+    let names: Vec<&str> = records
+        .iter()
+        .map(|record| record["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, ["a.txt", "b.txt", "big.bin"]);
+
+    let path = dir.path().join("a.txt");
+    assert_eq!(
+        records[0],
+        serde_json::json!({
+            "path": path.to_str().unwrap(),
+            "name": "a.txt",
+            "type": "file",
+            "permissions": "-rw-r-----",
+            "links": 1,
+            "owner": user,
+            "group": group,
+            "size": 5,
+            "modified": "2020-01-02T03:04:05Z",
+            "target": null,
+        })
+    );
+    assert_eq!(records[1]["type"], "symlink");
+    assert_eq!(records[1]["target"], "a.txt");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn json_recursive() -> Result<()> {
+    let output = Command::cargo_bin(PRG)?
+        .args(["--format=json", "-R", "-a", DIR])
+        .output()?;
+    assert!(output.status.success());
+    let records: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    let paths: Vec<&str> = records
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|record| record["path"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        paths,
+        [
+            "tests/inputs/dir/.",
+            "tests/inputs/dir/..",
+            "tests/inputs/dir/.gitkeep",
+            "tests/inputs/dir/spiders.txt",
+        ]
+    );
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn help() -> Result<()> {
+    // -h は -l と組み合わせるサイズの表示に使うので、ヘルプは長いオプションだけ
+    Command::cargo_bin(PRG)?
+        .arg("--help")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("--human-readable"));
+    Ok(())
+}
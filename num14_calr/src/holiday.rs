@@ -0,0 +1,290 @@
+use std::collections::BTreeMap;
+
+use chrono::{Datelike, Days, NaiveDate, Weekday};
+
+use crate::Lang;
+
+/// A Japanese public holiday.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct Holiday {
+    pub(crate) date: NaiveDate,
+    ja: &'static str,
+    en: &'static str,
+}
+
+impl Holiday {
+    pub(crate) fn name(&self, lang: Lang) -> &'static str {
+        match lang {
+            Lang::En => self.en,
+            Lang::Ja => self.ja,
+        }
+    }
+}
+
+/// The first year fully under the 1948 act on national holidays.
+const FIRST_YEAR: i32 = 1949;
+
+/// The last year the equinox approximation holds for.
+const LAST_YEAR: i32 = 2150;
+
+/// Days made holidays by their own acts rather than by the holiday act.
+const SPECIAL_DAYS: [(i32, u32, u32, &str, &str); 6] = [
+    (
+        1959,
+        4,
+        10,
+        "皇太子明仁親王の結婚の儀",
+        "Wedding of Crown Prince Akihito",
+    ),
+    (
+        1989,
+        2,
+        24,
+        "昭和天皇の大喪の礼",
+        "Funeral of Emperor Showa",
+    ),
+    (1990, 11, 12, "即位礼正殿の儀", "Enthronement Ceremony"),
+    (
+        1993,
+        6,
+        9,
+        "皇太子徳仁親王の結婚の儀",
+        "Wedding of Crown Prince Naruhito",
+    ),
+    (2019, 5, 1, "天皇の即位の日", "Enthronement Day"),
+    (2019, 10, 22, "即位礼正殿の儀", "Enthronement Ceremony"),
+];
+
+/// The public holidays of `year` in date order, computed from the rules of the holiday act,
+/// including substitute holidays and days between two holidays. Years outside 1949 to 2150 have
+/// none.
+pub(crate) fn holidays(year: i32) -> Vec<Holiday> {
+    if !(FIRST_YEAR..=LAST_YEAR).contains(&year) {
+        return vec![];
+    }
+    let national = national_holidays(year);
+    let mut days: BTreeMap<NaiveDate, Holiday> = national
+        .iter()
+        .map(|holiday| (holiday.date, *holiday))
+        .collect();
+
+    // 振替休日: 1973 年 4 月 12 日から。2007 年からは次の祝日でない日まで繰り下げる
+    let sundays: Vec<NaiveDate> = days
+        .keys()
+        .copied()
+        .filter(|date| date.weekday() == Weekday::Sun)
+        .filter(|&date| date >= ymd(1973, 4, 12))
+        .collect();
+    for sunday in sundays {
+        let mut date = next_day(sunday);
+        while year >= 2007 && days.contains_key(&date) {
+            date = next_day(date);
+        }
+        if !days.contains_key(&date) && date.year() == year {
+            days.insert(date, holiday(date, "振替休日", "Substitute Holiday"));
+        }
+    }
+
+    // 国民の休日: 1985 年 12 月 27 日から。2007 年までは日曜日を除く
+    for pair in national.windows(2) {
+        let between = next_day(pair[0].date);
+        if next_day(between) == pair[1].date
+            && between >= ymd(1985, 12, 27)
+            && !days.contains_key(&between)
+            && (year >= 2007 || between.weekday() != Weekday::Sun)
+        {
+            days.insert(between, holiday(between, "国民の休日", "Citizens' Holiday"));
+        }
+    }
+    days.into_values().collect()
+}
+
+/// The holidays named in the holiday act and the special acts, in date order.
+fn national_holidays(year: i32) -> Vec<Holiday> {
+    let mut days = vec![];
+    let mut add = |date: NaiveDate, ja, en| days.push(holiday(date, ja, en));
+
+    add(ymd(year, 1, 1), "元日", "New Year's Day");
+    add(
+        match year {
+            ..2000 => ymd(year, 1, 15),
+            _ => nth_monday(year, 1, 2),
+        },
+        "成人の日",
+        "Coming of Age Day",
+    );
+    if year >= 1967 {
+        add(ymd(year, 2, 11), "建国記念の日", "National Foundation Day");
+    }
+    match year {
+        ..1989 => add(ymd(year, 4, 29), "天皇誕生日", "Emperor's Birthday"),
+        1989..=2018 => add(ymd(year, 12, 23), "天皇誕生日", "Emperor's Birthday"),
+        2019 => {}
+        _ => add(ymd(year, 2, 23), "天皇誕生日", "Emperor's Birthday"),
+    }
+    add(
+        ymd(year, 3, equinox(year, [20.8357, 20.8431, 21.8510])),
+        "春分の日",
+        "Vernal Equinox Day",
+    );
+    match year {
+        ..1989 => {}
+        1989..=2006 => add(ymd(year, 4, 29), "みどりの日", "Greenery Day"),
+        _ => {
+            add(ymd(year, 4, 29), "昭和の日", "Showa Day");
+            add(ymd(year, 5, 4), "みどりの日", "Greenery Day");
+        }
+    }
+    add(ymd(year, 5, 3), "憲法記念日", "Constitution Memorial Day");
+    add(ymd(year, 5, 5), "こどもの日", "Children's Day");
+    // 2020 年と 2021 年は東京オリンピックのために移された
+    let marine_day = match year {
+        ..1996 => None,
+        1996..=2002 => Some(ymd(year, 7, 20)),
+        2020 => Some(ymd(year, 7, 23)),
+        2021 => Some(ymd(year, 7, 22)),
+        _ => Some(nth_monday(year, 7, 3)),
+    };
+    if let Some(date) = marine_day {
+        add(date, "海の日", "Marine Day");
+    }
+    let mountain_day = match year {
+        ..2016 => None,
+        2020 => Some(ymd(year, 8, 10)),
+        2021 => Some(ymd(year, 8, 8)),
+        _ => Some(ymd(year, 8, 11)),
+    };
+    if let Some(date) = mountain_day {
+        add(date, "山の日", "Mountain Day");
+    }
+    match year {
+        ..1966 => {}
+        1966..=2002 => add(ymd(year, 9, 15), "敬老の日", "Respect for the Aged Day"),
+        _ => add(
+            nth_monday(year, 9, 3),
+            "敬老の日",
+            "Respect for the Aged Day",
+        ),
+    }
+    add(
+        ymd(year, 9, equinox(year, [23.2588, 23.2488, 24.2488])),
+        "秋分の日",
+        "Autumnal Equinox Day",
+    );
+    match year {
+        ..1966 => {}
+        1966..=1999 => add(ymd(year, 10, 10), "体育の日", "Health and Sports Day"),
+        2000..=2019 => add(nth_monday(year, 10, 2), "体育の日", "Health and Sports Day"),
+        2020 => add(ymd(year, 7, 24), "スポーツの日", "Sports Day"),
+        2021 => add(ymd(year, 7, 23), "スポーツの日", "Sports Day"),
+        _ => add(nth_monday(year, 10, 2), "スポーツの日", "Sports Day"),
+    }
+    add(ymd(year, 11, 3), "文化の日", "Culture Day");
+    add(ymd(year, 11, 23), "勤労感謝の日", "Labor Thanksgiving Day");
+    for (special_year, month, day, ja, en) in SPECIAL_DAYS {
+        if special_year == year {
+            add(ymd(year, month, day), ja, en);
+        }
+    }
+
+    days.sort_by_key(|holiday| holiday.date);
+    days
+}
+
+/// The day of the equinox in `year`, by the usual approximation of the mean equinox with the
+/// constants for before 1980, until 2099 and after.
+fn equinox(year: i32, constants: [f64; 3]) -> u32 {
+    let (constant, leap_base) = match year {
+        ..1980 => (constants[0], 1983),
+        1980..=2099 => (constants[1], 1980),
+        _ => (constants[2], 1980),
+    };
+    let years = f64::from(year - 1980);
+    // 閏年の補正は 0 に向かって切り捨てる
+    let leaps = f64::from((year - leap_base) / 4);
+    (constant + 0.242194 * years - leaps).floor() as u32
+}
+
+fn holiday(date: NaiveDate, ja: &'static str, en: &'static str) -> Holiday {
+    Holiday { date, ja, en }
+}
+
+fn ymd(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).expect("valid date")
+}
+
+fn nth_monday(year: i32, month: u32, n: u8) -> NaiveDate {
+    NaiveDate::from_weekday_of_month_opt(year, month, Weekday::Mon, n).expect("valid date")
+}
+
+fn next_day(date: NaiveDate) -> NaiveDate {
+    date + Days::new(1)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn dates(year: i32) -> Vec<String> {
+        holidays(year)
+            .iter()
+            .map(|holiday| holiday.date.format("%m-%d").to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_holidays() {
+        assert_eq!(
+            dates(2020),
+            [
+                "01-01", "01-13", "02-11", "02-23", "02-24", "03-20", "04-29", "05-03", "05-04",
+                "05-05", "05-06", "07-23", "07-24", "08-10", "09-21", "09-22", "11-03", "11-23",
+            ]
+        );
+        // 即位の日の前後が国民の休日になる
+        assert_eq!(
+            dates(2019),
+            [
+                "01-01", "01-14", "02-11", "03-21", "04-29", "04-30", "05-01", "05-02", "05-03",
+                "05-04", "05-05", "05-06", "07-15", "08-11", "08-12", "09-16", "09-23", "10-14",
+                "10-22", "11-03", "11-04", "11-23",
+            ]
+        );
+        assert_eq!(holidays(2019)[5].name(Lang::Ja), "国民の休日",);
+        assert_eq!(holidays(2019)[11].name(Lang::En), "Substitute Holiday");
+        assert!(holidays(1948).is_empty());
+        assert!(holidays(2151).is_empty());
+    }
+
+    #[test]
+    fn test_substitute_holidays() {
+        // 最初の振替休日
+        assert!(dates(1973).contains(&"04-30".to_string()));
+        assert!(!dates(1972).contains(&"04-30".to_string()));
+        // 2007 年からは祝日が続く場合に繰り下げる
+        assert!(dates(2008).contains(&"05-06".to_string()));
+        assert!(!dates(1997).contains(&"05-06".to_string()));
+    }
+
+    #[test]
+    fn test_citizens_holidays() {
+        assert!(dates(1988).contains(&"05-04".to_string()));
+        // 2007 年までは日曜日を除く
+        assert!(!dates(1986).contains(&"05-04".to_string()));
+        assert_eq!(&dates(2009)[10..13], ["09-21", "09-22", "09-23"]);
+    }
+
+    #[test]
+    fn test_equinox() {
+        let spring = [20.8357, 20.8431, 21.8510];
+        let autumn = [23.2588, 23.2488, 24.2488];
+        assert_eq!(equinox(1960, spring), 20);
+        assert_eq!(equinox(1979, autumn), 24);
+        assert_eq!(equinox(2020, spring), 20);
+        assert_eq!(equinox(2020, autumn), 22);
+        assert_eq!(equinox(2025, spring), 20);
+        assert_eq!(equinox(2025, autumn), 23);
+        assert_eq!(equinox(1979, spring), 21);
+        assert_eq!(equinox(2012, autumn), 22);
+    }
+}
@@ -0,0 +1,470 @@
+use std::{
+    env,
+    error::Error,
+    ffi::OsString,
+    io::{self, IsTerminal},
+};
+
+use chrono::{Datelike, Local, Months, NaiveDate};
+use clap::{Parser, ValueEnum, value_parser};
+
+mod holiday;
+
+use holiday::Holiday;
+
+type MyResult<T> = Result<T, Box<dyn Error>>;
+
+/// The width of a month: seven days of two characters each, a space between them and two spaces
+/// after the month.
+const LINE_WIDTH: usize = 22;
+
+/// The lines of a month: the title, the weekdays and up to six weeks.
+const MONTH_LINES: usize = 8;
+
+const MONTH_NAMES: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+const WEEKDAYS_EN: [&str; 7] = ["Su", "Mo", "Tu", "We", "Th", "Fr", "Sa"];
+const WEEKDAYS_JA: [&str; 7] = ["日", "月", "火", "水", "木", "金", "土"];
+
+// 今日は反転表示、祝日は赤で表示する
+const TODAY_COLOR: &str = "7";
+const HOLIDAY_COLOR: &str = "31";
+
+#[derive(Debug)]
+pub struct Config {
+    view: View,
+    today: NaiveDate,
+    color: bool,
+    monday_first: bool,
+    lang: Lang,
+    holidays: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum View {
+    Month(i32, u32),
+    /// The month before and after as well.
+    ThreeMonths(i32, u32),
+    Year(i32),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+pub(crate) enum Lang {
+    En,
+    Ja,
+}
+
+#[derive(Parser, Debug)]
+#[command(version)]
+#[command(about = "Rust cal")]
+struct Cli {
+    /// Year (1-9999).
+    #[arg(value_parser = value_parser!(i32).range(1..=9999))]
+    year: Option<i32>,
+
+    /// Month name or number (1-12).
+    #[arg(short, value_parser = parse_month)]
+    month: Option<u32>,
+
+    /// Show the whole current year.
+    #[arg(short('y'), long("year"), conflicts_with_all = ["month", "year", "three"])]
+    show_current_year: bool,
+
+    /// Show the previous, current and next months.
+    #[arg(short('3'), long)]
+    three: bool,
+
+    /// Start weeks on Monday.
+    #[arg(short('M'), long)]
+    monday: bool,
+
+    /// Language of month and weekday names [default: from $LC_ALL, $LC_TIME or $LANG].
+    #[arg(long, value_enum)]
+    lang: Option<Lang>,
+
+    /// List Japanese public holidays, and highlight them on a terminal.
+    #[arg(short('H'), long)]
+    holidays: bool,
+}
+
+pub fn get_args() -> MyResult<Config> {
+    get_args_from(env::args_os())
+}
+
+pub fn get_args_from<I, T>(args: I) -> MyResult<Config>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<OsString> + Clone,
+{
+    let args = Cli::parse_from(args);
+    let today = Local::now().date_naive();
+    let view = if args.show_current_year {
+        View::Year(today.year())
+    } else if args.three {
+        View::ThreeMonths(
+            args.year.unwrap_or(today.year()),
+            args.month.unwrap_or(today.month()),
+        )
+    } else {
+        // 年だけを指定した場合はその年全体を表示する
+        match (args.year, args.month) {
+            (Some(year), None) => View::Year(year),
+            (year, month) => {
+                View::Month(year.unwrap_or(today.year()), month.unwrap_or(today.month()))
+            }
+        }
+    };
+
+    Ok(Config {
+        view,
+        today,
+        color: io::stdout().is_terminal(),
+        monday_first: args.monday,
+        lang: args.lang.unwrap_or_else(Lang::from_env),
+        holidays: args.holidays,
+    })
+}
+
+/// Parse a month number, `4月` or a unique prefix of an English month name.
+fn parse_month(month: &str) -> Result<u32, String> {
+    let number = month.strip_suffix('月').unwrap_or(month);
+    match number.parse::<u32>() {
+        Ok(num) if (1..=12).contains(&num) => Ok(num),
+        Ok(_) => Err(format!("month \"{}\" not in the range 1 through 12", month)),
+        Err(_) => {
+            let lower = month.to_lowercase();
+            let matches: Vec<u32> = (1..=12)
+                .filter(|&num| {
+                    MONTH_NAMES[num as usize - 1]
+                        .to_lowercase()
+                        .starts_with(&lower)
+                })
+                .collect();
+            match matches[..] {
+                [num] if !lower.is_empty() => Ok(num),
+                _ => Err(format!("Invalid month \"{}\"", month)),
+            }
+        }
+    }
+}
+
+pub fn run(config: Config) -> MyResult<()> {
+    let months = config.view.months();
+    match config.view {
+        View::Month(year, month) => {
+            for line in format_month(year, month, true, &config) {
+                println!("{}", line);
+            }
+        }
+        View::ThreeMonths(..) => {
+            let months: Vec<Vec<String>> = months
+                .iter()
+                .map(|&(year, month)| format_month(year, month, true, &config))
+                .collect();
+            for row in 0..MONTH_LINES {
+                let line: String = months.iter().map(|lines| lines[row].as_str()).collect();
+                println!("{}", line);
+            }
+        }
+        View::Year(year) => {
+            let title = config.lang.year_title(year);
+            // BSD の cal と同じく 32 桁目に右寄せする
+            let padding = 32_usize.saturating_sub(display_width(&title));
+            println!("{}{}", " ".repeat(padding), title);
+            let months: Vec<Vec<String>> = months
+                .iter()
+                .map(|&(year, month)| format_month(year, month, false, &config))
+                .collect();
+            for (index, quarter) in months.chunks(3).enumerate() {
+                if index > 0 {
+                    println!();
+                }
+                for row in 0..MONTH_LINES {
+                    let line: String = quarter.iter().map(|lines| lines[row].as_str()).collect();
+                    println!("{}", line);
+                }
+            }
+        }
+    }
+
+    if config.holidays {
+        // 表示した月の祝日を日付の順に一覧にする
+        let holidays: Vec<Holiday> = months
+            .iter()
+            .flat_map(|&(year, month)| {
+                holiday::holidays(year)
+                    .into_iter()
+                    .filter(move |holiday| holiday.date.month() == month)
+            })
+            .collect();
+        if !holidays.is_empty() {
+            println!();
+        }
+        for holiday in holidays {
+            println!(
+                "{} {}",
+                holiday.date.format("%Y-%m-%d"),
+                holiday.name(config.lang)
+            );
+        }
+    }
+    Ok(())
+}
+
+impl View {
+    /// The months shown, as years and months.
+    fn months(self) -> Vec<(i32, u32)> {
+        match self {
+            View::Month(year, month) => vec![(year, month)],
+            View::ThreeMonths(year, month) => {
+                let first = first_of_month(year, month);
+                [
+                    first.checked_sub_months(Months::new(1)),
+                    Some(first),
+                    first.checked_add_months(Months::new(1)),
+                ]
+                .into_iter()
+                .flatten()
+                .map(|date| (date.year(), date.month()))
+                .collect()
+            }
+            View::Year(year) => (1..=12).map(|month| (year, month)).collect(),
+        }
+    }
+}
+
+impl Lang {
+    /// Japanese when the first of `$LC_ALL`, `$LC_TIME` and `$LANG` that is set names a Japanese
+    /// locale, English otherwise.
+    fn from_env() -> Self {
+        let locale = ["LC_ALL", "LC_TIME", "LANG"]
+            .iter()
+            .filter_map(|name| env::var(name).ok())
+            .find(|value| !value.is_empty());
+        match locale {
+            Some(locale) if locale.starts_with("ja") => Lang::Ja,
+            _ => Lang::En,
+        }
+    }
+
+    fn month_title(self, year: i32, month: u32, print_year: bool) -> String {
+        match (self, print_year) {
+            (Lang::En, true) => format!("{} {}", MONTH_NAMES[month as usize - 1], year),
+            (Lang::En, false) => MONTH_NAMES[month as usize - 1].to_string(),
+            (Lang::Ja, true) => format!("{}年{}月", year, month),
+            (Lang::Ja, false) => format!("{}月", month),
+        }
+    }
+
+    fn year_title(self, year: i32) -> String {
+        match self {
+            Lang::En => year.to_string(),
+            Lang::Ja => format!("{}年", year),
+        }
+    }
+
+    fn weekdays(self, monday_first: bool) -> String {
+        let mut names = match self {
+            Lang::En => WEEKDAYS_EN,
+            Lang::Ja => WEEKDAYS_JA,
+        };
+        if monday_first {
+            names.rotate_left(1);
+        }
+        names.join(" ")
+    }
+}
+
+/// The lines of a month, each `LINE_WIDTH` columns wide.
+fn format_month(year: i32, month: u32, print_year: bool, config: &Config) -> Vec<String> {
+    let first = first_of_month(year, month);
+    let offset = match config.monday_first {
+        true => first.weekday().num_days_from_monday(),
+        false => first.weekday().num_days_from_sunday(),
+    };
+    let holidays: Vec<NaiveDate> = match config.holidays {
+        true => holiday::holidays(year)
+            .iter()
+            .map(|holiday| holiday.date)
+            .collect(),
+        false => vec![],
+    };
+
+    let mut days = vec!["  ".to_string(); offset as usize];
+    for date in first.iter_days().take_while(|date| date.month() == month) {
+        let day = format!("{:>2}", date.day());
+        let color = if date == config.today {
+            Some(TODAY_COLOR)
+        } else if holidays.contains(&date) {
+            Some(HOLIDAY_COLOR)
+        } else {
+            None
+        };
+        days.push(match color {
+            Some(color) if config.color => format!("\x1b[{}m{}\x1b[m", color, day),
+            _ => day,
+        });
+    }
+
+    let title = config.lang.month_title(year, month, print_year);
+    let mut lines = Vec::with_capacity(MONTH_LINES);
+    lines.push(format!("{}  ", center(&title, LINE_WIDTH - 2)));
+    lines.push(format!("{}  ", config.lang.weekdays(config.monday_first)));
+    for week in days.chunks(7) {
+        // 色の制御文字が幅に数えられないように、空いた日を埋めてから並べる
+        let mut week = week.to_vec();
+        week.resize(7, "  ".to_string());
+        lines.push(format!("{}  ", week.join(" ")));
+    }
+    lines.resize(MONTH_LINES, " ".repeat(LINE_WIDTH));
+    lines
+}
+
+fn first_of_month(year: i32, month: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, 1).expect("valid month")
+}
+
+/// Center `text` in `width` columns, with the odd space on the right.
+fn center(text: &str, width: usize) -> String {
+    let padding = width.saturating_sub(display_width(text));
+    format!(
+        "{}{}{}",
+        " ".repeat(padding / 2),
+        text,
+        " ".repeat(padding - padding / 2)
+    )
+}
+
+/// The columns `text` takes on a terminal. The only characters other than ASCII printed here are
+/// the CJK ones of the Japanese names, which take two.
+fn display_width(text: &str) -> usize {
+    text.chars().map(|c| if c.is_ascii() { 1 } else { 2 }).sum()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn config(monday_first: bool, lang: Lang) -> Config {
+        Config {
+            view: View::Month(2020, 4),
+            today: NaiveDate::from_ymd_opt(2021, 4, 7).unwrap(),
+            color: false,
+            monday_first,
+            lang,
+            holidays: false,
+        }
+    }
+
+    #[test]
+    fn test_parse_month() {
+        assert_eq!(parse_month("1"), Ok(1));
+        assert_eq!(parse_month("12"), Ok(12));
+        assert_eq!(parse_month("jan"), Ok(1));
+        assert_eq!(parse_month("SEPT"), Ok(9));
+        assert_eq!(parse_month("4月"), Ok(4));
+        assert_eq!(
+            parse_month("0"),
+            Err(r#"month "0" not in the range 1 through 12"#.to_string())
+        );
+        assert_eq!(
+            parse_month("13月"),
+            Err(r#"month "13月" not in the range 1 through 12"#.to_string())
+        );
+        // 複数の月に当てはまる場合は受け付けない
+        assert_eq!(parse_month("ju"), Err(r#"Invalid month "ju""#.to_string()));
+        assert_eq!(
+            parse_month("foo"),
+            Err(r#"Invalid month "foo""#.to_string())
+        );
+        assert_eq!(parse_month(""), Err(r#"Invalid month """#.to_string()));
+    }
+
+    #[test]
+    fn test_format_month() {
+        let april = vec![
+            "     April 2020       ",
+            "Su Mo Tu We Th Fr Sa  ",
+            "          1  2  3  4  ",
+            " 5  6  7  8  9 10 11  ",
+            "12 13 14 15 16 17 18  ",
+            "19 20 21 22 23 24 25  ",
+            "26 27 28 29 30        ",
+            "                      ",
+        ];
+        assert_eq!(format_month(2020, 4, true, &config(false, Lang::En)), april);
+
+        let may = vec![
+            "        May           ",
+            "Mo Tu We Th Fr Sa Su  ",
+            "             1  2  3  ",
+            " 4  5  6  7  8  9 10  ",
+            "11 12 13 14 15 16 17  ",
+            "18 19 20 21 22 23 24  ",
+            "25 26 27 28 29 30 31  ",
+            "                      ",
+        ];
+        assert_eq!(format_month(2020, 5, false, &config(true, Lang::En)), may);
+
+        let february = vec![
+            "     2021年2月        ",
+            "日 月 火 水 木 金 土  ",
+            "    1  2  3  4  5  6  ",
+            " 7  8  9 10 11 12 13  ",
+            "14 15 16 17 18 19 20  ",
+            "21 22 23 24 25 26 27  ",
+            "28                    ",
+            "                      ",
+        ];
+        assert_eq!(
+            format_month(2021, 2, true, &config(false, Lang::Ja)),
+            february
+        );
+    }
+
+    #[test]
+    fn test_format_month_color() {
+        // 色を付けても列はそろう
+        let mut config = config(false, Lang::En);
+        config.color = true;
+        config.holidays = true;
+        let lines = format_month(2021, 4, true, &config);
+        assert_eq!(lines[2], "             1  2  3  ");
+        assert_eq!(lines[3], " 4  5  6 \x1b[7m 7\x1b[m  8  9 10  ");
+        assert_eq!(lines[6], "25 26 27 28 \x1b[31m29\x1b[m 30     ");
+    }
+
+    #[test]
+    fn test_months() {
+        assert_eq!(View::Month(2020, 4).months(), [(2020, 4)]);
+        assert_eq!(
+            View::ThreeMonths(2020, 1).months(),
+            [(2019, 12), (2020, 1), (2020, 2)]
+        );
+        assert_eq!(
+            View::ThreeMonths(2020, 12).months(),
+            [(2020, 11), (2020, 12), (2021, 1)]
+        );
+        assert_eq!(View::Year(2020).months().len(), 12);
+    }
+
+    #[test]
+    fn test_center() {
+        assert_eq!(center("May", 20), "        May         ");
+        assert_eq!(center("5月", 20), "        5月         ");
+        assert_eq!(center("2020年12月", 20), "     2020年12月     ");
+    }
+}
@@ -0,0 +1,6 @@
+fn main() {
+    if let Err(e) = num14_calr::get_args().and_then(num14_calr::run) {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+}
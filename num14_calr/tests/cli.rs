@@ -0,0 +1,205 @@
+use anyhow::Result;
+use assert_cmd::Command;
+use chrono::{Datelike, Local};
+use predicates::prelude::*;
+use pretty_assertions::assert_eq;
+use std::fs;
+
+const PRG: &str = "num14_calr";
+
+// --------------------------------------------------
+fn dies(args: &[&str], expected: &str) -> Result<()> {
+    Command::cargo_bin(PRG)?
+        .args(args)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(expected));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_year() -> Result<()> {
+    dies(&["0"], "0 is not in 1..=9999")?;
+    dies(&["10000"], "10000 is not in 1..=9999")?;
+    dies(&["foo"], "invalid digit found in string")
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_month() -> Result<()> {
+    dies(&["-m", "0"], r#"month "0" not in the range 1 through 12"#)?;
+    dies(&["-m", "13"], r#"month "13" not in the range 1 through 12"#)?;
+    dies(&["-m", "foo"], r#"Invalid month "foo""#)?;
+    dies(&["-m", "ma"], r#"Invalid month "ma""#)
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_y_and_others() -> Result<()> {
+    dies(&["-y", "-m", "1"], "cannot be used with")?;
+    dies(&["-y", "2000"], "cannot be used with")?;
+    dies(&["-y", "-3"], "cannot be used with")?;
+    dies(&["--lang", "fr"], "invalid value 'fr'")
+}
+
+// --------------------------------------------------
+fn run(args: &[&str], expected: &str) -> Result<()> {
+    let output = Command::cargo_bin(PRG)?
+        .env("LC_ALL", "C")
+        .args(args)
+        .output()?;
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout)?, expected, "{args:?}");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn month() -> Result<()> {
+    let expected = concat!(
+        "     April 2020       \n",
+        "Su Mo Tu We Th Fr Sa  \n",
+        "          1  2  3  4  \n",
+        " 5  6  7  8  9 10 11  \n",
+        "12 13 14 15 16 17 18  \n",
+        "19 20 21 22 23 24 25  \n",
+        "26 27 28 29 30        \n",
+        "                      \n",
+    );
+    for month in ["4", "apr", "April", "4月"] {
+        run(&["-m", month, "2020"], expected)?;
+    }
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn month_monday_first() -> Result<()> {
+    let expected = concat!(
+        "    August 2021       \n",
+        "Mo Tu We Th Fr Sa Su  \n",
+        "                   1  \n",
+        " 2  3  4  5  6  7  8  \n",
+        " 9 10 11 12 13 14 15  \n",
+        "16 17 18 19 20 21 22  \n",
+        "23 24 25 26 27 28 29  \n",
+        "30 31                 \n",
+    );
+    run(&["-M", "-m", "8", "2021"], expected)?;
+    run(&["--monday", "-m", "aug", "2021"], expected)
+}
+
+// --------------------------------------------------
+#[test]
+fn month_japanese() -> Result<()> {
+    let expected = concat!(
+        "     2020年4月        \n",
+        "日 月 火 水 木 金 土  \n",
+        "          1  2  3  4  \n",
+        " 5  6  7  8  9 10 11  \n",
+        "12 13 14 15 16 17 18  \n",
+        "19 20 21 22 23 24 25  \n",
+        "26 27 28 29 30        \n",
+        "                      \n",
+    );
+    run(&["--lang", "ja", "-m", "4", "2020"], expected)?;
+
+    // 指定がなければロケールの環境変数に従う
+    for (name, value) in [("LC_ALL", "ja_JP.UTF-8"), ("LANG", "ja_JP.UTF-8")] {
+        Command::cargo_bin(PRG)?
+            .env_remove("LC_ALL")
+            .env_remove("LC_TIME")
+            .env(name, value)
+            .args(["-m", "4", "2020"])
+            .assert()
+            .success()
+            .stdout(expected);
+    }
+    Command::cargo_bin(PRG)?
+        .env("LC_ALL", "C")
+        .env("LANG", "ja_JP.UTF-8")
+        .args(["-m", "4", "2020"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("April 2020"));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn default_one_month() -> Result<()> {
+    let output = Command::cargo_bin(PRG)?.env("LC_ALL", "C").output()?;
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout)?;
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines.len(), 8);
+
+    let today = Local::now().date_naive();
+    let title = format!("{} {}", today.format("%B"), today.year());
+    assert_eq!(lines[0].trim(), title);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn current_year() -> Result<()> {
+    let output = Command::cargo_bin(PRG)?
+        .env("LC_ALL", "C")
+        .arg("-y")
+        .output()?;
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout)?;
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines.len(), 36);
+    assert_eq!(lines[0].trim(), Local::now().year().to_string());
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn expected() -> Result<()> {
+    let cases: &[(&[&str], &str)] = &[
+        (&["2020"], "2020"),
+        (&["--lang=ja", "-M", "2021"], "2021.ja.M"),
+        (&["-H", "2019"], "2019.H"),
+        (&["-3", "-m", "1", "2021"], "1-2021.3"),
+        (
+            &["--holidays", "--lang", "ja", "-3", "-m", "5", "2019"],
+            "5-2019.3.ja.H",
+        ),
+    ];
+    for (args, name) in cases {
+        let expected =
+            fs::read_to_string(format!("tests/expected/{name}.out"))?;
+        run(args, &expected)?;
+    }
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn holidays() -> Result<()> {
+    let expected = concat!(
+        "   September 2026     \n",
+        "Su Mo Tu We Th Fr Sa  \n",
+        "       1  2  3  4  5  \n",
+        " 6  7  8  9 10 11 12  \n",
+        "13 14 15 16 17 18 19  \n",
+        "20 21 22 23 24 25 26  \n",
+        "27 28 29 30           \n",
+        "                      \n",
+        "\n",
+        "2026-09-21 Respect for the Aged Day\n",
+        "2026-09-22 Citizens' Holiday\n",
+        "2026-09-23 Autumnal Equinox Day\n",
+    );
+    run(&["-H", "-m", "9", "2026"], expected)?;
+
+    // 祝日のない月は一覧を出さない
+    let output = Command::cargo_bin(PRG)?
+        .args(["-H", "-m", "6", "2026"])
+        .output()?;
+    assert_eq!(String::from_utf8(output.stdout)?.lines().count(), 8);
+    Ok(())
+}
@@ -0,0 +1,248 @@
+use std::{
+    env,
+    error::Error,
+    ffi::{OsStr, OsString},
+    fs::{self, File},
+    io::{BufRead, BufReader},
+    path::PathBuf,
+};
+
+use clap::Parser;
+use rand::{SeedableRng, rngs::StdRng, seq::IndexedRandom};
+use regex::{Regex, RegexBuilder};
+use walkdir::WalkDir;
+
+type MyResult<T> = Result<T, Box<dyn Error>>;
+
+#[derive(Debug)]
+pub struct Config {
+    sources: Vec<String>,
+    pattern: Option<Regex>,
+    seed: Option<u64>,
+}
+
+#[derive(Debug, PartialEq)]
+struct Fortune {
+    /// The file it came from.
+    source: PathBuf,
+    text: String,
+}
+
+#[derive(Parser, Debug)]
+#[command(version)]
+#[command(about = "Rust fortune")]
+struct Cli {
+    /// Input files or directories.
+    #[arg(required = true, value_name = "FILE")]
+    sources: Vec<String>,
+
+    /// Print all fortunes matching PATTERN instead of a random one.
+    #[arg(short('m'), long)]
+    pattern: Option<String>,
+
+    /// Case-insensitive pattern matching.
+    #[arg(short, long)]
+    insensitive: bool,
+
+    /// Random seed.
+    #[arg(short, long)]
+    seed: Option<u64>,
+}
+
+pub fn get_args() -> MyResult<Config> {
+    get_args_from(env::args_os())
+}
+
+pub fn get_args_from<I, T>(args: I) -> MyResult<Config>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<OsString> + Clone,
+{
+    let args = Cli::parse_from(args);
+    let pattern = args
+        .pattern
+        .map(|pattern| {
+            RegexBuilder::new(&pattern)
+                .case_insensitive(args.insensitive)
+                .build()
+                .map_err(|_| format!("Invalid --pattern \"{}\"", pattern))
+        })
+        .transpose()?;
+
+    Ok(Config {
+        sources: args.sources,
+        pattern,
+        seed: args.seed,
+    })
+}
+
+pub fn run(config: Config) -> MyResult<()> {
+    let files = find_files(&config.sources)?;
+    let fortunes = read_fortunes(&files)?;
+
+    match &config.pattern {
+        Some(pattern) => {
+            // 一致した格言を出力し、どのファイルのものかは見つけたときのパスで標準エラーに示す
+            let mut prev_source = None;
+            for fortune in fortunes
+                .iter()
+                .filter(|fortune| pattern.is_match(&fortune.text))
+            {
+                if prev_source != Some(&fortune.source) {
+                    eprintln!("({})\n%", fortune.source.display());
+                    prev_source = Some(&fortune.source);
+                }
+                println!("{}\n%", fortune.text);
+            }
+        }
+        None => match pick_fortune(&fortunes, config.seed) {
+            Some(fortune) => println!("{}", fortune),
+            None => println!("No fortunes found"),
+        },
+    }
+    Ok(())
+}
+
+/// The files under `paths`, sorted and without duplicates. The `.dat` indexes written by `strfile`
+/// are skipped.
+fn find_files(paths: &[String]) -> MyResult<Vec<PathBuf>> {
+    let dat = OsStr::new("dat");
+    let mut files = vec![];
+    for path in paths {
+        fs::metadata(path).map_err(|e| format!("{}: {}", path, e))?;
+        for entry in WalkDir::new(path) {
+            let entry = entry.map_err(|e| format!("{}: {}", path, e))?;
+            if entry.file_type().is_file() && entry.path().extension() != Some(dat) {
+                files.push(entry.into_path());
+            }
+        }
+    }
+    files.sort();
+    files.dedup();
+    Ok(files)
+}
+
+/// The fortunes in `paths`, separated by lines holding only `%`. Empty ones are skipped.
+fn read_fortunes(paths: &[PathBuf]) -> MyResult<Vec<Fortune>> {
+    let mut fortunes = vec![];
+    for path in paths {
+        let file = File::open(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+
+        let mut lines = vec![];
+        let mut push = |lines: &mut Vec<String>| {
+            if !lines.is_empty() {
+                fortunes.push(Fortune {
+                    source: path.clone(),
+                    text: lines.join("\n"),
+                });
+                lines.clear();
+            }
+        };
+        for line in BufReader::new(file).lines() {
+            let line = line.map_err(|e| format!("{}: {}", path.display(), e))?;
+            if line == "%" {
+                push(&mut lines);
+            } else {
+                lines.push(line);
+            }
+        }
+        // 最後の区切りがなくても strfile と同じく 1 つの格言として扱う
+        push(&mut lines);
+    }
+    Ok(fortunes)
+}
+
+/// A random fortune, the same one for the same `seed`.
+fn pick_fortune(fortunes: &[Fortune], seed: Option<u64>) -> Option<String> {
+    let fortune = match seed {
+        Some(seed) => fortunes.choose(&mut StdRng::seed_from_u64(seed)),
+        None => fortunes.choose(&mut rand::rng()),
+    };
+    fortune.map(|fortune| fortune.text.clone())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn sources(paths: &[&str]) -> Vec<String> {
+        paths.iter().map(|path| path.to_string()).collect()
+    }
+
+    #[test]
+    fn test_find_files() {
+        let files = find_files(&sources(&["./tests/inputs/jokes"])).unwrap();
+        assert_eq!(files, [PathBuf::from("./tests/inputs/jokes")]);
+
+        let err = find_files(&sources(&["/path/does/not/exist"])).unwrap_err();
+        assert!(err.to_string().starts_with("/path/does/not/exist: "));
+
+        // 索引は読まず、重複は除く
+        let files = find_files(&sources(&[
+            "./tests/inputs",
+            "./tests/inputs/quotes",
+            "./tests/inputs/quotes",
+        ]))
+        .unwrap();
+        assert_eq!(
+            files,
+            [
+                "./tests/inputs/jokes",
+                "./tests/inputs/literature",
+                "./tests/inputs/more/empty",
+                "./tests/inputs/more/proverbs",
+                "./tests/inputs/quotes",
+            ]
+            .map(PathBuf::from)
+        );
+    }
+
+    #[test]
+    fn test_read_fortunes() {
+        let fortunes = read_fortunes(&[PathBuf::from("./tests/inputs/jokes")]).unwrap();
+        assert_eq!(fortunes.len(), 6);
+        assert_eq!(
+            fortunes[0],
+            Fortune {
+                source: PathBuf::from("./tests/inputs/jokes"),
+                text: "Q. What do you call a boomerang that won't come back?\nA. A stick."
+                    .to_string(),
+            }
+        );
+
+        // 空の格言は飛ばし、最後の区切りがなくても読む
+        let fortunes = read_fortunes(&[
+            PathBuf::from("./tests/inputs/more/empty"),
+            PathBuf::from("./tests/inputs/more/proverbs"),
+        ])
+        .unwrap();
+        let texts: Vec<&str> = fortunes
+            .iter()
+            .map(|fortune| fortune.text.as_str())
+            .collect();
+        assert_eq!(
+            texts,
+            [
+                "Time and tide wait for no man.",
+                "A stitch in time saves nine.",
+                "Fortune favors the bold.",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_pick_fortune() {
+        let fortunes: Vec<Fortune> = ["You cannot quarrel with a volcano.", "Hard work.", "Luck."]
+            .iter()
+            .map(|text| Fortune {
+                source: PathBuf::from("fortunes"),
+                text: text.to_string(),
+            })
+            .collect();
+
+        let picked = pick_fortune(&fortunes, Some(1));
+        assert!(picked.is_some());
+        assert_eq!(pick_fortune(&fortunes, Some(1)), picked);
+        assert_eq!(pick_fortune(&[], Some(1)), None);
+    }
+}
@@ -0,0 +1,6 @@
+fn main() {
+    if let Err(e) = num15_fortuner::get_args().and_then(num15_fortuner::run) {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+}
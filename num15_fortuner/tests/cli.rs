@@ -0,0 +1,220 @@
+use anyhow::Result;
+use assert_cmd::Command;
+use predicates::prelude::*;
+use pretty_assertions::assert_eq;
+use rand::{distr::Alphanumeric, Rng};
+use std::fs;
+
+const PRG: &str = "num15_fortuner";
+const INPUTS: &str = "tests/inputs";
+const JOKES: &str = "tests/inputs/jokes";
+const LITERATURE: &str = "tests/inputs/literature";
+const QUOTES: &str = "tests/inputs/quotes";
+const EMPTY: &str = "tests/inputs/more/empty";
+
+// --------------------------------------------------
+fn gen_bad_file() -> String {
+    loop {
+        let filename: String = rand::rng()
+            .sample_iter(&Alphanumeric)
+            .take(7)
+            .map(char::from)
+            .collect();
+
+        if fs::metadata(&filename).is_err() {
+            return filename;
+        }
+    }
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_not_enough_args() -> Result<()> {
+    let expected = "the following required arguments were not provided";
+    Command::cargo_bin(PRG)?
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(expected));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_file() -> Result<()> {
+    let bad = gen_bad_file();
+    let expected = format!("{bad}: .* [(]os error 2[)]");
+    Command::cargo_bin(PRG)?
+        .args([LITERATURE, &bad])
+        .assert()
+        .failure()
+        .stderr(predicate::str::is_match(expected)?);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_pattern() -> Result<()> {
+    Command::cargo_bin(PRG)?
+        .args(["--pattern", "*", LITERATURE])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(r#"Invalid --pattern "*""#));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_seed() -> Result<()> {
+    let bad = gen_bad_file();
+    let expected = format!("invalid value '{bad}' for '--seed <SEED>'");
+    Command::cargo_bin(PRG)?
+        .args([LITERATURE, "--seed", &bad])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(expected));
+    Ok(())
+}
+
+// --------------------------------------------------
+fn run(args: &[&str], expected: &str) -> Result<()> {
+    let output = Command::cargo_bin(PRG)?.args(args).output()?;
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout)?, expected, "{args:?}");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn no_fortunes_found() -> Result<()> {
+    run(&[EMPTY], "No fortunes found\n")
+}
+
+// --------------------------------------------------
+#[test]
+fn seeds() -> Result<()> {
+    let cases: &[(&[&str], &str)] = &[
+        (
+            &[JOKES, "-s", "1"],
+            "Q. What did the ocean say to the beach?\n\
+             A. Nothing, it just waved.\n",
+        ),
+        (
+            &[JOKES, "--seed", "2"],
+            "Q. What do you call a boomerang that won't come back?\n\
+             A. A stick.\n",
+        ),
+        (
+            &[INPUTS, "-s", "1"],
+            "The secret of getting ahead is getting started.\n\
+             -- Mark Twain\n",
+        ),
+        (&[INPUTS, "-s", "3"], "A stitch in time saves nine.\n"),
+        (
+            &[QUOTES, LITERATURE, JOKES, "-s", "10"],
+            "It was the best of times, it was the worst of times.\n\
+             -- Charles Dickens, A Tale of Two Cities\n",
+        ),
+    ];
+    for (args, expected) in cases {
+        run(args, expected)?;
+    }
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn random() -> Result<()> {
+    // 種を指定しなければ、いずれかの格言を出力する
+    let output = Command::cargo_bin(PRG)?.arg(QUOTES).output()?;
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout)?;
+    let quotes = fs::read_to_string(QUOTES)?;
+    assert!(quotes.split("%\n").any(|quote| quote == stdout));
+    Ok(())
+}
+
+// --------------------------------------------------
+fn run_outfiles(args: &[&str], out: &str, err: &str) -> Result<()> {
+    let output = Command::cargo_bin(PRG)?.args(args).output()?;
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout)?, out, "{args:?}");
+    assert_eq!(String::from_utf8(output.stderr)?, err, "{args:?}");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn pattern() -> Result<()> {
+    run_outfiles(
+        &["-m", "Twain", QUOTES, LITERATURE],
+        "The secret of getting ahead is getting started.\n\
+         -- Mark Twain\n\
+         %\n\
+         Never put off till tomorrow what may be done day after \
+         tomorrow just as well.\n\
+         -- Mark Twain\n\
+         %\n",
+        "(tests/inputs/quotes)\n%\n",
+    )?;
+    run_outfiles(&["--pattern", "Twain", JOKES], "", "")
+}
+
+// --------------------------------------------------
+#[test]
+fn pattern_groups_by_source() -> Result<()> {
+    // ファイルはパスの順に並べて重複を除き、ファイルごとに出どころを示す
+    run_outfiles(
+        &["-m", "time", QUOTES, INPUTS],
+        "It was the best of times, it was the worst of times.\n\
+         -- Charles Dickens, A Tale of Two Cities\n\
+         %\n\
+         The time is out of joint.\n\
+         -- William Shakespeare, Hamlet\n\
+         %\n\
+         A stitch in time saves nine.\n\
+         %\n\
+         Lost time is never found again.\n\
+         -- Benjamin Franklin, Poor Richard's Almanack\n\
+         %\n",
+        "(tests/inputs/literature)\n%\n\
+         (tests/inputs/more/proverbs)\n%\n\
+         (tests/inputs/quotes)\n%\n",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn pattern_groups_same_names() -> Result<()> {
+    // 名前が同じでも別のファイルなら、それぞれのパスを見出しにする
+    let dir = tempfile::tempdir()?;
+    for (subdir, text) in
+        [("a", "A penny saved.\n"), ("b", "A penny earned.\n")]
+    {
+        fs::create_dir(dir.path().join(subdir))?;
+        fs::write(dir.path().join(subdir).join("quotes"), text)?;
+    }
+    let dir = dir.path().to_str().unwrap();
+    run_outfiles(
+        &["-m", "penny", dir],
+        "A penny saved.\n%\nA penny earned.\n%\n",
+        &format!("({dir}/a/quotes)\n%\n({dir}/b/quotes)\n%\n"),
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn pattern_insensitive() -> Result<()> {
+    run_outfiles(
+        &["-i", "-m", "TIME", "tests/inputs/more"],
+        "Time and tide wait for no man.\n\
+         %\n\
+         A stitch in time saves nine.\n\
+         %\n",
+        "(tests/inputs/more/proverbs)\n%\n",
+    )?;
+    run_outfiles(
+        &["--insensitive", "--pattern", "^time", "tests/inputs/more"],
+        "Time and tide wait for no man.\n%\n",
+        "(tests/inputs/more/proverbs)\n%\n",
+    )
+}
@@ -0,0 +1,34 @@
+#!/usr/bin/env bash
+
+# GNU coreutils の nl で期待値を生成する
+export LC_ALL=C
+NL="/usr/bin/nl"
+ROOT="tests/inputs"
+OUT_DIR="tests/expected"
+
+[[ ! -d "$OUT_DIR" ]] && mkdir -p "$OUT_DIR"
+rm -f "$OUT_DIR"/*
+
+EMPTY="$ROOT/empty.txt"
+FOX="$ROOT/fox.txt"
+NOEOL="$ROOT/noeol.txt"
+SECTIONS="$ROOT/sections.txt"
+BUSTLE="$ROOT/the-bustle.txt"
+
+$NL $EMPTY                            > "$OUT_DIR/empty.txt.out"
+$NL $FOX                              > "$OUT_DIR/fox.txt.out"
+$NL $NOEOL                            > "$OUT_DIR/noeol.txt.out"
+$NL $BUSTLE                           > "$OUT_DIR/the-bustle.txt.out"
+$NL -b a $BUSTLE                      > "$OUT_DIR/the-bustle.txt.b.a.out"
+$NL -b n $BUSTLE                      > "$OUT_DIR/the-bustle.txt.b.n.out"
+$NL -b 'p^The' $BUSTLE                > "$OUT_DIR/the-bustle.txt.b.p.out"
+$NL -n ln $BUSTLE                     > "$OUT_DIR/the-bustle.txt.n.ln.out"
+$NL -n rz -w 3 $BUSTLE                > "$OUT_DIR/the-bustle.txt.n.rz.w3.out"
+$NL -s ': ' -w 2 $BUSTLE              > "$OUT_DIR/the-bustle.txt.s.w2.out"
+$NL -v 10 -i 5 $BUSTLE                > "$OUT_DIR/the-bustle.txt.v10.i5.out"
+$NL -v -2 -i 3 -n rz -w 4 $BUSTLE     > "$OUT_DIR/the-bustle.txt.v-2.i3.out"
+$NL $SECTIONS                         > "$OUT_DIR/sections.txt.out"
+$NL -h a -f t $SECTIONS               > "$OUT_DIR/sections.txt.h.a.f.t.out"
+$NL -b 'pwas' -h 'pChapter' $SECTIONS > "$OUT_DIR/sections.txt.b.p.h.p.out"
+$NL -b a -v 0 $SECTIONS               > "$OUT_DIR/sections.txt.b.a.v0.out"
+$NL $BUSTLE $SECTIONS $NOEOL $FOX     > "$OUT_DIR/all.out"
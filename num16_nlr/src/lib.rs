@@ -0,0 +1,375 @@
+use std::{
+    env,
+    error::Error,
+    ffi::OsString,
+    fs::File,
+    io::{self, BufRead, BufReader, Write},
+};
+
+use clap::{ArgAction, Parser, value_parser};
+use num3_catr::{NumberColumn, NumberFormat};
+use regex::bytes::Regex;
+
+type MyResult<T> = Result<T, Box<dyn Error>>;
+
+#[derive(Debug)]
+pub struct Config {
+    files: Vec<String>,
+    header: Style,
+    body: Style,
+    footer: Style,
+    column: NumberColumn,
+    start: i64,
+    increment: i64,
+}
+
+/// Which lines of a section get a number.
+#[derive(Clone, Debug)]
+enum Style {
+    All,
+    NonEmpty,
+    None,
+    Matching(Regex),
+}
+
+/// The part of a logical page a line is in, switched by the `\:\:\:`, `\:\:` and `\:` lines.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Section {
+    Header,
+    Body,
+    Footer,
+}
+
+#[derive(Parser, Debug)]
+#[command(version, disable_help_flag = true)]
+#[command(about = "Rust nl")]
+struct Cli {
+    /// Input files.
+    #[arg(value_name = "FILE", default_value = "-")]
+    files: Vec<String>,
+
+    /// Number body lines: a (all), t (non-empty), n (none) or pREGEX (matching).
+    #[arg(
+        short('b'),
+        long,
+        value_name = "STYLE",
+        default_value = "t",
+        value_parser = parse_style
+    )]
+    body_numbering: Style,
+
+    /// Number header lines, like --body-numbering.
+    #[arg(
+        short('h'),
+        long,
+        value_name = "STYLE",
+        default_value = "n",
+        value_parser = parse_style
+    )]
+    header_numbering: Style,
+
+    /// Number footer lines, like --body-numbering.
+    #[arg(
+        short('f'),
+        long,
+        value_name = "STYLE",
+        default_value = "n",
+        value_parser = parse_style
+    )]
+    footer_numbering: Style,
+
+    /// Line number format: ln (left), rn (right) or rz (right with zeros).
+    #[arg(
+        short('n'),
+        long,
+        value_name = "FORMAT",
+        default_value = "rn",
+        value_parser = parse_format
+    )]
+    number_format: NumberFormat,
+
+    /// Use NUMBER columns for line numbers.
+    #[arg(
+        short('w'),
+        long,
+        value_name = "NUMBER",
+        default_value_t = 6,
+        value_parser = value_parser!(u16).range(1..)
+    )]
+    number_width: u16,
+
+    /// Add STRING after line numbers [default: TAB].
+    #[arg(
+        short('s'),
+        long,
+        value_name = "STRING",
+        default_value = "\t",
+        hide_default_value = true
+    )]
+    number_separator: String,
+
+    /// First line number of each section.
+    #[arg(
+        short('v'),
+        long,
+        value_name = "NUMBER",
+        default_value_t = 1,
+        allow_negative_numbers = true
+    )]
+    starting_line_number: i64,
+
+    /// Line number increment at each numbered line.
+    #[arg(
+        short('i'),
+        long,
+        value_name = "NUMBER",
+        default_value_t = 1,
+        allow_negative_numbers = true
+    )]
+    line_increment: i64,
+
+    /// Print help.
+    #[arg(long, action = ArgAction::Help)]
+    help: Option<bool>,
+}
+
+pub fn get_args() -> MyResult<Config> {
+    get_args_from(env::args_os())
+}
+
+pub fn get_args_from<I, T>(args: I) -> MyResult<Config>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<OsString> + Clone,
+{
+    let args = Cli::parse_from(args);
+
+    Ok(Config {
+        files: args.files,
+        header: args.header_numbering,
+        body: args.body_numbering,
+        footer: args.footer_numbering,
+        column: NumberColumn::new(
+            args.number_format,
+            usize::from(args.number_width),
+            &args.number_separator,
+        ),
+        start: args.starting_line_number,
+        increment: args.line_increment,
+    })
+}
+
+fn parse_style(style: &str) -> Result<Style, String> {
+    match style {
+        "a" => Ok(Style::All),
+        "t" => Ok(Style::NonEmpty),
+        "n" => Ok(Style::None),
+        _ => match style.strip_prefix('p') {
+            Some(pattern) => Regex::new(pattern)
+                .map(Style::Matching)
+                .map_err(|_| format!("invalid regular expression: '{}'", pattern)),
+            None => Err(format!("invalid numbering style: '{}'", style)),
+        },
+    }
+}
+
+fn parse_format(format: &str) -> Result<NumberFormat, String> {
+    match format {
+        "ln" => Ok(NumberFormat::Left),
+        "rn" => Ok(NumberFormat::Right),
+        "rz" => Ok(NumberFormat::RightZero),
+        _ => Err(format!("invalid line numbering format: '{}'", format)),
+    }
+}
+
+pub fn run(config: Config) -> MyResult<()> {
+    let mut stdout = io::stdout().lock();
+    // ファイルをまたいでも、番号とセクションは続ける
+    let mut numberer = Numberer::new(&config);
+    for file_name in &config.files {
+        match open(file_name) {
+            Err(err) => eprintln!("{}: {}", file_name, err),
+            Ok(reader) => numberer.number(reader, &mut stdout)?,
+        }
+    }
+    stdout.flush()?;
+    Ok(())
+}
+
+fn open(filename: &str) -> MyResult<Box<dyn BufRead>> {
+    match filename {
+        "-" => Ok(Box::new(BufReader::new(io::stdin()))),
+        _ => Ok(Box::new(BufReader::new(File::open(filename)?))),
+    }
+}
+
+impl Section {
+    fn from_delimiter(line: &[u8]) -> Option<Self> {
+        match line {
+            br"\:\:\:" => Some(Section::Header),
+            br"\:\:" => Some(Section::Body),
+            br"\:" => Some(Section::Footer),
+            _ => None,
+        }
+    }
+}
+
+/// Numbers lines while keeping track of the section and the next number.
+#[derive(Debug)]
+struct Numberer<'a> {
+    config: &'a Config,
+    section: Section,
+    /// The number of the next numbered line, or `None` once it has overflowed.
+    next: Option<i64>,
+}
+
+impl<'a> Numberer<'a> {
+    fn new(config: &'a Config) -> Self {
+        Numberer {
+            config,
+            section: Section::Body,
+            next: Some(config.start),
+        }
+    }
+
+    fn style(&self) -> &Style {
+        match self.section {
+            Section::Header => &self.config.header,
+            Section::Body => &self.config.body,
+            Section::Footer => &self.config.footer,
+        }
+    }
+
+    fn number(&mut self, mut reader: impl BufRead, out: &mut impl Write) -> MyResult<()> {
+        let mut line = Vec::new();
+        loop {
+            line.clear();
+            if reader.read_until(b'\n', &mut line)? == 0 {
+                return Ok(());
+            }
+            let content = line.strip_suffix(b"\n").unwrap_or(&line);
+
+            // 区切りの行は空行にして、セクションごとに番号を振り直す
+            if let Some(section) = Section::from_delimiter(content) {
+                self.section = section;
+                self.next = Some(self.config.start);
+                writeln!(out)?;
+                continue;
+            }
+
+            let numbered = match self.style() {
+                Style::All => true,
+                Style::NonEmpty => !content.is_empty(),
+                Style::None => false,
+                Style::Matching(pattern) => pattern.is_match(content),
+            };
+            if numbered {
+                // 溢れるのは次の番号を使うときまで問題にしない
+                let number = self.next.ok_or("line number overflow")?;
+                self.config.column.write(number, out)?;
+                self.next = number.checked_add(self.config.increment);
+            } else {
+                self.config.column.write_blank(out)?;
+            }
+            // 最後の行に改行がなくても付け足す
+            out.write_all(content)?;
+            out.write_all(b"\n")?;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn config(body: Style, start: i64, increment: i64) -> Config {
+        Config {
+            files: vec![],
+            header: Style::None,
+            body,
+            footer: Style::All,
+            column: NumberColumn::new(NumberFormat::Right, 2, " "),
+            start,
+            increment,
+        }
+    }
+
+    fn number(config: &Config, chunks: &[&str]) -> String {
+        let mut numberer = Numberer::new(config);
+        let mut out = Vec::new();
+        for chunk in chunks {
+            numberer.number(chunk.as_bytes(), &mut out).unwrap();
+        }
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_parse_style() {
+        assert!(matches!(parse_style("a"), Ok(Style::All)));
+        assert!(matches!(parse_style("t"), Ok(Style::NonEmpty)));
+        assert!(matches!(parse_style("n"), Ok(Style::None)));
+        assert!(matches!(
+            parse_style("p^a"),
+            Ok(Style::Matching(pattern)) if pattern.as_str() == "^a"
+        ));
+        assert_eq!(
+            parse_style("x").unwrap_err(),
+            "invalid numbering style: 'x'"
+        );
+        assert_eq!(
+            parse_style("p(").unwrap_err(),
+            "invalid regular expression: '('"
+        );
+    }
+
+    #[test]
+    fn test_parse_format() {
+        assert_eq!(parse_format("ln"), Ok(NumberFormat::Left));
+        assert_eq!(parse_format("rn"), Ok(NumberFormat::Right));
+        assert_eq!(parse_format("rz"), Ok(NumberFormat::RightZero));
+        assert_eq!(
+            parse_format("lz"),
+            Err("invalid line numbering format: 'lz'".to_string())
+        );
+    }
+
+    #[test]
+    fn test_number() {
+        let config = config(Style::NonEmpty, 1, 1);
+        assert_eq!(number(&config, &["a\n\nb"]), " 1 a\n   \n 2 b\n");
+
+        // ファイルをまたいでも番号は続く
+        assert_eq!(number(&config, &["a\n", "b\n"]), " 1 a\n 2 b\n");
+
+        let config = self::config(Style::Matching(Regex::new("^b").unwrap()), 10, -5);
+        assert_eq!(
+            number(&config, &["a\nb\nbb\nb\n"]),
+            "   a\n10 b\n 5 bb\n 0 b\n"
+        );
+    }
+
+    #[test]
+    fn test_number_sections() {
+        let config = config(Style::All, 1, 1);
+        assert_eq!(
+            number(&config, &["a\n\\:\\:\\:\nh\n\\:\\:\nb\nb\n\\:\nf\n"]),
+            " 1 a\n\n   h\n\n 1 b\n 2 b\n\n 1 f\n"
+        );
+        // 区切りに見えても前後に文字があれば普通の行
+        assert_eq!(number(&config, &["\\:\\: \n"]), " 1 \\:\\: \n");
+    }
+
+    #[test]
+    fn test_number_overflow() {
+        let config = config(Style::All, i64::MAX - 1, 1);
+        let max = format!("{} a\n{} b\n", i64::MAX - 1, i64::MAX);
+        assert_eq!(number(&config, &["a\nb\n"]), max);
+
+        let mut out = Vec::new();
+        let err = Numberer::new(&config)
+            .number("a\nb\nc\n".as_bytes(), &mut out)
+            .unwrap_err();
+        assert_eq!(err.to_string(), "line number overflow");
+        assert_eq!(String::from_utf8(out).unwrap(), max);
+    }
+}
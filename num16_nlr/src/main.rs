@@ -0,0 +1,6 @@
+fn main() {
+    if let Err(e) = num16_nlr::get_args().and_then(num16_nlr::run) {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,172 @@
+use anyhow::Result;
+use assert_cmd::Command;
+use predicates::prelude::*;
+use pretty_assertions::assert_eq;
+use rand::{distr::Alphanumeric, Rng};
+use std::fs;
+
+const PRG: &str = "num16_nlr";
+const EMPTY: &str = "tests/inputs/empty.txt";
+const FOX: &str = "tests/inputs/fox.txt";
+const NOEOL: &str = "tests/inputs/noeol.txt";
+const SECTIONS: &str = "tests/inputs/sections.txt";
+const BUSTLE: &str = "tests/inputs/the-bustle.txt";
+
+// --------------------------------------------------
+#[test]
+fn usage() -> Result<()> {
+    // -h はヘッダーの番号の振り方に使うので、ヘルプは長いオプションだけ
+    Command::cargo_bin(PRG)?
+        .arg("--help")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Usage"));
+    Ok(())
+}
+
+// --------------------------------------------------
+fn gen_bad_file() -> String {
+    loop {
+        let filename: String = rand::rng()
+            .sample_iter(&Alphanumeric)
+            .take(7)
+            .map(char::from)
+            .collect();
+
+        if fs::metadata(&filename).is_err() {
+            return filename;
+        }
+    }
+}
+
+// --------------------------------------------------
+#[test]
+fn skips_bad_file() -> Result<()> {
+    let bad = gen_bad_file();
+    let expected = format!("{bad}: .* [(]os error 2[)]");
+    Command::cargo_bin(PRG)?
+        .args([&bad, FOX])
+        .assert()
+        .success()
+        .stderr(predicate::str::is_match(expected)?)
+        .stdout(fs::read_to_string("tests/expected/fox.txt.out")?);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_options() -> Result<()> {
+    let cases: &[(&[&str], &str)] = &[
+        (&["-b", "x"], "invalid numbering style: 'x'"),
+        (&["-h", "q"], "invalid numbering style: 'q'"),
+        (
+            &["--footer-numbering", "all"],
+            "invalid numbering style: 'all'",
+        ),
+        (&["-b", "p("], "invalid regular expression: '('"),
+        (&["-n", "lz"], "invalid line numbering format: 'lz'"),
+        (&["-w", "0"], "invalid value '0'"),
+        (&["-v", "x"], "invalid value 'x'"),
+        (&["-i", "1.5"], "invalid value '1.5'"),
+    ];
+    for (args, expected) in cases {
+        Command::cargo_bin(PRG)?
+            .args(*args)
+            .arg(FOX)
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(*expected));
+    }
+    Ok(())
+}
+
+// --------------------------------------------------
+fn run(args: &[&str], expected_file: &str) -> Result<()> {
+    let expected = fs::read_to_string(expected_file)?;
+    let output = Command::cargo_bin(PRG)?.args(args).output()?;
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout)?, expected, "{args:?}");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn expected() -> Result<()> {
+    let cases: &[(&[&str], &str)] = &[
+        (&[EMPTY], "empty.txt"),
+        (&[FOX], "fox.txt"),
+        (&[NOEOL], "noeol.txt"),
+        (&[BUSTLE], "the-bustle.txt"),
+        (&["-b", "a", BUSTLE], "the-bustle.txt.b.a"),
+        (&["--body-numbering=n", BUSTLE], "the-bustle.txt.b.n"),
+        (&["-b", "p^The", BUSTLE], "the-bustle.txt.b.p"),
+        (&["-n", "ln", BUSTLE], "the-bustle.txt.n.ln"),
+        (
+            &["--number-format", "rz", "-w", "3", BUSTLE],
+            "the-bustle.txt.n.rz.w3",
+        ),
+        (
+            &["-s", ": ", "--number-width", "2", BUSTLE],
+            "the-bustle.txt.s.w2",
+        ),
+        (&["-v", "10", "-i", "5", BUSTLE], "the-bustle.txt.v10.i5"),
+        (
+            &["-v", "-2", "-i", "3", "-n", "rz", "-w", "4", BUSTLE],
+            "the-bustle.txt.v-2.i3",
+        ),
+        (&[SECTIONS], "sections.txt"),
+        (&["-h", "a", "-f", "t", SECTIONS], "sections.txt.h.a.f.t"),
+        (
+            &[
+                "--body-numbering=pwas",
+                "--header-numbering=pChapter",
+                SECTIONS,
+            ],
+            "sections.txt.b.p.h.p",
+        ),
+        (
+            &["-b", "a", "--starting-line-number", "0", SECTIONS],
+            "sections.txt.b.a.v0",
+        ),
+        (&[BUSTLE, SECTIONS, NOEOL, FOX], "all"),
+    ];
+    for (args, name) in cases {
+        run(args, &format!("tests/expected/{name}.out"))?;
+    }
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn stdin() -> Result<()> {
+    let expected = fs::read_to_string("tests/expected/all.out")?;
+    Command::cargo_bin(PRG)?
+        .args([BUSTLE, "-", NOEOL, FOX])
+        .write_stdin(fs::read(SECTIONS)?)
+        .assert()
+        .success()
+        .stdout(expected);
+
+    let expected = fs::read_to_string("tests/expected/the-bustle.txt.out")?;
+    Command::cargo_bin(PRG)?
+        .write_stdin(fs::read(BUSTLE)?)
+        .assert()
+        .success()
+        .stdout(expected);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn overflow() -> Result<()> {
+    Command::cargo_bin(PRG)?
+        .args(["-v", &i64::MAX.to_string(), FOX, FOX])
+        .assert()
+        .failure()
+        .stdout(format!(
+            "{}\tThe quick brown fox jumps over the lazy dog.\n",
+            i64::MAX
+        ))
+        .stderr("line number overflow\n");
+    Ok(())
+}
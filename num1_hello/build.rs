@@ -0,0 +1,43 @@
+use std::{env, path::Path, process::Command};
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+
+    let git_hash = command_output("git", &["rev-parse", "--short", "HEAD"]);
+    println!(
+        "cargo:rustc-env=HELLO_GIT_HASH={}",
+        git_hash.as_deref().unwrap_or("unknown")
+    );
+
+    // コミットが変わったときに再ビルドされるよう HEAD と参照先を監視する
+    if let Some(git_dir) = command_output("git", &["rev-parse", "--git-dir"]) {
+        let git_dir = Path::new(&git_dir);
+        println!("cargo:rerun-if-changed={}", git_dir.join("HEAD").display());
+        if let Some(head_ref) = command_output("git", &["symbolic-ref", "-q", "HEAD"]) {
+            println!(
+                "cargo:rerun-if-changed={}",
+                git_dir.join(head_ref).display()
+            );
+        }
+    }
+
+    let target = env::var("TARGET").unwrap_or_else(|_| "unknown".to_string());
+    println!("cargo:rustc-env=HELLO_TARGET={}", target);
+
+    let rustc = env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    let rustc_version = command_output(&rustc, &["--version"]);
+    println!(
+        "cargo:rustc-env=HELLO_RUSTC_VERSION={}",
+        rustc_version.as_deref().unwrap_or("unknown")
+    );
+}
+
+/// Trimmed stdout of a successful command, or `None` if it could not be run.
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let stdout = String::from_utf8(output.stdout).ok()?;
+    Some(stdout.trim().to_string()).filter(|s| !s.is_empty())
+}
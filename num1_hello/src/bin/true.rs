@@ -1 +1,5 @@
-fn main() {}
+use std::{env, process::ExitCode};
+
+fn main() -> ExitCode {
+    num1_hello::true_main(env::args_os())
+}
@@ -0,0 +1,217 @@
+use std::{
+    env,
+    error::Error,
+    ffi::OsString,
+    io::{self, Write},
+    process::ExitCode,
+};
+
+use clap::Parser;
+use serde::Serialize;
+
+type MyResult<T> = Result<T, Box<dyn Error>>;
+
+#[derive(Debug)]
+pub struct Config {
+    name: Option<String>,
+    json: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Lang {
+    En,
+    Ja,
+}
+
+#[derive(Debug, Serialize)]
+struct Greeting {
+    greeting: String,
+    name: Option<String>,
+    lang: Lang,
+    build: BuildInfo,
+}
+
+/// Build information captured by `build.rs`.
+#[derive(Debug, Serialize)]
+struct BuildInfo {
+    version: &'static str,
+    git_hash: &'static str,
+    target: &'static str,
+    rustc: &'static str,
+}
+
+const BUILD_INFO: BuildInfo = BuildInfo {
+    version: env!("CARGO_PKG_VERSION"),
+    git_hash: env!("HELLO_GIT_HASH"),
+    target: env!("HELLO_TARGET"),
+    rustc: env!("HELLO_RUSTC_VERSION"),
+};
+
+pub fn get_args() -> MyResult<Config> {
+    get_args_from(env::args_os())
+}
+
+pub fn get_args_from<I, T>(args: I) -> MyResult<Config>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<OsString> + Clone,
+{
+    let args = Cli::parse_from(args);
+    Ok(Config {
+        name: args.name,
+        json: args.json,
+    })
+}
+
+pub fn run(config: Config) -> MyResult<()> {
+    let lang = detect_lang(|key| env::var(key).ok());
+    let greeting = greet(config.name.as_deref(), lang);
+
+    if config.json {
+        let record = Greeting {
+            greeting,
+            name: config.name,
+            lang,
+            build: BUILD_INFO,
+        };
+        println!("{}", serde_json::to_string(&record)?);
+    } else {
+        println!("{}", greeting);
+    }
+    Ok(())
+}
+
+/// Pick the message language from `LC_ALL`, `LC_MESSAGES` and `LANG`, in that order.
+fn detect_lang(var: impl Fn(&str) -> Option<String>) -> Lang {
+    // 空の変数は未設定として扱い、次の候補を見る
+    let locale = ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .into_iter()
+        .filter_map(var)
+        .find(|value| !value.is_empty());
+
+    match locale {
+        Some(locale) if locale.starts_with("ja") => Lang::Ja,
+        _ => Lang::En,
+    }
+}
+
+fn greet(name: Option<&str>, lang: Lang) -> String {
+    match (lang, name) {
+        (Lang::En, None) => "Hello, world!".to_string(),
+        (Lang::En, Some(name)) => format!("Hello, {}!", name),
+        (Lang::Ja, None) => "こんにちは、世界！".to_string(),
+        (Lang::Ja, Some(name)) => format!("こんにちは、{}さん！", name),
+    }
+}
+
+/// `true`: exit successfully. `args` starts with the program name.
+pub fn true_main(args: impl IntoIterator<Item = OsString>) -> ExitCode {
+    exit_with(
+        args,
+        "true",
+        "Exit with a status code indicating success.",
+        0,
+    )
+}
+
+/// `false`: exit unsuccessfully. `args` starts with the program name.
+pub fn false_main(args: impl IntoIterator<Item = OsString>) -> ExitCode {
+    exit_with(
+        args,
+        "false",
+        "Exit with a status code indicating failure.",
+        1,
+    )
+}
+
+/// Exit with `status` the way GNU `true` and `false` do.
+///
+/// All arguments are ignored, except that a lone `--help` or `--version` prints usage or version
+/// information first. The exit status is `status` either way, unless writing to stdout fails.
+fn exit_with(
+    args: impl IntoIterator<Item = OsString>,
+    name: &str,
+    description: &str,
+    status: u8,
+) -> ExitCode {
+    let args: Vec<_> = args.into_iter().skip(1).collect();
+
+    let message = match args.as_slice() {
+        [arg] if arg == "--help" => usage(name, description),
+        [arg] if arg == "--version" => format!("{} {}\n", name, env!("CARGO_PKG_VERSION")),
+        _ => return ExitCode::from(status),
+    };
+
+    // 書き込みに失敗した場合は GNU と同様に失敗扱いにする
+    let mut stdout = io::stdout();
+    if let Err(e) = stdout
+        .write_all(message.as_bytes())
+        .and_then(|_| stdout.flush())
+    {
+        eprintln!("{}: write error: {}", name, e);
+        return ExitCode::FAILURE;
+    }
+    ExitCode::from(status)
+}
+
+fn usage(name: &str, description: &str) -> String {
+    format!(
+        "Usage: {name} [ignored command line arguments]
+  or:  {name} OPTION
+{description}
+
+      --help        display this help and exit
+      --version     output version information and exit
+"
+    )
+}
+
+#[derive(Parser, Debug)]
+#[command(version)]
+#[command(about = "Rust hello")]
+struct Cli {
+    /// Name to greet.
+    name: Option<String>,
+
+    /// Print the greeting and build information as JSON.
+    #[arg(long)]
+    json: bool,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn lang_of(vars: &[(&str, &str)]) -> Lang {
+        detect_lang(|key| {
+            vars.iter()
+                .find(|(k, _)| *k == key)
+                .map(|(_, v)| v.to_string())
+        })
+    }
+
+    #[test]
+    fn test_detect_lang() {
+        assert_eq!(lang_of(&[]), Lang::En);
+        assert_eq!(lang_of(&[("LANG", "ja_JP.UTF-8")]), Lang::Ja);
+        assert_eq!(lang_of(&[("LANG", "C")]), Lang::En);
+        assert_eq!(
+            lang_of(&[("LANG", "ja_JP.UTF-8"), ("LC_MESSAGES", "en_US.UTF-8")]),
+            Lang::En
+        );
+        assert_eq!(
+            lang_of(&[("LC_MESSAGES", "ja_JP.UTF-8"), ("LC_ALL", "C")]),
+            Lang::En
+        );
+        assert_eq!(lang_of(&[("LC_ALL", ""), ("LANG", "ja")]), Lang::Ja);
+    }
+
+    #[test]
+    fn test_greet() {
+        assert_eq!(greet(None, Lang::En), "Hello, world!");
+        assert_eq!(greet(Some("Rust"), Lang::En), "Hello, Rust!");
+        assert_eq!(greet(None, Lang::Ja), "こんにちは、世界！");
+        assert_eq!(greet(Some("Rust"), Lang::Ja), "こんにちは、Rustさん！");
+    }
+}
@@ -1,3 +1,6 @@
 fn main() {
-    println!("Hello, world!");
+    if let Err(e) = num1_hello::get_args().and_then(num1_hello::run) {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
 }
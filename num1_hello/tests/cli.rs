@@ -3,17 +3,139 @@ use assert_cmd::Command;
 #[test]
 fn true_ok() {
     let mut cmd = Command::cargo_bin("true").unwrap();
-    cmd.assert().success();
+    cmd.assert().code(0).stdout("");
 }
 
 #[test]
 fn false_not_ok() {
     let mut cmd = Command::cargo_bin("false").unwrap();
-    cmd.assert().failure();
+    cmd.assert().code(1).stdout("");
 }
 
 #[test]
-fn runs() {
+fn ignores_arguments() {
+    let cases: &[&[&str]] = &[
+        &["foo"],
+        &["-x", "--bogus"],
+        &["--help", "extra"],
+        &["--version", "--help"],
+        &["--", "--help"],
+    ];
+    for (name, code) in [("true", 0), ("false", 1)] {
+        for args in cases {
+            let mut cmd = Command::cargo_bin(name).unwrap();
+            cmd.args(*args).assert().code(code).stdout("").stderr("");
+        }
+    }
+}
+
+#[test]
+fn help() {
+    for (name, code, description) in [
+        ("true", 0, "Exit with a status code indicating success.\n"),
+        ("false", 1, "Exit with a status code indicating failure.\n"),
+    ] {
+        let mut cmd = Command::cargo_bin(name).unwrap();
+        let output = cmd.arg("--help").assert().code(code).get_output().clone();
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert!(stdout.starts_with(&format!("Usage: {} [ignored", name)));
+        assert!(stdout.contains(description));
+        assert!(stdout.contains("--version"));
+    }
+}
+
+#[test]
+fn version() {
+    for (name, code) in [("true", 0), ("false", 1)] {
+        let mut cmd = Command::cargo_bin(name).unwrap();
+        cmd.arg("--version").assert().code(code).stdout(format!(
+            "{} {}\n",
+            name,
+            env!("CARGO_PKG_VERSION")
+        ));
+    }
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn write_error() {
+    use std::{fs::File, process};
+
+    let status = process::Command::new(assert_cmd::cargo::cargo_bin("true"))
+        .arg("--help")
+        .stdout(File::create("/dev/full").unwrap())
+        .stderr(process::Stdio::null())
+        .status()
+        .unwrap();
+    assert_eq!(status.code(), Some(1));
+}
+
+fn hello() -> Command {
     let mut cmd = Command::cargo_bin("num1_hello").unwrap();
-    cmd.assert().success().stdout("Hello, world!\n");
+    for key in ["LC_ALL", "LC_MESSAGES", "LANG"] {
+        cmd.env_remove(key);
+    }
+    cmd
+}
+
+#[test]
+fn runs() {
+    hello().assert().success().stdout("Hello, world!\n");
+}
+
+#[test]
+fn greets_name() {
+    hello()
+        .arg("Rust")
+        .assert()
+        .success()
+        .stdout("Hello, Rust!\n");
+}
+
+#[test]
+fn greets_in_japanese() {
+    hello()
+        .env("LANG", "ja_JP.UTF-8")
+        .assert()
+        .success()
+        .stdout("こんにちは、世界！\n");
+    hello()
+        .env("LANG", "en_US.UTF-8")
+        .env("LC_MESSAGES", "ja_JP.UTF-8")
+        .arg("Rust")
+        .assert()
+        .success()
+        .stdout("こんにちは、Rustさん！\n");
+    hello()
+        .env("LC_MESSAGES", "ja_JP.UTF-8")
+        .env("LC_ALL", "C")
+        .assert()
+        .success()
+        .stdout("Hello, world!\n");
+}
+
+#[test]
+fn json() {
+    let output = hello()
+        .args(["--json", "Rust"])
+        .env("LANG", "ja_JP.UTF-8")
+        .assert()
+        .success()
+        .get_output()
+        .clone();
+    let value: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(value["greeting"], "こんにちは、Rustさん！");
+    assert_eq!(value["name"], "Rust");
+    assert_eq!(value["lang"], "ja");
+
+    let build = &value["build"];
+    assert_eq!(build["version"], env!("CARGO_PKG_VERSION"));
+    for key in ["git_hash", "target", "rustc"] {
+        assert!(
+            build[key].as_str().is_some_and(|s| !s.is_empty()),
+            "{}",
+            key
+        );
+    }
+    assert!(build["rustc"].as_str().unwrap().starts_with("rustc "));
 }
@@ -1,5 +1,8 @@
 #!/usr/bin/env bash
 
+# bash の echo (一部は GNU echo) と GNU coreutils の printf で参照用の期待値を生成する。
+# UPDATE_EXPECTED=1 cargo test で作り直した場合は、このスクリプトを流して差分がないことを確かめる。
+
 OUTDIR="tests/expected"
 [[ ! -d "$OUTDIR" ]] && mkdir -p "$OUTDIR"
 
@@ -7,3 +10,23 @@ echo    "Hello there"    > $OUTDIR/hello1.txt
 echo    "Hello"  "there" > $OUTDIR/hello2.txt
 echo -n "Hello  there"   > $OUTDIR/hello1.n.txt
 echo -n "Hello"  "there" > $OUTDIR/hello2.n.txt
+
+# -e/-E: backslash escapes
+echo -e "tab\there" "new\nline" "back\\\\slash\a\b\f\v\r\e" > $OUTDIR/escapes.e.txt
+echo -e "\0101\0102\0" "\x41\x4a\x4Z" "\xff"              > $OUTDIR/octal_hex.e.txt
+echo -e "あ\u41" "\u"                                  > $OUTDIR/unicode.e.txt
+echo -e "before\c after" "never"                           > $OUTDIR/stop.e.txt
+echo -e -n "trailing\\" "\q\x\xg"                          > $OUTDIR/malformed.e.n.txt
+echo -E "tab\there\c"                                      > $OUTDIR/no_escapes.E.txt
+# bash の echo は \NNN を解釈しないため GNU echo で生成する
+/bin/echo -e "\101\1012"                                   > $OUTDIR/gnu_octal.e.txt
+
+# printfr: GNU coreutils の printf で生成する
+PRINTF="/usr/bin/printf"
+$PRINTF "%s=%d\n" a 1 b 2 c                                > $OUTDIR/printf_recycle.txt
+$PRINTF "[%5s|%-5s|%.2s|%c]\n" ab cd efg hij                 > $OUTDIR/printf_strings.txt
+$PRINTF "%d %+i %05d %.3u %x %#X %#o\n" 42 7 -3 5 255 255 8 > $OUTDIR/printf_ints.txt
+$PRINTF "%f %.2e %g %G %8.3f|%-8g|\n" 3.14159 1234.5 0.0001 1e-10 2.5 100 > $OUTDIR/printf_floats.txt
+$PRINTF "%*d|%-*d|%.*f\n" 5 1 4 2 1 3.14159                 > $OUTDIR/printf_star.txt
+$PRINTF "%b|%q|%q\n" 'tab\there\0101' 'a b' "it's"         > $OUTDIR/printf_b_q.txt
+$PRINTF "%s\c%s\n" stop never                              > $OUTDIR/printf_stop.txt
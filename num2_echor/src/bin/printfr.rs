@@ -0,0 +1,50 @@
+use std::{
+    env,
+    io::{self, Write},
+};
+
+use num2_echor::{into_bytes, printf::printf};
+
+const USAGE: &str = "Rust printf
+
+Usage: printfr FORMAT [ARGUMENT]...
+
+Print ARGUMENT(s) according to FORMAT, reusing FORMAT until all arguments are consumed.
+Supports %s %d %i %u %x %X %o %f %e %g %c %b %q with flags, width and precision.
+";
+
+fn main() {
+    let mut args: Vec<Vec<u8>> = env::args_os().skip(1).map(into_bytes).collect();
+    match args.first().map(Vec::as_slice) {
+        Some(b"--help") if args.len() == 1 => {
+            print!("{}", USAGE);
+            return;
+        }
+        Some(b"--version") if args.len() == 1 => {
+            println!("printfr {}", env!("CARGO_PKG_VERSION"));
+            return;
+        }
+        Some(b"--") => {
+            args.remove(0);
+        }
+        _ => {}
+    }
+
+    let Some((format, args)) = args.split_first() else {
+        eprintln!("printfr: missing operand");
+        eprintln!("Try 'printfr --help' for more information.");
+        std::process::exit(1);
+    };
+
+    let output = printf(format, args);
+    if let Err(e) = io::stdout().write_all(&output.bytes) {
+        eprintln!("printfr: {}", e);
+        std::process::exit(1);
+    }
+    for message in &output.messages {
+        eprintln!("printfr: {}", message);
+    }
+    if output.failed {
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,167 @@
+use std::ops::ControlFlow;
+
+/// How octal escapes are written.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Octal {
+    /// `\0NNN` or `\NNN`, as in `echo -e` and the `%b` conversion of printf.
+    ZeroPrefixed,
+    /// `\NNN`, as in a printf format.
+    Plain,
+}
+
+/// Interpret backslash escapes in `bytes` the way `echo -e` does, appending the result to `out`.
+///
+/// Returns `ControlFlow::Break` when `\c` is found, after which nothing more should be printed.
+/// Malformed escapes such as `\q` or `\x` without hex digits are written out unchanged.
+pub fn unescape(bytes: &[u8], out: &mut Vec<u8>) -> ControlFlow<()> {
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i] == b'\\' {
+            i += unescape_at(&bytes[i..], Octal::ZeroPrefixed, out)?;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    ControlFlow::Continue(())
+}
+
+/// Interpret the single escape at the start of `bytes`, which must begin with a backslash.
+///
+/// Returns the number of bytes consumed, or `ControlFlow::Break` for `\c`.
+pub fn unescape_at(bytes: &[u8], octal: Octal, out: &mut Vec<u8>) -> ControlFlow<(), usize> {
+    // 末尾の単独のバックスラッシュはそのまま出力する
+    let Some(&escape) = bytes.get(1) else {
+        out.push(b'\\');
+        return ControlFlow::Continue(1);
+    };
+
+    let rest = &bytes[2..];
+    let mut len = 2;
+    match escape {
+        b'\\' => out.push(b'\\'),
+        b'"' if octal == Octal::Plain => out.push(b'"'),
+        b'a' => out.push(0x07),
+        b'b' => out.push(0x08),
+        b'c' => return ControlFlow::Break(()),
+        b'e' => out.push(0x1b),
+        b'f' => out.push(0x0c),
+        b'n' => out.push(b'\n'),
+        b'r' => out.push(b'\r'),
+        b't' => out.push(b'\t'),
+        b'v' => out.push(0x0b),
+        b'0' if octal == Octal::ZeroPrefixed => {
+            // \0NNN: 0の後ろに最大3桁の8進数
+            let (value, digits) = parse_digits(rest, 8, 3);
+            out.push(value as u8);
+            len += digits;
+        }
+        b'0'..=b'7' => {
+            // \NNN: 先頭の桁を含めて最大3桁の8進数
+            let (value, digits) = parse_digits(&bytes[1..], 8, 3);
+            out.push(value as u8);
+            len += digits - 1;
+        }
+        b'x' => match parse_digits(rest, 16, 2) {
+            (_, 0) => out.extend_from_slice(b"\\x"),
+            (value, digits) => {
+                out.push(value as u8);
+                len += digits;
+            }
+        },
+        b'u' => {
+            let (value, digits) = parse_digits(rest, 16, 4);
+            match char::from_u32(value).filter(|_| digits > 0) {
+                Some(c) => {
+                    let mut buf = [0; 4];
+                    out.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+                    len += digits;
+                }
+                // サロゲートなど文字にならない値はエスケープせずに出力する
+                None => out.extend_from_slice(b"\\u"),
+            }
+        }
+        _ => {
+            out.push(b'\\');
+            out.push(escape);
+        }
+    }
+    ControlFlow::Continue(len)
+}
+
+/// Parse up to `max_len` leading digits of `radix`, returning the value and the number of digits used.
+fn parse_digits(bytes: &[u8], radix: u32, max_len: usize) -> (u32, usize) {
+    bytes
+        .iter()
+        .take(max_len)
+        .map_while(|&b| char::from(b).to_digit(radix))
+        .fold((0, 0), |(value, len), digit| {
+            (value * radix + digit, len + 1)
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn generate(text: &str) -> (Vec<u8>, bool) {
+        let mut out = Vec::new();
+        let flow = unescape(text.as_bytes(), &mut out);
+        (out, flow.is_break())
+    }
+
+    #[test]
+    fn test_unescape() {
+        let cases: &[(&str, &[u8], bool)] = &[
+            ("plain", b"plain", false),
+            (r"a\tb\nc\\d", b"a\tb\nc\\d", false),
+            (r"\a\b\e\f\r\v", b"\x07\x08\x1b\x0c\r\x0b", false),
+            (r"\0101\0", b"A\0", false),
+            (r"\101\1012", b"AA2", false),
+            (r"\x41\x4a\x4Z\xff", b"AJ\x04Z\xff", false),
+            (r"あ\u41", "\u{3042}A".as_bytes(), false),
+            (r"stop\c here", b"stop", true),
+        ];
+        for (text, expected, stopped) in cases {
+            assert_eq!(generate(text), (expected.to_vec(), *stopped), "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_unescape_malformed() {
+        let cases: &[(&str, &[u8])] = &[
+            (r"trailing\", b"trailing\\"),
+            (r"\q", b"\\q"),
+            (r"\x", b"\\x"),
+            (r"\xg", b"\\xg"),
+            (r"\u", b"\\u"),
+            (r"\ud800", b"\\ud800"),
+            (r"\☺", "\\☺".as_bytes()),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(generate(text), (expected.to_vec(), false), "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_unescape_at_plain_octal() {
+        let cases: &[(&str, &[u8], usize)] = &[
+            (r"\0101", b"\x08", 4),
+            (r"\101x", b"A", 4),
+            (r"\7", b"\x07", 2),
+            (r#"\"x"#, b"\"", 2),
+            (r"\", b"\\", 1),
+        ];
+        for (text, expected, len) in cases {
+            let mut out = Vec::new();
+            let flow = unescape_at(text.as_bytes(), Octal::Plain, &mut out);
+            assert_eq!(
+                (out, flow),
+                (expected.to_vec(), ControlFlow::Continue(*len)),
+                "{}",
+                text
+            );
+        }
+    }
+}
@@ -0,0 +1,274 @@
+use std::{
+    env,
+    error::Error,
+    ffi::OsString,
+    io::{self, Write},
+};
+
+use clap::{Arg, ArgAction, Command};
+
+pub mod escape;
+pub mod printf;
+
+type MyResult<T> = Result<T, Box<dyn Error>>;
+
+/// The name shown in usage and version output, also when run as `num2_echor`.
+const NAME: &str = "echor";
+
+#[derive(Debug, PartialEq)]
+pub struct Config {
+    text: Vec<Vec<u8>>,
+    no_newline: bool,
+    escapes: bool,
+}
+
+#[derive(Debug, PartialEq)]
+enum Action {
+    Help,
+    Version,
+    Echo(Config),
+}
+
+pub fn get_args() -> MyResult<Config> {
+    get_args_from(env::args_os())
+}
+
+/// Unlike the other tools, `--help` and `--version` are scanned by hand and come back as the
+/// [`clap::Error`] that displays them.
+pub fn get_args_from<I, T>(args: I) -> MyResult<Config>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<OsString>,
+{
+    let mut args = args.into_iter().map(Into::into);
+    let program = args.next().unwrap_or_else(|| NAME.into());
+    let args: Vec<Vec<u8>> = args.map(into_bytes).collect();
+    let posixly_correct = env::var_os("POSIXLY_CORRECT").is_some();
+
+    match scan_args(&args, posixly_correct) {
+        // clap の try_parse と同じく、ヘルプとバージョンは clap のエラーとして返し、表示は呼び出し側に任せる
+        Action::Help => Err(display_error(program, "--help").into()),
+        Action::Version => Err(display_error(program, "--version").into()),
+        Action::Echo(config) => Ok(config),
+    }
+}
+
+pub fn run(config: Config) -> MyResult<()> {
+    io::stdout().write_all(&echo(&config))?;
+    Ok(())
+}
+
+/// The error clap returns for `--help` or `--version`, which displays them when printed.
+fn display_error(program: OsString, flag: &str) -> clap::Error {
+    match command().try_get_matches_from([program, flag.into()]) {
+        Err(err) => err,
+        Ok(_) => unreachable!("{} always stops parsing", flag),
+    }
+}
+
+/// Usage shown for `--help`. Parsing itself is done by `scan_args`.
+fn command() -> Command {
+    // 呼び出し方によらず、使い方とバージョンにはツールの名前を示す
+    Command::new(NAME)
+        .bin_name(NAME)
+        .version(env!("CARGO_PKG_VERSION"))
+        .about("Rust echo")
+        .disable_help_flag(true)
+        .disable_version_flag(true)
+        .arg(
+            Arg::new("text")
+                .value_name("TEXT")
+                .num_args(0..)
+                .help("Input text"),
+        )
+        .arg(
+            Arg::new("newline")
+                .short('n')
+                .action(ArgAction::SetTrue)
+                .help("Do not print newline"),
+        )
+        .arg(
+            Arg::new("escapes")
+                .short('e')
+                .action(ArgAction::SetTrue)
+                .help("Enable interpretation of backslash escapes"),
+        )
+        .arg(
+            Arg::new("no_escapes")
+                .short('E')
+                .action(ArgAction::SetTrue)
+                .help("Disable interpretation of backslash escapes (default)"),
+        )
+        .arg(
+            Arg::new("help")
+                .long("help")
+                .action(ArgAction::Help)
+                .help("Print help"),
+        )
+        .arg(
+            Arg::new("version")
+                .long("version")
+                .action(ArgAction::Version)
+                .help("Print version"),
+        )
+}
+
+/// Scan arguments the way GNU echo does.
+///
+/// Only leading arguments made up entirely of `-n`, `-e` and `-E` clusters are options; everything
+/// from the first other argument on, including `--` and unknown flags, is echoed verbatim. With
+/// `POSIXLY_CORRECT` set, options are only recognized when the first argument is exactly `-n`, and
+/// backslash escapes are always interpreted.
+fn scan_args(args: &[Vec<u8>], posixly_correct: bool) -> Action {
+    let allow_options = !posixly_correct || args.first().is_some_and(|arg| arg == b"-n");
+
+    if allow_options && args.len() == 1 {
+        match args[0].as_slice() {
+            b"--help" => return Action::Help,
+            b"--version" => return Action::Version,
+            _ => {}
+        }
+    }
+
+    let mut no_newline = false;
+    let mut escapes = false;
+    let mut rest = args;
+    while allow_options && let Some((arg, tail)) = rest.split_first() {
+        let Some(flags) = arg.strip_prefix(b"-") else {
+            break;
+        };
+        if flags.is_empty() || !flags.iter().all(|b| matches!(b, b'n' | b'e' | b'E')) {
+            break;
+        }
+
+        for flag in flags {
+            match flag {
+                b'n' => no_newline = true,
+                b'e' => escapes = true,
+                b'E' => escapes = false,
+                _ => unreachable!(),
+            }
+        }
+        rest = tail;
+    }
+
+    Action::Echo(Config {
+        text: rest.to_vec(),
+        no_newline,
+        escapes: escapes || posixly_correct,
+    })
+}
+
+fn echo(config: &Config) -> Vec<u8> {
+    let mut output = Vec::new();
+    let mut ending = !config.no_newline;
+
+    for (i, text) in config.text.iter().enumerate() {
+        if i > 0 {
+            output.push(b' ');
+        }
+        if !config.escapes {
+            output.extend_from_slice(text);
+        } else if escape::unescape(text, &mut output).is_break() {
+            // \c 以降は改行も含めて何も出力しない
+            ending = false;
+            break;
+        }
+    }
+    if ending {
+        output.push(b'\n');
+    }
+    output
+}
+
+/// Raw bytes of an argument, so that non UTF-8 arguments are passed through unchanged on Unix.
+#[cfg(unix)]
+pub fn into_bytes(arg: OsString) -> Vec<u8> {
+    use std::os::unix::ffi::OsStringExt;
+    arg.into_vec()
+}
+
+#[cfg(not(unix))]
+pub fn into_bytes(arg: OsString) -> Vec<u8> {
+    arg.to_string_lossy().into_owned().into_bytes()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn scan(args: &[&str], posixly_correct: bool) -> Action {
+        let args: Vec<Vec<u8>> = args.iter().map(|arg| arg.as_bytes().to_vec()).collect();
+        scan_args(&args, posixly_correct)
+    }
+
+    fn echo_config(text: &[&str], no_newline: bool, escapes: bool) -> Action {
+        Action::Echo(Config {
+            text: text.iter().map(|arg| arg.as_bytes().to_vec()).collect(),
+            no_newline,
+            escapes,
+        })
+    }
+
+    #[test]
+    fn test_scan_args() {
+        assert_eq!(scan(&[], false), echo_config(&[], false, false));
+        assert_eq!(scan(&["--help"], false), Action::Help);
+        assert_eq!(scan(&["--version"], false), Action::Version);
+        assert_eq!(
+            scan(&["--help", "me"], false),
+            echo_config(&["--help", "me"], false, false)
+        );
+        assert_eq!(
+            scan(&["-n", "-eE", "-ne", "a", "-n"], false),
+            echo_config(&["a", "-n"], true, true)
+        );
+        assert_eq!(
+            scan(&["--", "-n"], false),
+            echo_config(&["--", "-n"], false, false)
+        );
+        assert_eq!(
+            scan(&["-nx", "-"], false),
+            echo_config(&["-nx", "-"], false, false)
+        );
+    }
+
+    #[test]
+    fn test_get_args_from_display() {
+        for (flag, kind) in [
+            ("--help", clap::error::ErrorKind::DisplayHelp),
+            ("--version", clap::error::ErrorKind::DisplayVersion),
+        ] {
+            let err = get_args_from(["echor", flag]).unwrap_err();
+            let err = err.downcast_ref::<clap::Error>().unwrap();
+            assert_eq!(err.kind(), kind);
+            assert_eq!(err.exit_code(), 0);
+        }
+
+        // パッケージ名ではなくツールの名前を示す
+        let err = get_args_from(["num2_echor", "--version"]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!("echor {}\n", env!("CARGO_PKG_VERSION"))
+        );
+        let err = get_args_from(["num2_echor", "--help"]).unwrap_err();
+        assert!(err.to_string().contains("Usage: echor [OPTIONS]"));
+        assert!(get_args_from(["echor", "--help", "me"]).is_ok());
+    }
+
+    #[test]
+    fn test_scan_args_posixly_correct() {
+        assert_eq!(
+            scan(&["--help"], true),
+            echo_config(&["--help"], false, true)
+        );
+        assert_eq!(
+            scan(&["-e", "-n", "a"], true),
+            echo_config(&["-e", "-n", "a"], false, true)
+        );
+        assert_eq!(
+            scan(&["-n", "-E", "a"], true),
+            echo_config(&["a"], true, true)
+        );
+    }
+}
@@ -1,22 +1,10 @@
-use clap::Parser;
-
-#[derive(Parser, Debug)]
-#[command(version)]
-#[command(about = "Rust echo")]
-struct Cli {
-    /// Input text
-    #[arg(required = true, num_args = 1..)]
-    text: Vec<String>,
-
-    #[arg(short, num_args = 0)]
-    /// Do not print newline
-    newline: bool,
-}
-
 fn main() {
-    let args = Cli::parse();
-    let texts = args.text.join(" ");
-    let ending = if args.newline { "" } else { "\n" };
-
-    print!("{}{}", texts, ending);
+    if let Err(e) = num2_echor::get_args().and_then(num2_echor::run) {
+        // ヘルプとバージョンは clap のエラーとして返ってくるので、clap と同じく表示して終了する
+        if let Some(err) = e.downcast_ref::<clap::Error>() {
+            err.exit();
+        }
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
 }
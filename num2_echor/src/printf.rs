@@ -0,0 +1,867 @@
+use std::{borrow::Cow, ops::ControlFlow};
+
+use crate::escape::{self, Octal};
+
+/// Result of `printf`: the bytes to write and the diagnostics for stderr.
+#[derive(Debug, Default, PartialEq)]
+pub struct Output {
+    pub bytes: Vec<u8>,
+    pub messages: Vec<String>,
+    /// Set when an argument was not a valid number or the format was invalid.
+    pub failed: bool,
+}
+
+/// Format `args` according to `format` like coreutils `printf`.
+///
+/// The format is reused until all arguments are consumed; missing arguments are treated as an
+/// empty string or zero. Both are raw bytes, so arguments that are not UTF-8 pass through `%s`.
+pub fn printf(format: &[u8], args: &[Vec<u8>]) -> Output {
+    let mut printer = Printer {
+        args,
+        next_arg: 0,
+        output: Output::default(),
+    };
+
+    loop {
+        let start = printer.next_arg;
+        match printer.format_once(format) {
+            Err(message) => {
+                printer.output.messages.push(message);
+                printer.output.failed = true;
+                break;
+            }
+            Ok(ControlFlow::Break(())) => break,
+            Ok(ControlFlow::Continue(())) => {}
+        }
+
+        if printer.next_arg >= args.len() {
+            break;
+        }
+        if printer.next_arg == start {
+            // 引数を一つも使わない書式の場合は繰り返さない
+            printer.output.messages.push(format!(
+                "warning: ignoring excess arguments, starting with '{}'",
+                String::from_utf8_lossy(&args[start])
+            ));
+            break;
+        }
+    }
+    printer.output
+}
+
+/// Largest field width or precision, the range of `int` like coreutils.
+const MAX_COUNT: usize = i32::MAX as usize;
+
+/// Fraction digits after which the decimal expansion of any `f64` is all zeros.
+const EXACT_DIGITS: usize = 1100;
+
+#[derive(Debug, Default)]
+struct Flags {
+    left: bool,
+    plus: bool,
+    space: bool,
+    alt: bool,
+    zero: bool,
+}
+
+#[derive(Debug)]
+enum Count {
+    Fixed(usize),
+    FromArg,
+}
+
+#[derive(Debug)]
+struct Spec {
+    flags: Flags,
+    width: Option<Count>,
+    precision: Option<Count>,
+    conversion: u8,
+}
+
+/// A converted argument before padding to the field width.
+enum Field {
+    Text(Vec<u8>),
+    Number {
+        prefix: String,
+        digits: String,
+        zero: bool,
+    },
+}
+
+struct Printer<'a> {
+    args: &'a [Vec<u8>],
+    next_arg: usize,
+    output: Output,
+}
+
+impl<'a> Printer<'a> {
+    fn format_once(&mut self, format: &[u8]) -> Result<ControlFlow<()>, String> {
+        let mut i = 0;
+        while i < format.len() {
+            match format[i] {
+                b'%' if format.get(i + 1) == Some(&b'%') => {
+                    self.output.bytes.push(b'%');
+                    i += 2;
+                }
+                b'%' => {
+                    let (spec, len) = parse_spec(&format[i..])?;
+                    i += len;
+                    if self.convert(&spec)?.is_break() {
+                        return Ok(ControlFlow::Break(()));
+                    }
+                }
+                b'\\' => {
+                    match escape::unescape_at(&format[i..], Octal::Plain, &mut self.output.bytes) {
+                        ControlFlow::Continue(len) => i += len,
+                        ControlFlow::Break(()) => return Ok(ControlFlow::Break(())),
+                    }
+                }
+                b => {
+                    self.output.bytes.push(b);
+                    i += 1;
+                }
+            }
+        }
+        Ok(ControlFlow::Continue(()))
+    }
+
+    fn next_arg(&mut self) -> Option<&'a [u8]> {
+        let arg = self.args.get(self.next_arg)?;
+        self.next_arg += 1;
+        Some(arg)
+    }
+
+    fn peek_arg(&self) -> Cow<'a, str> {
+        let arg = self.args.get(self.next_arg).map_or(&[][..], Vec::as_slice);
+        String::from_utf8_lossy(arg)
+    }
+
+    fn convert(&mut self, spec: &Spec) -> Result<ControlFlow<()>, String> {
+        let mut left = spec.flags.left;
+        let width = match spec.width {
+            None => 0,
+            Some(Count::Fixed(width)) => width,
+            Some(Count::FromArg) => {
+                let arg = self.peek_arg();
+                // 負の幅は左寄せとして扱う
+                let (negative, width) = self.int_arg();
+                left |= negative;
+                count(width).ok_or_else(|| format!("invalid field width: '{}'", arg))?
+            }
+        };
+        let precision = match spec.precision {
+            None => None,
+            Some(Count::Fixed(precision)) => Some(precision),
+            Some(Count::FromArg) => {
+                let arg = self.peek_arg();
+                match self.int_arg() {
+                    // 負の精度は大きさによらず指定がないものとして扱う
+                    (true, _) => None,
+                    (false, precision) => Some(
+                        count(precision).ok_or_else(|| format!("invalid precision: '{}'", arg))?,
+                    ),
+                }
+            }
+        };
+
+        let mut flow = ControlFlow::Continue(());
+        let field = match spec.conversion {
+            b's' => Field::Text(truncate(self.next_arg().unwrap_or_default(), precision).to_vec()),
+            b'b' => {
+                let mut body = Vec::new();
+                flow = escape::unescape(self.next_arg().unwrap_or_default(), &mut body);
+                Field::Text(truncate(&body, precision).to_vec())
+            }
+            b'q' => Field::Text(quote(self.next_arg().unwrap_or_default()).into_bytes()),
+            b'c' => Field::Text(vec![
+                self.next_arg()
+                    .unwrap_or_default()
+                    .first()
+                    .copied()
+                    .unwrap_or(0),
+            ]),
+            b'd' | b'i' => {
+                let (negative, value) = self.int_arg();
+                Field::Number {
+                    prefix: sign(negative, &spec.flags).to_string(),
+                    digits: format_digits(value, 10, precision),
+                    zero: spec.flags.zero && precision.is_none(),
+                }
+            }
+            b'o' | b'u' | b'x' | b'X' => {
+                let value = self.uint_arg();
+                let radix = match spec.conversion {
+                    b'o' => 8,
+                    b'u' => 10,
+                    _ => 16,
+                };
+                let mut digits = format_digits(value, radix, precision);
+                let mut prefix = String::new();
+                if spec.flags.alt && radix == 8 && !digits.starts_with('0') {
+                    digits.insert(0, '0');
+                } else if spec.flags.alt && radix == 16 && value != 0 {
+                    prefix.push_str("0x");
+                }
+                if spec.conversion == b'X' {
+                    digits.make_ascii_uppercase();
+                    prefix.make_ascii_uppercase();
+                }
+                Field::Number {
+                    prefix,
+                    digits,
+                    zero: spec.flags.zero && precision.is_none(),
+                }
+            }
+            _ => {
+                let value = self.float_arg();
+                let mut digits =
+                    format_float(value.abs(), spec.conversion, precision, spec.flags.alt);
+                if spec.conversion.is_ascii_uppercase() {
+                    digits.make_ascii_uppercase();
+                }
+                let negative = value.is_sign_negative() && !value.is_nan();
+                Field::Number {
+                    prefix: sign(negative, &spec.flags).to_string(),
+                    digits,
+                    zero: spec.flags.zero && value.is_finite(),
+                }
+            }
+        };
+
+        let out = &mut self.output.bytes;
+        match field {
+            Field::Text(body) => push_padded(out, &body, left, width),
+            // 0埋めは符号や 0x の後ろに入れる
+            Field::Number {
+                prefix,
+                digits,
+                zero,
+            } if zero && !left => {
+                let padding = width.saturating_sub(prefix.len() + digits.len());
+                out.extend_from_slice(prefix.as_bytes());
+                out.resize(out.len() + padding, b'0');
+                out.extend_from_slice(digits.as_bytes());
+            }
+            Field::Number { prefix, digits, .. } => {
+                push_padded(out, format!("{}{}", prefix, digits).as_bytes(), left, width)
+            }
+        }
+        Ok(flow)
+    }
+
+    /// Signed argument as its sign and magnitude, clamped to the range of `i64` like `strtoimax`.
+    fn int_arg(&mut self) -> (bool, u64) {
+        let (negative, value, _) = self.integer_arg(|negative| match negative {
+            true => i64::MIN.unsigned_abs(),
+            false => i64::MAX as u64,
+        });
+        (negative, value)
+    }
+
+    /// Unsigned argument, where negative values wrap around like `strtoumax`.
+    fn uint_arg(&mut self) -> u64 {
+        match self.integer_arg(|_| u64::MAX) {
+            // 範囲外の場合、strtoumax は符号によらず最大値を返す
+            (_, value, true) => value,
+            (true, value, false) => value.wrapping_neg(),
+            (false, value, false) => value,
+        }
+    }
+
+    /// Integer argument as its sign and magnitude, clamped to `limit(negative)`. Returns whether
+    /// it was out of range, which is reported like coreutils does.
+    fn integer_arg(&mut self, limit: fn(bool) -> u64) -> (bool, u64, bool) {
+        // 引数が足りない場合は0として扱う
+        let Some(arg) = self.next_arg() else {
+            return (false, 0, false);
+        };
+        if let Some(value) = char_constant(arg) {
+            return (false, u64::from(value), false);
+        }
+        let arg = String::from_utf8_lossy(arg);
+        let (negative, value, overflow, rest) = parse_integer(&arg);
+        let limit = limit(negative);
+        if overflow || value > limit {
+            // 範囲外の場合は、変換しきれなかった部分があっても範囲のエラーだけを示す
+            self.output
+                .messages
+                .push(format!("'{}': Numerical result out of range", arg));
+            self.output.failed = true;
+            return (negative, limit, true);
+        }
+        self.check_converted(&arg, rest);
+        (negative && value != 0, value, false)
+    }
+
+    fn float_arg(&mut self) -> f64 {
+        let Some(arg) = self.next_arg() else {
+            return 0.0;
+        };
+        if let Some(value) = char_constant(arg) {
+            return f64::from(value);
+        }
+        let arg = String::from_utf8_lossy(arg);
+        let (value, rest) = parse_float(&arg);
+        self.check_converted(&arg, rest);
+        value
+    }
+
+    fn check_converted(&mut self, arg: &str, rest: Option<&str>) {
+        let message = match rest {
+            None => format!("'{}': expected a numeric value", arg),
+            Some("") => return,
+            Some(_) => format!("'{}': value not completely converted", arg),
+        };
+        self.output.messages.push(message);
+        self.output.failed = true;
+    }
+}
+
+/// Parse a conversion specification starting at `%`, returning it and its length.
+fn parse_spec(format: &[u8]) -> Result<(Spec, usize), String> {
+    let mut flags = Flags::default();
+    let mut i = 1;
+    while let Some(&b) = format.get(i) {
+        match b {
+            b'-' => flags.left = true,
+            b'+' => flags.plus = true,
+            b' ' => flags.space = true,
+            b'#' => flags.alt = true,
+            b'0' => flags.zero = true,
+            b'\'' => {}
+            _ => break,
+        }
+        i += 1;
+    }
+
+    let (width, len) = parse_count(&format[i..], "field width")?;
+    i += len;
+    let mut precision = None;
+    if format.get(i) == Some(&b'.') {
+        i += 1;
+        let (count, len) = parse_count(&format[i..], "precision")?;
+        precision = Some(count.unwrap_or(Count::Fixed(0)));
+        i += len;
+    }
+
+    // 長さ修飾子は数値の変換でのみ受け付けて無視する
+    let length_start = i;
+    while format.get(i).is_some_and(|b| b"hlLjzt".contains(b)) {
+        i += 1;
+    }
+    let conversions: &[u8] = if i > length_start {
+        b"diouxXfFeEgG"
+    } else {
+        b"diouxXfFeEgGcsbq"
+    };
+
+    match format.get(i) {
+        Some(&conversion) if conversions.contains(&conversion) => {
+            let spec = Spec {
+                flags,
+                width,
+                precision,
+                conversion,
+            };
+            Ok((spec, i + 1))
+        }
+        _ => {
+            let end = (i + 1).min(format.len());
+            Err(format!(
+                "{}: invalid conversion specification",
+                String::from_utf8_lossy(&format[..end])
+            ))
+        }
+    }
+}
+
+/// Parse a field width or precision, `name` being which one for the error message.
+fn parse_count(bytes: &[u8], name: &str) -> Result<(Option<Count>, usize), String> {
+    if bytes.first() == Some(&b'*') {
+        return Ok((Some(Count::FromArg), 1));
+    }
+    let len = bytes.iter().take_while(|b| b.is_ascii_digit()).count();
+    if len == 0 {
+        return Ok((None, 0));
+    }
+    let digits = String::from_utf8_lossy(&bytes[..len]);
+    match digits.parse().ok().and_then(count) {
+        Some(count) => Ok((Some(Count::Fixed(count)), len)),
+        None => Err(format!("invalid {}: '{}'", name, digits)),
+    }
+}
+
+/// `value` as a field width or precision, or `None` when it is out of range.
+fn count(value: u64) -> Option<usize> {
+    usize::try_from(value)
+        .ok()
+        .filter(|&value| value <= MAX_COUNT)
+}
+
+/// Parse an integer the way `strtoimax` does.
+///
+/// Returns the sign, the magnitude, whether the magnitude overflowed `u64` and the unparsed rest,
+/// or `None` as rest when nothing parsed. An overflowing magnitude is `u64::MAX`.
+fn parse_integer(arg: &str) -> (bool, u64, bool, Option<&str>) {
+    let text = arg.trim_start();
+    let (negative, text) = match text.as_bytes().first() {
+        Some(b'-') => (true, &text[1..]),
+        Some(b'+') => (false, &text[1..]),
+        _ => (false, text),
+    };
+    let (radix, digits) = if let Some(hex) = text.strip_prefix("0x").or(text.strip_prefix("0X")) {
+        (16, hex)
+    } else if text.len() > 1 && text.starts_with('0') {
+        (8, &text[1..])
+    } else {
+        (10, text)
+    };
+
+    let len = digits
+        .bytes()
+        .take_while(|b| char::from(*b).is_digit(radix))
+        .count();
+    if len == 0 {
+        // "0x" や "0" だけの場合は先頭の0までを数値とみなす
+        return match text.starts_with('0') {
+            true => (negative, 0, false, Some(&text[1..])),
+            false => (false, 0, false, None),
+        };
+    }
+    // 数字だけを渡すので、失敗するのは桁あふれの場合に限る
+    let (value, overflow) = match u64::from_str_radix(&digits[..len], radix) {
+        Ok(value) => (value, false),
+        Err(_) => (u64::MAX, true),
+    };
+    (negative, value, overflow, Some(&digits[len..]))
+}
+
+/// Parse a floating point number, using the longest prefix that is a valid number.
+fn parse_float(arg: &str) -> (f64, Option<&str>) {
+    let text = arg.trim_start();
+    (1..=text.len())
+        .rev()
+        .filter(|&len| text.is_char_boundary(len))
+        .find_map(|len| Some((text[..len].parse().ok()?, Some(&text[len..]))))
+        .unwrap_or((0.0, None))
+}
+
+/// Code of the character following a leading quote, e.g. `'A` is 65.
+///
+/// A byte that does not start a valid UTF-8 character gives its own value, as in the C locale.
+fn char_constant(arg: &[u8]) -> Option<u32> {
+    let rest = arg.strip_prefix(b"'").or(arg.strip_prefix(b"\""))?;
+    let chunk = rest.utf8_chunks().next();
+    Some(match chunk {
+        Some(chunk) if !chunk.valid().is_empty() => {
+            chunk.valid().chars().next().map_or(0, u32::from)
+        }
+        Some(chunk) => chunk.invalid().first().map_or(0, |&b| u32::from(b)),
+        None => 0,
+    })
+}
+
+fn sign(negative: bool, flags: &Flags) -> &'static str {
+    if negative {
+        "-"
+    } else if flags.plus {
+        "+"
+    } else if flags.space {
+        " "
+    } else {
+        ""
+    }
+}
+
+fn format_digits(value: u64, radix: u32, precision: Option<usize>) -> String {
+    let digits = match radix {
+        8 => format!("{:o}", value),
+        16 => format!("{:x}", value),
+        _ => value.to_string(),
+    };
+    match precision {
+        // 精度0で値が0の場合は何も出力しない
+        Some(0) if value == 0 => String::new(),
+        Some(precision) => "0".repeat(precision.saturating_sub(digits.len())) + &digits,
+        None => digits,
+    }
+}
+
+/// Format a non-negative value for `%f`, `%e` or `%g` in lowercase.
+fn format_float(value: f64, conversion: u8, precision: Option<usize>, alt: bool) -> String {
+    if value.is_nan() {
+        return "nan".to_string();
+    }
+    if value.is_infinite() {
+        return "inf".to_string();
+    }
+
+    let precision = precision.unwrap_or(6);
+    let mut digits = match conversion.to_ascii_lowercase() {
+        b'f' => format_fixed(value, precision),
+        b'e' => format_exp(value, precision),
+        _ => {
+            let precision = precision.max(1);
+            let exp = i64::from(exponent(value, precision - 1));
+            let digits = if (-4..precision as i64).contains(&exp) {
+                format_fixed(value, (precision as i64 - 1 - exp) as usize)
+            } else {
+                format_exp(value, precision - 1)
+            };
+            if alt {
+                return digits;
+            }
+            strip_trailing_zeros(&digits)
+        }
+    };
+    if alt && precision == 0 && !digits.contains('.') {
+        match digits.find('e') {
+            Some(pos) => digits.insert(pos, '.'),
+            None => digits.push('.'),
+        }
+    }
+    digits
+}
+
+/// `%f` style with `precision` fraction digits.
+fn format_fixed(value: f64, precision: usize) -> String {
+    // format! は大きな精度を扱えないので、値の桁が尽きた先は0を足す
+    let mut digits = format!("{:.*}", precision.min(EXACT_DIGITS), value);
+    pad_zeros(&mut digits, precision);
+    digits
+}
+
+/// `%e` style: `d.ddde+XX` with at least two exponent digits.
+fn format_exp(value: f64, precision: usize) -> String {
+    let exp = exponent(value, precision);
+    let formatted = format!("{:.*e}", precision.min(EXACT_DIGITS), value);
+    let mut mantissa = formatted.split('e').next().unwrap_or_default().to_string();
+    pad_zeros(&mut mantissa, precision);
+    format!(
+        "{}e{}{:02}",
+        mantissa,
+        if exp < 0 { '-' } else { '+' },
+        exp.abs()
+    )
+}
+
+fn pad_zeros(digits: &mut String, precision: usize) {
+    let padding = precision.saturating_sub(EXACT_DIGITS);
+    digits.extend(std::iter::repeat_n('0', padding));
+}
+
+/// Decimal exponent of `value` after rounding to `precision` fraction digits in `%e` style.
+fn exponent(value: f64, precision: usize) -> i32 {
+    let formatted = format!("{:.*e}", precision.min(EXACT_DIGITS), value);
+    formatted
+        .split('e')
+        .nth(1)
+        .and_then(|exp| exp.parse().ok())
+        .unwrap_or(0)
+}
+
+fn strip_trailing_zeros(digits: &str) -> String {
+    let (mantissa, exp) = match digits.find('e') {
+        Some(pos) => digits.split_at(pos),
+        None => (digits, ""),
+    };
+    let mantissa = match mantissa.contains('.') {
+        true => mantissa.trim_end_matches('0').trim_end_matches('.'),
+        false => mantissa,
+    };
+    format!("{}{}", mantissa, exp)
+}
+
+fn truncate(bytes: &[u8], precision: Option<usize>) -> &[u8] {
+    match precision {
+        Some(precision) => &bytes[..precision.min(bytes.len())],
+        None => bytes,
+    }
+}
+
+fn push_padded(out: &mut Vec<u8>, body: &[u8], left: bool, width: usize) {
+    let padding = width.saturating_sub(body.len());
+    if !left {
+        out.resize(out.len() + padding, b' ');
+    }
+    out.extend_from_slice(body);
+    if left {
+        out.resize(out.len() + padding, b' ');
+    }
+}
+
+/// Quote `arg` so that it can be reused as shell input, like `%q` of coreutils printf.
+///
+/// Bytes that are not valid UTF-8 are written as octal escapes, the same way as control characters.
+fn quote(arg: &[u8]) -> String {
+    match std::str::from_utf8(arg) {
+        Ok(text) if !text.chars().any(char::is_control) => quote_printable(text),
+        _ => quote_escaped(arg),
+    }
+}
+
+fn quote_printable(arg: &str) -> String {
+    let is_safe = |(i, c): (usize, char)| {
+        c.is_alphanumeric()
+            || (!c.is_ascii() && !c.is_control())
+            || "%+,-./:@]_{}".contains(c)
+            || (i > 0 && "#~^".contains(c))
+    };
+    if !arg.is_empty() && arg.char_indices().all(is_safe) {
+        return arg.to_string();
+    }
+
+    if arg.contains('\'') && !arg.contains(['"', '$', '`', '\\', '!']) {
+        return format!("\"{}\"", arg);
+    }
+    format!("'{}'", arg.replace('\'', r"'\''"))
+}
+
+fn quote_escaped(arg: &[u8]) -> String {
+    // UTF-8 として読めないバイトは Err として文字と並べる
+    let units = arg.utf8_chunks().flat_map(|chunk| {
+        let invalid = chunk.invalid().iter().map(|&b| Err(b));
+        chunk.valid().chars().map(Ok).chain(invalid)
+    });
+
+    // 制御文字は $'\n' の形式で、それ以外は '' で囲んで連結する
+    let mut quoted = "'".to_string();
+    let mut ends_with_control = false;
+    for unit in units {
+        ends_with_control = unit.is_err() || unit.is_ok_and(char::is_control);
+        match unit {
+            Err(b) => quoted.push_str(&format!(r"'$'\{:03o}''", b)),
+            Ok('\'') => quoted.push_str(r"'\''"),
+            Ok(c) if c.is_control() => {
+                let escaped = match c {
+                    '\x07' => r"\a".to_string(),
+                    '\x08' => r"\b".to_string(),
+                    '\t' => r"\t".to_string(),
+                    '\n' => r"\n".to_string(),
+                    '\x0b' => r"\v".to_string(),
+                    '\x0c' => r"\f".to_string(),
+                    '\r' => r"\r".to_string(),
+                    c => format!(r"\{:03o}", u32::from(c)),
+                };
+                quoted.push_str(&format!("'$'{}''", escaped));
+            }
+            Ok(c) => quoted.push(c),
+        }
+    }
+    if ends_with_control {
+        quoted.truncate(quoted.len() - 1);
+    } else {
+        quoted.push('\'');
+    }
+    quoted
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn to_args(args: &[&str]) -> Vec<Vec<u8>> {
+        args.iter().map(|arg| arg.as_bytes().to_vec()).collect()
+    }
+
+    fn generate(format: &str, args: &[&str]) -> (String, bool) {
+        let output = printf(format.as_bytes(), &to_args(args));
+        (String::from_utf8(output.bytes).unwrap(), output.failed)
+    }
+
+    #[test]
+    fn test_printf() {
+        let cases: &[(&str, &[&str], &str)] = &[
+            (r"%s-%s\n", &["a", "b", "c"], "a-b\nc-\n"),
+            ("%s=%d %.1f|", &["a", "1", "2", "b"], "a=1 2.0|b=0 0.0|"),
+            ("%5s|%-5s|%.2s", &["ab", "cd", "efg"], "   ab|cd   |ef"),
+            (
+                "%d %i %+d % d %05d %.3d %-4d|",
+                &["1", "-2", "3", "4", "-5", "6", "7"],
+                "1 -2 +3  4 -0005 006 7   |",
+            ),
+            (
+                "%u %x %X %o %#x %#o %#X",
+                &["-1", "255", "255", "8", "255", "8", "0"],
+                "18446744073709551615 ff FF 10 0xff 010 0",
+            ),
+            ("%d %d %d %d", &["0x1f", "010", "'A", " 7"], "31 8 65 7"),
+            (
+                "%d %u %u",
+                &[
+                    "-9223372036854775808",
+                    "9223372036854775808",
+                    "-9223372036854775809",
+                ],
+                "-9223372036854775808 9223372036854775808 9223372036854775807",
+            ),
+            (
+                "%*d|%-*d|%.*f",
+                &["5", "1", "4", "2", "-1", "3.14159"],
+                "    1|2   |3.141590",
+            ),
+            (
+                "%f %.2f %5.1f %#.0f",
+                &["1", "2.345", "-3.14", "4"],
+                "1.000000 2.35  -3.1 4.",
+            ),
+            (
+                "%e %.2E %e",
+                &["1234.5", "0.000123", "0"],
+                "1.234500e+03 1.23E-04 0.000000e+00",
+            ),
+            (
+                "%g %g %g %g %G %#g",
+                &["0.0001", "123456789", "100", "0.00001", "1e-10", "1"],
+                "0.0001 1.23457e+08 100 1e-05 1E-10 1.00000",
+            ),
+            (
+                "%f %e %G %5f",
+                &["inf", "-inf", "nan", "inf"],
+                "inf -inf NAN   inf",
+            ),
+            ("%c%c|", &["hello", ""], "h\0|"),
+            (r"%b|", &[r"a\tb\0101"], "a\tbA|"),
+            (r"%b|%s", &[r"a\cb", "x"], "a"),
+            (r"a\c%s", &["x"], "a"),
+            (r#"\"\101\0101\q%%"#, &[], "\"A\x081\\q%"),
+            ("%ld %hd %lf", &["1", "2", "3"], "1 2 3.000000"),
+        ];
+        for (format, args, expected) in cases {
+            assert_eq!(
+                generate(format, args),
+                (expected.to_string(), false),
+                "{}",
+                format
+            );
+        }
+    }
+
+    #[test]
+    fn test_printf_errors() {
+        let output = printf(b"%d %d|", &to_args(&["abc", "12x"]));
+        assert_eq!(output.bytes, b"0 12|");
+        assert_eq!(
+            output.messages,
+            [
+                "'abc': expected a numeric value",
+                "'12x': value not completely converted"
+            ]
+        );
+        assert!(output.failed);
+
+        let output = printf(b"a%zb", &[]);
+        assert_eq!(output.bytes, b"a");
+        assert_eq!(output.messages, ["%zb: invalid conversion specification"]);
+        assert!(output.failed);
+
+        let output = printf(b"abc%", &[]);
+        assert_eq!(output.messages, ["%: invalid conversion specification"]);
+
+        // 幅と精度は int の範囲まで
+        let cases = [
+            ("a%*d|", "99999999999", "invalid field width: '99999999999'"),
+            ("a%*d|", "-2147483649", "invalid field width: '-2147483649'"),
+            ("a%.*f|", "2147483648", "invalid precision: '2147483648'"),
+        ];
+        for (format, arg, message) in cases {
+            let output = printf(format.as_bytes(), &to_args(&[arg, "1"]));
+            assert_eq!(output.bytes, b"a", "{}", format);
+            assert_eq!(output.messages, [message]);
+            assert!(output.failed);
+        }
+        let output = printf(b"a%.*e|", &to_args(&["18446744073709551616", "1"]));
+        assert_eq!(output.bytes, b"a");
+        assert_eq!(
+            output.messages,
+            [
+                "'18446744073709551616': Numerical result out of range",
+                "invalid precision: '18446744073709551616'",
+            ]
+        );
+        assert!(output.failed);
+        for (format, message) in [
+            ("a%99999999999d", "invalid field width: '99999999999'"),
+            ("a%.2147483648f", "invalid precision: '2147483648'"),
+        ] {
+            let output = printf(format.as_bytes(), &to_args(&["1"]));
+            assert_eq!(output.bytes, b"a", "{}", format);
+            assert_eq!(output.messages, [message]);
+            assert!(output.failed);
+        }
+
+        // 範囲外の整数は変換の型の端に丸めて報告する
+        let cases = [
+            ("%d", "99999999999999999999", "9223372036854775807"),
+            ("%i", "9223372036854775808", "9223372036854775807"),
+            ("%d", "-9223372036854775809", "-9223372036854775808"),
+            ("%d", "0x1ffffffffffffffff", "9223372036854775807"),
+            ("%d", "99999999999999999999abc", "9223372036854775807"),
+            ("%u", "99999999999999999999", "18446744073709551615"),
+            ("%x", "-99999999999999999999", "ffffffffffffffff"),
+            ("%o", "0x10000000000000000", "1777777777777777777777"),
+        ];
+        for (format, arg, expected) in cases {
+            let output = printf(format.as_bytes(), &to_args(&[arg]));
+            assert_eq!(output.bytes, expected.as_bytes(), "{} {}", format, arg);
+            assert_eq!(
+                output.messages,
+                [format!("'{}': Numerical result out of range", arg)]
+            );
+            assert!(output.failed);
+        }
+
+        let output = printf(b"x", &to_args(&["extra"]));
+        assert_eq!(output.bytes, b"x");
+        assert_eq!(
+            output.messages,
+            ["warning: ignoring excess arguments, starting with 'extra'"]
+        );
+        assert!(!output.failed);
+    }
+
+    #[test]
+    fn test_printf_large_counts() {
+        // format! の上限を超える幅や精度も扱う
+        let cases: &[(&str, &[&str], usize, &str)] = &[
+            ("%70000s", &["a"], 70000, "a"),
+            ("%.70000d", &["12"], 70000, "12"),
+            ("%.70000f", &["0.5"], 70002, "0"),
+            ("%.70000e", &["-2"], 70007, "0e+00"),
+            ("%#.70000g", &["1"], 70001, "0"),
+            ("%.*f", &["70000", "1"], 70002, "0"),
+        ];
+        for (format, args, len, end) in cases {
+            let (output, failed) = generate(format, args);
+            assert_eq!(output.len(), *len, "{}", format);
+            assert!(output.ends_with(end), "{}", format);
+            assert!(!failed);
+        }
+    }
+
+    #[test]
+    fn test_quote() {
+        let cases = [
+            ("abc", "abc"),
+            ("", "''"),
+            ("a b", "'a b'"),
+            ("it's", "\"it's\""),
+            ("a\"b", "'a\"b'"),
+            ("#a", "'#a'"),
+            ("a#", "a#"),
+            ("x=y", "'x=y'"),
+            ("あ", "あ"),
+            ("a\nb", "'a'$'\\n''b'"),
+            ("\x01", "''$'\\001'"),
+            ("it's\tz", "'it'\\''s'$'\\t''z'"),
+        ];
+        for (arg, expected) in cases {
+            assert_eq!(quote(arg.as_bytes()), expected, "{}", arg);
+        }
+
+        // UTF-8 として読めないバイトは8進数で表す
+        assert_eq!(quote(b"caf\xe9"), "'caf'$'\\351'");
+        assert_eq!(quote(b"\xff"), "''$'\\377'");
+        assert_eq!(quote(b"it's\xe9\n"), "'it'\\''s'$'\\351'''$'\\n'");
+    }
+}
@@ -1,18 +1,75 @@
-use std::fs;
+use std::{env, fs};
 
 use assert_cmd::Command;
 
 const BINARY_NAME: &str = "num2_echor";
+const PRINTF_BINARY_NAME: &str = "printfr";
 const EXPECTED_DIRECTORY: &str = "tests/expected/";
+/// Set this to rewrite the expected files from the current output instead of comparing.
+/// The rewritten files are snapshots; `mk-outs.sh` regenerates the references from GNU tools.
+const UPDATE_ENV: &str = "UPDATE_EXPECTED";
 
 type TestResult = Result<(), Box<dyn std::error::Error>>;
 
 #[test]
-fn dies_no_args() -> TestResult {
+fn no_args() -> TestResult {
     let mut cmd = Command::cargo_bin(BINARY_NAME)?;
-    cmd.assert()
-        .failure()
-        .stderr(predicates::str::contains("Usage"));
+    cmd.assert().success().stdout("\n");
+    Ok(())
+}
+
+#[test]
+fn help() -> TestResult {
+    Command::cargo_bin(BINARY_NAME)?
+        .arg("--help")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Usage: echor"));
+    Command::cargo_bin(BINARY_NAME)?
+        .arg("--version")
+        .assert()
+        .success()
+        .stdout(format!("echor {}\n", env!("CARGO_PKG_VERSION")));
+    Ok(())
+}
+
+#[test]
+fn prints_non_option_dashes() -> TestResult {
+    let cases: &[(&[&str], &str)] = &[
+        (&["--", "-n"], "-- -n\n"),
+        (&["-x", "Hello"], "-x Hello\n"),
+        (&["-nx", "Hello"], "-nx Hello\n"),
+        (&["-", "Hello"], "- Hello\n"),
+        (&["Hello", "-n"], "Hello -n\n"),
+        (&["--help", "Hello"], "--help Hello\n"),
+        (&["-n", "-e", "Hello", "--version"], "Hello --version"),
+    ];
+    for (args, expected) in cases {
+        Command::cargo_bin(BINARY_NAME)?
+            .args(*args)
+            .assert()
+            .success()
+            .stdout(*expected);
+    }
+    Ok(())
+}
+
+#[test]
+fn posixly_correct() -> TestResult {
+    let cases: &[(&[&str], &str)] = &[
+        (&["-e", r"a\tb"], "-e a\tb\n"),
+        (&["-E", r"a\tb"], "-E a\tb\n"),
+        (&["-n", "-E", r"a\tb"], "a\tb"),
+        (&["--help"], "--help\n"),
+    ];
+    for (args, expected) in cases {
+        Command::cargo_bin(BINARY_NAME)?
+            .env("POSIXLY_CORRECT", "1")
+            .args(*args)
+            .assert()
+            .success()
+            .stdout(*expected);
+    }
     Ok(())
 }
 
@@ -35,7 +92,7 @@ fn hello2() -> TestResult {
 
 #[test]
 fn hello1_no_newline() -> TestResult {
-    run(&["Hello  there", "-n"], "hello1.n.txt")
+    run(&["-n", "Hello  there"], "hello1.n.txt")
 }
 
 #[test]
@@ -43,10 +100,170 @@ fn hello2_no_newline() -> TestResult {
     run(&["-n", "Hello", "there"], "hello2.n.txt")
 }
 
+#[cfg(unix)]
+#[test]
+fn non_utf8_args() -> TestResult {
+    use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+    // Latin-1 の "café" と "naïve"
+    let cafe = OsStr::from_bytes(b"caf\xe9");
+    let naive = OsStr::from_bytes(b"na\xefve\\t");
+    Command::cargo_bin(BINARY_NAME)?
+        .arg(cafe)
+        .arg(naive)
+        .assert()
+        .success()
+        .stdout(&b"caf\xe9 na\xefve\\t\n"[..]);
+    Command::cargo_bin(BINARY_NAME)?
+        .arg("-ne")
+        .arg(cafe)
+        .arg(naive)
+        .assert()
+        .success()
+        .stdout(&b"caf\xe9 na\xefve\t"[..]);
+    Ok(())
+}
+
+#[test]
+fn escapes() -> TestResult {
+    // mk-outs.sh で echo から生成した出力と比較する
+    let fixtures: &[(&[&str], &str)] = &[
+        (
+            &["-e", r"tab\there", r"new\nline", r"back\\slash\a\b\f\v\r\e"],
+            "escapes.e.txt",
+        ),
+        (
+            &["-e", r"\0101\0102\0", r"\x41\x4a\x4Z", r"\xff"],
+            "octal_hex.e.txt",
+        ),
+        (&["-e", r"あ\u41", r"\u"], "unicode.e.txt"),
+        (&["-e", r"before\c after", "never"], "stop.e.txt"),
+        (&["-e", "-n", r"trailing\", r"\q\x\xg"], "malformed.e.n.txt"),
+        (&["-E", r"tab\there\c"], "no_escapes.E.txt"),
+        (&["-e", "-E", r"tab\there\c"], "no_escapes.E.txt"),
+        (&[r"tab\there\c"], "no_escapes.E.txt"),
+        (&["-e", r"\101\1012"], "gnu_octal.e.txt"),
+    ];
+    for (args, expected_file) in fixtures {
+        run(args, expected_file)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn printf() -> TestResult {
+    // mk-outs.sh で coreutils の printf から生成した出力と比較する
+    let fixtures: &[(&[&str], &str)] = &[
+        (&[r"%s=%d\n", "a", "1", "b", "2", "c"], "printf_recycle.txt"),
+        (
+            &[r"[%5s|%-5s|%.2s|%c]\n", "ab", "cd", "efg", "hij"],
+            "printf_strings.txt",
+        ),
+        (
+            &[
+                r"%d %+i %05d %.3u %x %#X %#o\n",
+                "42",
+                "7",
+                "-3",
+                "5",
+                "255",
+                "255",
+                "8",
+            ],
+            "printf_ints.txt",
+        ),
+        (
+            &[
+                r"%f %.2e %g %G %8.3f|%-8g|\n",
+                "3.14159",
+                "1234.5",
+                "0.0001",
+                "1e-10",
+                "2.5",
+                "100",
+            ],
+            "printf_floats.txt",
+        ),
+        (
+            &[r"%*d|%-*d|%.*f\n", "5", "1", "4", "2", "1", "3.14159"],
+            "printf_star.txt",
+        ),
+        (
+            &[r"%b|%q|%q\n", r"tab\there\0101", "a b", "it's"],
+            "printf_b_q.txt",
+        ),
+        (&[r"%s\c%s\n", "stop", "never"], "printf_stop.txt"),
+    ];
+    for (args, expected_file) in fixtures {
+        run_binary(PRINTF_BINARY_NAME, args, expected_file)?;
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn printf_non_utf8_args() -> TestResult {
+    use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+    // Latin-1 の "café" と "naïve"
+    let cafe = OsStr::from_bytes(b"caf\xe9");
+    let naive = OsStr::from_bytes(b"na\xefve\\t");
+    Command::cargo_bin(PRINTF_BINARY_NAME)?
+        .arg("%s|%b|%c|%d\n")
+        .arg(cafe)
+        .arg(naive)
+        .arg(naive)
+        .arg(OsStr::from_bytes(b"'\xe9"))
+        .assert()
+        .success()
+        .stdout(&b"caf\xe9|na\xefve\t|n|233\n"[..]);
+    Command::cargo_bin(PRINTF_BINARY_NAME)?
+        .arg(OsStr::from_bytes(b"\xff%q\n"))
+        .arg(cafe)
+        .assert()
+        .success()
+        .stdout(&b"\xff'caf'$'\\351'\n"[..]);
+    Ok(())
+}
+
+#[test]
+fn printf_errors() -> TestResult {
+    Command::cargo_bin(PRINTF_BINARY_NAME)?
+        .assert()
+        .failure()
+        .stderr("printfr: missing operand\nTry 'printfr --help' for more information.\n");
+    Command::cargo_bin(PRINTF_BINARY_NAME)?
+        .args(["%d|%d\n", "abc", "7"])
+        .assert()
+        .failure()
+        .stdout("0|7\n")
+        .stderr("printfr: 'abc': expected a numeric value\n");
+    Command::cargo_bin(PRINTF_BINARY_NAME)?
+        .args(["a%y"])
+        .assert()
+        .failure()
+        .stdout("a")
+        .stderr("printfr: %y: invalid conversion specification\n");
+    Ok(())
+}
+
 fn run(args: &[&str], expected_file: &str) -> TestResult {
+    run_binary(BINARY_NAME, args, expected_file)
+}
+
+fn run_binary(binary_name: &str, args: &[&str], expected_file: &str) -> TestResult {
     let outfile = format!("{}{}", EXPECTED_DIRECTORY, expected_file);
-    let expected = fs::read_to_string(outfile)?;
-    Command::cargo_bin(BINARY_NAME)?
+    if env::var_os(UPDATE_ENV).is_some() {
+        // UPDATE_EXPECTED=1 cargo test で期待値を再生成する。新しいケースは mk-outs.sh にも加える
+        let output = Command::cargo_bin(binary_name)?.args(args).output()?;
+        assert!(output.status.success(), "{} {:?}", binary_name, args);
+        fs::create_dir_all(EXPECTED_DIRECTORY)?;
+        fs::write(outfile, output.stdout)?;
+        return Ok(());
+    }
+
+    let expected = fs::read(outfile)?;
+    Command::cargo_bin(binary_name)?
         .args(args)
         .assert()
         .success()
@@ -0,0 +1,359 @@
+use std::{
+    fs::{self, File, Metadata},
+    io::{self, Read, Seek, SeekFrom, Write},
+    thread,
+    time::Duration,
+};
+
+use crate::{
+    MyResult,
+    number::{LineNumbers, Numbering},
+};
+
+/// How files are followed after EOF.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Follow {
+    /// `-f`: keep reading the open file, even if it is renamed.
+    Descriptor,
+    /// `-F`: reopen the path when the file is rotated, removed or recreated.
+    Name,
+}
+
+#[derive(Debug)]
+pub(crate) struct FollowOptions {
+    pub(crate) follow: Follow,
+    pub(crate) pid: Option<u32>,
+    pub(crate) sleep_interval: Duration,
+}
+
+/// Device and inode numbers, used to notice that a path now names a different file.
+type FileId = (u64, u64);
+
+struct Watch<'a> {
+    name: &'a str,
+    file: Option<File>,
+    id: Option<FileId>,
+    position: u64,
+    numbers: LineNumbers,
+}
+
+/// Print `files` and then keep printing data appended to them until the process given with
+/// `--pid` exits, or forever if there is none.
+pub(crate) fn follow(
+    files: &[String],
+    numbering: Numbering,
+    options: &FollowOptions,
+    out: &mut impl Write,
+) -> MyResult<()> {
+    let mut watches = vec![];
+    for name in files {
+        let mut numbers = LineNumbers::new(numbering);
+        // 標準入力は EOF まで出力するだけで追跡しない
+        if name == "-" {
+            numbers.copy(io::stdin().lock(), out)?;
+            continue;
+        }
+
+        let mut watch = Watch::open(name, numbers);
+        watch.read_appended(out)?;
+        // -F の場合は開けなかったファイルも現れるのを待つ
+        if watch.file.is_some() || options.follow == Follow::Name {
+            watches.push(watch);
+        }
+    }
+    out.flush()?;
+
+    if watches.is_empty() {
+        return Ok(());
+    }
+    loop {
+        // 監視対象のプロセスが終了していても、最後にもう一度読み込んでから終了する
+        let done = options.pid.is_some_and(|pid| !process_exists(pid));
+        for watch in &mut watches {
+            watch.read_appended(out)?;
+            if options.follow == Follow::Name {
+                watch.reopen_if_replaced(out)?;
+            }
+        }
+        out.flush()?;
+
+        if done {
+            return Ok(());
+        }
+        thread::sleep(options.sleep_interval);
+    }
+}
+
+impl<'a> Watch<'a> {
+    /// Start watching `name` from its beginning. A file that cannot be opened is reported and
+    /// left closed.
+    fn open(name: &'a str, numbers: LineNumbers) -> Self {
+        let file = match File::open(name) {
+            Err(err) => {
+                eprintln!("Failed to open! {}: {}", name, err);
+                None
+            }
+            Ok(file) => Some(file),
+        };
+        Watch {
+            name,
+            id: file
+                .as_ref()
+                .and_then(|file| file.metadata().ok())
+                .map(|meta| file_id(&meta)),
+            file,
+            position: 0,
+            numbers,
+        }
+    }
+
+    /// Print whatever was appended since the last read.
+    fn read_appended(&mut self, out: &mut impl Write) -> MyResult<()> {
+        let Some(file) = &mut self.file else {
+            return Ok(());
+        };
+
+        match read_available(file, &mut self.position, self.name, &mut self.numbers, out) {
+            Ok(()) => Ok(()),
+            Err(ReadError::Write(err)) => Err(err.into()),
+            Err(ReadError::Read(err)) => {
+                eprintln!("{}: {}", self.name, err);
+                self.file = None;
+                Ok(())
+            }
+        }
+    }
+
+    /// `-F`: start over from the beginning when the path names a different file than the one
+    /// being read, as happens with rename-based log rotation.
+    fn reopen_if_replaced(&mut self, out: &mut impl Write) -> MyResult<()> {
+        let id = match fs::metadata(self.name) {
+            Err(err) => {
+                if self.file.take().is_some() {
+                    eprintln!("catr: '{}' has become inaccessible: {}", self.name, err);
+                }
+                return Ok(());
+            }
+            Ok(meta) => file_id(&meta),
+        };
+        if self.file.is_some() && self.id == Some(id) {
+            return Ok(());
+        }
+
+        // 開けなければ次の確認で再試行する
+        let Ok(file) = File::open(self.name) else {
+            return Ok(());
+        };
+        let event = if self.file.is_some() {
+            "has been replaced"
+        } else {
+            "has appeared"
+        };
+        eprintln!("catr: '{}' {}; following new file", self.name, event);
+
+        self.id = file.metadata().map(|meta| file_id(&meta)).ok();
+        self.file = Some(file);
+        self.position = 0;
+        self.read_appended(out)
+    }
+}
+
+enum ReadError {
+    Read(io::Error),
+    Write(io::Error),
+}
+
+fn read_available(
+    file: &mut File,
+    position: &mut u64,
+    name: &str,
+    numbers: &mut LineNumbers,
+    out: &mut impl Write,
+) -> Result<(), ReadError> {
+    // 読み込み済みの位置よりファイルが短くなっていれば切り詰められたとみなす
+    let meta = file.metadata().map_err(ReadError::Read)?;
+    if meta.is_file() && meta.len() < *position {
+        eprintln!("catr: {}: file truncated", name);
+        file.seek(SeekFrom::Start(0)).map_err(ReadError::Read)?;
+        *position = 0;
+    }
+
+    let mut buffer = [0; 8192];
+    loop {
+        let size = match file.read(&mut buffer) {
+            Ok(0) => return Ok(()),
+            Ok(size) => size,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(ReadError::Read(err)),
+        };
+        numbers
+            .write(&buffer[..size], out)
+            .map_err(ReadError::Write)?;
+        *position += size as u64;
+    }
+}
+
+#[cfg(unix)]
+fn file_id(meta: &Metadata) -> FileId {
+    use std::os::unix::fs::MetadataExt;
+    (meta.dev(), meta.ino())
+}
+
+/// Without inode numbers a replaced file cannot be told apart, so only truncation is noticed.
+#[cfg(not(unix))]
+fn file_id(_meta: &Metadata) -> FileId {
+    (0, 0)
+}
+
+#[cfg(unix)]
+fn process_exists(pid: u32) -> bool {
+    let Ok(pid) = libc::pid_t::try_from(pid) else {
+        return false;
+    };
+    // シグナル 0 は送信せずにプロセスの存在だけを確認する
+    // SAFETY: kill はポインタを受け取らず、シグナル 0 はプロセスに影響しない
+    let result = unsafe { libc::kill(pid, 0) };
+    result == 0 || io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+#[cfg(not(unix))]
+fn process_exists(_pid: u32) -> bool {
+    true
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::{fs::OpenOptions, path::Path};
+
+    fn append(path: &Path, text: &str) {
+        let mut file = OpenOptions::new()
+            .append(true)
+            .create(true)
+            .open(path)
+            .unwrap();
+        file.write_all(text.as_bytes()).unwrap();
+    }
+
+    /// Output of one `read_appended` call, followed by `reopen_if_replaced` for `-F`.
+    fn poll(watch: &mut Watch, follow: Follow) -> String {
+        let mut out = Vec::new();
+        watch.read_appended(&mut out).unwrap();
+        if follow == Follow::Name {
+            watch.reopen_if_replaced(&mut out).unwrap();
+        }
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_read_appended() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = dir.path().join("log");
+        fs::write(&log, "one\n").unwrap();
+
+        let name = log.to_str().unwrap();
+        let mut watch = Watch::open(name, LineNumbers::new(Numbering::All));
+        assert_eq!(poll(&mut watch, Follow::Descriptor), "     1\tone\n");
+        assert_eq!(poll(&mut watch, Follow::Descriptor), "");
+
+        // 行の途中で読み込みが区切られても番号は行頭にだけ付く
+        append(&log, "two\npar");
+        assert_eq!(
+            poll(&mut watch, Follow::Descriptor),
+            "     2\ttwo\n     3\tpar"
+        );
+        append(&log, "tial\n\nthree\n");
+        assert_eq!(
+            poll(&mut watch, Follow::Descriptor),
+            "tial\n     4\t\n     5\tthree\n"
+        );
+    }
+
+    #[test]
+    fn test_read_appended_truncated() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = dir.path().join("log");
+        fs::write(&log, "a\nb\n").unwrap();
+
+        let name = log.to_str().unwrap();
+        let mut watch = Watch::open(name, LineNumbers::new(Numbering::All));
+        assert_eq!(
+            poll(&mut watch, Follow::Descriptor),
+            "     1\ta\n     2\tb\n"
+        );
+
+        fs::write(&log, "c\n").unwrap();
+        assert_eq!(poll(&mut watch, Follow::Descriptor), "     3\tc\n");
+    }
+
+    #[test]
+    fn test_descriptor_keeps_renamed_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = dir.path().join("log");
+        let rotated = dir.path().join("log.1");
+        fs::write(&log, "old\n").unwrap();
+
+        let name = log.to_str().unwrap();
+        let mut watch = Watch::open(name, LineNumbers::new(Numbering::None));
+        assert_eq!(poll(&mut watch, Follow::Descriptor), "old\n");
+
+        fs::rename(&log, &rotated).unwrap();
+        append(&rotated, "still old\n");
+        append(&log, "new\n");
+        assert_eq!(poll(&mut watch, Follow::Descriptor), "still old\n");
+        assert_eq!(poll(&mut watch, Follow::Descriptor), "");
+    }
+
+    #[test]
+    fn test_reopen_if_replaced() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = dir.path().join("log");
+        let rotated = dir.path().join("log.1");
+        fs::write(&log, "one\n").unwrap();
+
+        let name = log.to_str().unwrap();
+        let mut watch = Watch::open(name, LineNumbers::new(Numbering::All));
+        assert_eq!(poll(&mut watch, Follow::Name), "     1\tone\n");
+
+        // リネームした直後に書かれた分も、新しいファイルに移る前に読む
+        append(&log, "two\n");
+        fs::rename(&log, &rotated).unwrap();
+        assert_eq!(poll(&mut watch, Follow::Name), "     2\ttwo\n");
+        assert!(watch.file.is_none());
+
+        append(&log, "three\n");
+        assert_eq!(poll(&mut watch, Follow::Name), "     3\tthree\n");
+
+        // 新しいファイルへ一度に置き換えられる場合
+        let tmp = dir.path().join("log.tmp");
+        fs::write(&tmp, "four\n").unwrap();
+        fs::rename(&tmp, &log).unwrap();
+        assert_eq!(poll(&mut watch, Follow::Name), "     4\tfour\n");
+        assert_eq!(poll(&mut watch, Follow::Name), "");
+    }
+
+    #[test]
+    fn test_reopen_if_replaced_missing() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = dir.path().join("log");
+
+        let name = log.to_str().unwrap();
+        let mut watch = Watch::open(name, LineNumbers::new(Numbering::None));
+        assert!(watch.file.is_none());
+        assert_eq!(poll(&mut watch, Follow::Name), "");
+
+        append(&log, "hello\n");
+        assert_eq!(poll(&mut watch, Follow::Name), "hello\n");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_process_exists() {
+        assert!(process_exists(std::process::id()));
+
+        let mut dead = std::process::Command::new("true").spawn().unwrap();
+        let pid = dead.id();
+        dead.wait().unwrap();
+        assert!(!process_exists(pid));
+    }
+}
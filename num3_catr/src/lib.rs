@@ -1,21 +1,33 @@
 use std::{
+    env,
     error::Error,
+    ffi::OsString,
     fs::File,
-    io::{self, BufRead, BufReader},
+    io::{self, BufRead, BufReader, Write},
+    time::Duration,
 };
 
-use clap::Parser;
+use clap::{ArgGroup, Parser, value_parser};
+
+mod follow;
+mod number;
+
+use follow::{Follow, FollowOptions};
+use number::{LineNumbers, Numbering};
+pub use number::{NumberColumn, NumberFormat};
 
 #[derive(Debug)]
 pub struct Config {
     files: Vec<String>,
     number_lines: bool,
     number_nonblank_lines: bool,
+    follow: Option<FollowOptions>,
 }
 
 #[derive(Parser, Debug)]
 #[command(version)]
 #[command(about = "Rust cat")]
+#[command(group(ArgGroup::new("follow_mode").args(["follow", "follow_name"])))]
 struct Cli {
     /// Target files.
     #[arg(num_args = 1.., default_values_t = ["-".to_string()])]
@@ -28,48 +40,87 @@ struct Cli {
     /// Is display number line if non blank.
     #[arg(short('b'), long, default_value_t = false)]
     number_nonblank: bool,
+
+    /// Keep printing data appended to the files after EOF.
+    #[arg(short('f'), long, overrides_with = "follow_name")]
+    follow: bool,
+
+    /// Like --follow, but reopen files that are rotated, removed or recreated.
+    #[arg(short('F'), long, overrides_with = "follow")]
+    follow_name: bool,
+
+    /// With --follow, exit after the process PID dies.
+    #[arg(long, requires = "follow_mode", value_parser = value_parser!(u32).range(1..=i32::MAX as i64))]
+    pid: Option<u32>,
+
+    /// With --follow, seconds to wait between checks for new data.
+    #[arg(
+        long,
+        value_name = "N",
+        default_value = "1.0",
+        requires = "follow_mode",
+        value_parser = parse_sleep_interval
+    )]
+    sleep_interval: Duration,
 }
 
 type MyResult<T> = Result<T, Box<dyn Error>>;
 
 pub fn run(config: Config) -> MyResult<()> {
+    let numbering = Numbering::new(config.number_lines, config.number_nonblank_lines);
+    let mut stdout = io::stdout().lock();
+    if let Some(options) = &config.follow {
+        return follow::follow(&config.files, numbering, options, &mut stdout);
+    }
+
     for file_name in config.files {
         match open(&file_name) {
             Err(err) => eprintln!("Failed to open! {}: {}", file_name, err),
             Ok(buf_read) => {
-                let mut line_count = 1;
-                for line in buf_read.lines() {
-                    let line_string = line.unwrap();
-                    if config.number_nonblank_lines && line_string.is_empty() {
-                        println!();
-                        continue;
-                    }
-                    println!(
-                        "{}{}",
-                        if config.number_lines || config.number_nonblank_lines {
-                            format!("{0: >6}\t", line_count)
-                        } else {
-                            "".to_string()
-                        },
-                        line_string
-                    );
-                    line_count += 1;
-                }
+                // 行番号はファイルごとに 1 から振り直す
+                LineNumbers::new(numbering).copy(buf_read, &mut stdout)?;
             }
         }
     }
+    stdout.flush()?;
     Ok(())
 }
 
 pub fn get_args() -> MyResult<Config> {
-    let args = Cli::parse();
+    get_args_from(env::args_os())
+}
+
+pub fn get_args_from<I, T>(args: I) -> MyResult<Config>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<OsString> + Clone,
+{
+    let args = Cli::parse_from(args);
+    let follow = match (args.follow, args.follow_name) {
+        (_, true) => Some(Follow::Name),
+        (true, false) => Some(Follow::Descriptor),
+        (false, false) => None,
+    };
     Ok(Config {
         files: args.files,
         number_lines: args.number,
         number_nonblank_lines: args.number_nonblank,
+        follow: follow.map(|follow| FollowOptions {
+            follow,
+            pid: args.pid,
+            sleep_interval: args.sleep_interval,
+        }),
     })
 }
 
+fn parse_sleep_interval(val: &str) -> Result<Duration, String> {
+    // 負の値や NaN は Duration にできないため、ここでまとめて弾かれる
+    val.parse()
+        .ok()
+        .and_then(|seconds| Duration::try_from_secs_f64(seconds).ok())
+        .ok_or_else(|| format!("invalid number of seconds: '{}'", val))
+}
+
 fn open(file_name: &str) -> MyResult<Box<dyn BufRead>> {
     match file_name {
         "-" => Ok(Box::new(BufReader::new(io::stdin()))),
@@ -0,0 +1,199 @@
+use std::io::{self, Read, Write};
+
+/// Which lines get a number, as chosen with `-n` and `-b`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Numbering {
+    None,
+    All,
+    NonBlank,
+}
+
+impl Numbering {
+    pub(crate) fn new(number_lines: bool, number_nonblank_lines: bool) -> Self {
+        match (number_lines, number_nonblank_lines) {
+            (_, true) => Numbering::NonBlank,
+            (true, false) => Numbering::All,
+            (false, false) => Numbering::None,
+        }
+    }
+}
+
+/// How a line number is justified in its column, as chosen with `nl -n`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NumberFormat {
+    /// Left justified, `ln`.
+    Left,
+    /// Right justified, `rn`.
+    Right,
+    /// Right justified with leading zeros, `rz`.
+    RightZero,
+}
+
+/// The column line numbers are written in: `width` characters and then `separator`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NumberColumn {
+    format: NumberFormat,
+    width: usize,
+    separator: String,
+}
+
+impl Default for NumberColumn {
+    /// The column of `cat -n` and `nl`: six characters, right justified, and a tab.
+    fn default() -> Self {
+        NumberColumn::new(NumberFormat::Right, 6, "\t")
+    }
+}
+
+impl NumberColumn {
+    pub fn new(format: NumberFormat, width: usize, separator: &str) -> Self {
+        NumberColumn {
+            format,
+            width,
+            separator: separator.to_string(),
+        }
+    }
+
+    /// Write `number` and the separator. Numbers wider than the column are written in full.
+    pub fn write(&self, number: i64, out: &mut impl Write) -> io::Result<()> {
+        let width = self.width;
+        match self.format {
+            NumberFormat::Left => write!(out, "{:<width$}", number)?,
+            NumberFormat::Right => write!(out, "{:>width$}", number)?,
+            NumberFormat::RightZero => write!(out, "{:0width$}", number)?,
+        }
+        out.write_all(self.separator.as_bytes())
+    }
+
+    /// Write the spaces taking the place of the number and the separator on unnumbered lines.
+    pub fn write_blank(&self, out: &mut impl Write) -> io::Result<()> {
+        let blank = self.width + self.separator.len();
+        write!(out, "{:blank$}", "")
+    }
+}
+
+/// Prefixes lines with their numbers while bytes are streamed through, so that a line split
+/// across reads, or across appends in follow mode, is numbered only once.
+#[derive(Debug)]
+pub(crate) struct LineNumbers {
+    numbering: Numbering,
+    column: NumberColumn,
+    next: i64,
+    at_line_start: bool,
+}
+
+impl LineNumbers {
+    pub(crate) fn new(numbering: Numbering) -> Self {
+        LineNumbers {
+            numbering,
+            column: NumberColumn::default(),
+            next: 1,
+            at_line_start: true,
+        }
+    }
+
+    pub(crate) fn write(&mut self, bytes: &[u8], out: &mut impl Write) -> io::Result<()> {
+        if self.numbering == Numbering::None {
+            return out.write_all(bytes);
+        }
+
+        for line in bytes.split_inclusive(|&b| b == b'\n') {
+            // -b の場合、空行には番号を振らない
+            let is_blank = line == b"\n";
+            if self.at_line_start && !(self.numbering == Numbering::NonBlank && is_blank) {
+                self.column.write(self.next, out)?;
+                self.next += 1;
+            }
+            out.write_all(line)?;
+            self.at_line_start = line.ends_with(b"\n");
+        }
+        Ok(())
+    }
+
+    /// Number everything read from `reader` until EOF.
+    pub(crate) fn copy(&mut self, mut reader: impl Read, out: &mut impl Write) -> io::Result<()> {
+        let mut buffer = [0; 8192];
+        loop {
+            let size = match reader.read(&mut buffer) {
+                Ok(0) => return Ok(()),
+                Ok(size) => size,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                Err(err) => return Err(err),
+            };
+            self.write(&buffer[..size], out)?;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn number(numbering: Numbering, chunks: &[&str]) -> String {
+        let mut numbers = LineNumbers::new(numbering);
+        let mut out = Vec::new();
+        for chunk in chunks {
+            numbers.write(chunk.as_bytes(), &mut out).unwrap();
+        }
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_numbering_new() {
+        assert_eq!(Numbering::new(false, false), Numbering::None);
+        assert_eq!(Numbering::new(true, false), Numbering::All);
+        assert_eq!(Numbering::new(false, true), Numbering::NonBlank);
+        assert_eq!(Numbering::new(true, true), Numbering::NonBlank);
+    }
+
+    fn column(column: &NumberColumn, number: Option<i64>) -> String {
+        let mut out = Vec::new();
+        match number {
+            Some(number) => column.write(number, &mut out).unwrap(),
+            None => column.write_blank(&mut out).unwrap(),
+        }
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_number_column() {
+        let default = NumberColumn::default();
+        assert_eq!(column(&default, Some(12)), "    12\t");
+        assert_eq!(column(&default, Some(1234567)), "1234567\t");
+        assert_eq!(column(&default, None), "       ");
+
+        let left = NumberColumn::new(NumberFormat::Left, 3, ": ");
+        assert_eq!(column(&left, Some(7)), "7  : ");
+        assert_eq!(column(&left, None), "     ");
+
+        // 負の数は符号の後を 0 で埋める
+        let zero = NumberColumn::new(NumberFormat::RightZero, 4, "");
+        assert_eq!(column(&zero, Some(42)), "0042");
+        assert_eq!(column(&zero, Some(-2)), "-002");
+    }
+
+    #[test]
+    fn test_line_numbers() {
+        assert_eq!(number(Numbering::None, &["a\n", "\nb"]), "a\n\nb");
+        assert_eq!(
+            number(Numbering::All, &["a\n\nb"]),
+            "     1\ta\n     2\t\n     3\tb"
+        );
+        assert_eq!(
+            number(Numbering::NonBlank, &["a\n\n", "b\n"]),
+            "     1\ta\n\n     2\tb\n"
+        );
+    }
+
+    #[test]
+    fn test_line_numbers_split_lines() {
+        // 行の途中で区切られても、次の行頭まで番号は振らない
+        assert_eq!(
+            number(Numbering::All, &["par", "tial\nne", "", "xt\n"]),
+            "     1\tpartial\n     2\tnext\n"
+        );
+        assert_eq!(
+            number(Numbering::NonBlank, &["a\n", "\n", "\n", "b"]),
+            "     1\ta\n\n\n     2\tb"
+        );
+    }
+}
@@ -3,7 +3,12 @@ use assert_cmd::Command;
 use predicates::prelude::*;
 use pretty_assertions::assert_eq;
 use rand::{distr::Alphanumeric, Rng};
-use std::fs;
+use std::fs::{self, OpenOptions};
+use std::io::{Read, Write};
+use std::process::{self, Stdio};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::{Duration, Instant};
 
 const PRG: &str = "num3_catr";
 const EMPTY: &str = "tests/inputs/empty.txt";
@@ -201,3 +206,101 @@ fn all_n() -> Result<()> {
 fn all_b() -> Result<()> {
     run(&[FOX, SPIDERS, BUSTLE, "-b"], "tests/expected/all.b.out")
 }
+
+// --------------------------------------------------
+#[test]
+fn dies_pid_without_follow() -> Result<()> {
+    Command::cargo_bin(PRG)?
+        .args(["--pid", "1", FOX])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--follow"));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_sleep_interval_without_follow() -> Result<()> {
+    Command::cargo_bin(PRG)?
+        .args(["--sleep-interval", "5", FOX])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--follow"))
+        .stdout("");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_sleep_interval() -> Result<()> {
+    Command::cargo_bin(PRG)?
+        .args(["-f", "--sleep-interval=-1", FOX])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid number of seconds"));
+    Ok(())
+}
+
+// --------------------------------------------------
+// 追跡モードの細かな動作は follow.rs の単体テストで確かめ、ここでは
+// 実際に追記が出力されることだけを確認する。出力が現れるまで待つので、
+// 期限は十分に長くとる
+const DEADLINE: Duration = Duration::from_secs(30);
+
+fn wait_for_output(
+    output: &Receiver<Vec<u8>>,
+    received: &mut Vec<u8>,
+    expected: &str,
+) -> Result<()> {
+    let deadline = Instant::now() + DEADLINE;
+    while received != expected.as_bytes() {
+        let timeout = deadline.saturating_duration_since(Instant::now());
+        match output.recv_timeout(timeout) {
+            Ok(chunk) => received.extend(chunk),
+            Err(_) => anyhow::bail!(
+                "expected {expected:?}, got {:?}",
+                String::from_utf8_lossy(received)
+            ),
+        }
+    }
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn follow_appended() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let log = dir.path().join("log");
+    fs::write(&log, "one\n")?;
+
+    let mut catr = process::Command::new(assert_cmd::cargo::cargo_bin(PRG))
+        .args(["-n", "-f", "--sleep-interval", "0.05"])
+        .arg(&log)
+        .stdout(Stdio::piped())
+        .spawn()?;
+    let mut stdout = catr.stdout.take().unwrap();
+    let (sender, output) = mpsc::channel();
+    thread::spawn(move || {
+        let mut buffer = [0; 1024];
+        while let Ok(size @ 1..) = stdout.read(&mut buffer) {
+            if sender.send(buffer[..size].to_vec()).is_err() {
+                break;
+            }
+        }
+    });
+
+    let mut received = vec![];
+    let result = wait_for_output(&output, &mut received, "     1\tone\n")
+        .and_then(|()| {
+            let mut file = OpenOptions::new().append(true).open(&log)?;
+            file.write_all(b"two\n")?;
+            wait_for_output(
+                &output,
+                &mut received,
+                "     1\tone\n     2\ttwo\n",
+            )
+        });
+    catr.kill()?;
+    catr.wait()?;
+    result
+}
@@ -1,10 +1,20 @@
 use std::{
+    collections::HashMap,
+    env,
     error::Error,
+    ffi::OsString,
     fs::File,
     io::{self, BufRead, BufReader, Read},
+    num::NonZeroUsize,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc,
+    },
+    thread,
 };
 
-use clap::{ArgGroup, Parser};
+use clap::{ArgGroup, Parser, ValueEnum};
+use serde::Serialize;
 
 type MyResult<T> = Result<T, Box<dyn Error>>;
 
@@ -13,59 +23,215 @@ pub struct Config {
     files: Vec<String>,
     lines: usize,
     bytes: Option<usize>,
+    jobs: usize,
+    format: Format,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+enum Format {
+    Text,
+    Json,
 }
 
 pub fn get_args() -> MyResult<Config> {
-    let args = Cli::parse();
+    get_args_from(env::args_os())
+}
+
+pub fn get_args_from<I, T>(args: I) -> MyResult<Config>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<OsString> + Clone,
+{
+    let args = Cli::parse_from(args);
     Ok(Config {
         files: args.files,
         lines: args.lines,
         bytes: args.bytes,
+        jobs: args.jobs.get(),
+        format: args.format,
     })
 }
 
 pub fn run(config: Config) -> MyResult<()> {
-    let mut is_first_file = true;
-    let is_multiple_line = config.files.len() > 1;
-
-    for file_name in config.files {
-        match open(&file_name) {
-            Err(err) => eprintln!("{}: {}", file_name, err),
-            Ok(mut read_buf) => {
-                // 二つ目以降のファイル出力の場合は空行を空ける
-                if is_first_file {
-                    is_first_file = false;
-                } else {
-                    println!();
-                }
+    match config.format {
+        Format::Text => {
+            let mut is_first_file = true;
+            let is_multiple_line = config.files.len() > 1;
+            for_each_head(&config, |file_name, head| {
+                print_head(file_name, head, &mut is_first_file, is_multiple_line)
+            })
+        }
+        Format::Json => {
+            let mut records = vec![];
+            for_each_head(&config, |file_name, head| {
+                records.push(HeadRecord::new(file_name, head));
+                Ok(())
+            })?;
+            println!("{}", serde_json::to_string(&records)?);
+            Ok(())
+        }
+    }
+}
 
-                if is_multiple_line {
-                    println!("==> {} <==", file_name);
-                }
+/// 各ファイルの先頭を読み込み、引数の順に`f`へ渡す
+fn for_each_head(config: &Config, mut f: impl FnMut(&str, Head) -> MyResult<()>) -> MyResult<()> {
+    if config.jobs > 1 && config.files.len() > 1 {
+        return prefetch_heads(config, f);
+    }
+
+    for file_name in &config.files {
+        f(file_name, read_head(file_name, config))?;
+    }
+    Ok(())
+}
+
+/// 複数のファイルの先頭をスレッドで並行に読み込み、引数の順に`f`へ渡す
+fn prefetch_heads(config: &Config, mut f: impl FnMut(&str, Head) -> MyResult<()>) -> MyResult<()> {
+    let next_index = AtomicUsize::new(0);
+    let (tx, rx) = mpsc::channel();
 
-                if let Some(bytes) = config.bytes {
-                    let mut handle = read_buf.take(bytes as u64);
-
-                    let mut buffer = vec![0; bytes];
-                    let result_bytes = handle.read(&mut buffer)?;
-                    print!("{}", String::from_utf8_lossy(&buffer[..result_bytes]));
-                } else {
-                    for _ in 0..config.lines {
-                        let mut line = String::new();
-                        let result_bytes = read_buf.read_line(&mut line)?;
-
-                        if result_bytes == 0 {
-                            break;
-                        }
-                        print!("{}", line);
+    thread::scope(|scope| {
+        for _ in 0..config.jobs.min(config.files.len()) {
+            let tx = tx.clone();
+            let next_index = &next_index;
+            scope.spawn(move || {
+                loop {
+                    let index = next_index.fetch_add(1, Ordering::Relaxed);
+                    let Some(file_name) = config.files.get(index) else {
+                        break;
+                    };
+                    let head = read_head(file_name, config);
+                    // 受信側がエラーで終了している場合は送信できないが、そのまま読み捨てる
+                    if tx.send((index, head)).is_err() {
+                        break;
                     }
                 }
+            });
+        }
+        drop(tx);
+
+        // 先に読み終わったファイルは順番が来るまで保持しておく
+        let mut pending = HashMap::new();
+        let mut next_print = 0;
+        for (index, head) in rx {
+            pending.insert(index, head);
+            while let Some(head) = pending.remove(&next_print) {
+                f(&config.files[next_print], head)?;
+                next_print += 1;
             }
         }
+        Ok(())
+    })
+}
+
+#[derive(Debug)]
+enum Head {
+    OpenFailed(String),
+    ReadFailed(String),
+    Content { content: String, truncated: bool },
+}
+
+fn read_head(file_name: &str, config: &Config) -> Head {
+    // 続きがあるかは JSON でしか使わない。パイプで次のデータを待たないよう、テキストでは確かめない
+    let peek = config.format == Format::Json;
+    match open(file_name) {
+        Err(err) => Head::OpenFailed(err.to_string()),
+        Ok(read_buf) => match take_head(read_buf, config.lines, config.bytes, peek) {
+            Err(err) => Head::ReadFailed(err.to_string()),
+            Ok((content, truncated)) => Head::Content { content, truncated },
+        },
     }
+}
+
+/// 先頭部分と、`peek`の場合はその後ろにまだデータが残っているかを返す
+fn take_head(
+    mut read_buf: impl BufRead,
+    lines: usize,
+    bytes: Option<usize>,
+    peek: bool,
+) -> MyResult<(String, bool)> {
+    if let Some(bytes) = bytes {
+        let mut buffer = Vec::with_capacity(bytes);
+        read_buf
+            .by_ref()
+            .take(bytes as u64)
+            .read_to_end(&mut buffer)?;
+        let truncated = peek && !read_buf.fill_buf()?.is_empty();
+        return Ok((String::from_utf8_lossy(&buffer).into_owned(), truncated));
+    }
+
+    let mut content = String::new();
+    for _ in 0..lines {
+        let result_bytes = read_buf.read_line(&mut content)?;
+
+        if result_bytes == 0 {
+            break;
+        }
+    }
+    let truncated = peek && !read_buf.fill_buf()?.is_empty();
+    Ok((content, truncated))
+}
+
+fn print_head(
+    file_name: &str,
+    head: Head,
+    is_first_file: &mut bool,
+    is_multiple_line: bool,
+) -> MyResult<()> {
+    let content = match head {
+        Head::OpenFailed(err) => {
+            eprintln!("{}: {}", file_name, err);
+            return Ok(());
+        }
+        Head::ReadFailed(err) => Err(err),
+        Head::Content { content, .. } => Ok(content),
+    };
+
+    // 二つ目以降のファイル出力の場合は空行を空ける
+    if *is_first_file {
+        *is_first_file = false;
+    } else {
+        println!();
+    }
+
+    if is_multiple_line {
+        println!("==> {} <==", file_name);
+    }
+
+    print!("{}", content?);
     Ok(())
 }
 
+#[derive(Debug, Serialize)]
+struct HeadRecord {
+    file: String,
+    lines: Vec<String>,
+    truncated: bool,
+    error: Option<String>,
+}
+
+impl HeadRecord {
+    fn new(file_name: &str, head: Head) -> Self {
+        let (lines, truncated, error) = match head {
+            Head::OpenFailed(err) | Head::ReadFailed(err) => (vec![], false, Some(err)),
+            Head::Content { content, truncated } => (
+                content
+                    .split_inclusive('\n')
+                    .map(|line| line.trim_end_matches(['\n', '\r']).to_string())
+                    .collect(),
+                truncated,
+                None,
+            ),
+        };
+        HeadRecord {
+            file: file_name.to_string(),
+            lines,
+            truncated,
+            error,
+        }
+    }
+}
+
 #[derive(Parser, Debug)]
 #[command(version)]
 #[command(about = "Rust head")]
@@ -82,6 +248,14 @@ struct Cli {
     /// Is display number line if non blank.
     #[arg(short('c'), long)]
     bytes: Option<usize>,
+
+    /// Number of files to read concurrently.
+    #[arg(short('j'), long, default_value_t = NonZeroUsize::MIN)]
+    jobs: NonZeroUsize,
+
+    /// Output format.
+    #[arg(long, value_enum, default_value_t = Format::Text)]
+    format: Format,
 }
 
 #[allow(dead_code)]
@@ -99,6 +273,48 @@ fn open(file_name: &str) -> MyResult<Box<dyn BufRead>> {
     }
 }
 
+#[test]
+fn test_take_head() {
+    let text = "one\ntwo\r\nthree";
+
+    let res_lines = take_head(io::Cursor::new(text), 2, None, true);
+    assert!(res_lines.is_ok());
+    assert_eq!(res_lines.unwrap(), ("one\ntwo\r\n".to_string(), true));
+
+    let res_all = take_head(io::Cursor::new(text), 10, None, true);
+    assert!(res_all.is_ok());
+    assert_eq!(res_all.unwrap(), (text.to_string(), false));
+
+    let res_bytes = take_head(io::Cursor::new(text), 10, Some(5), true);
+    assert!(res_bytes.is_ok());
+    assert_eq!(res_bytes.unwrap(), ("one\nt".to_string(), true));
+
+    let res_exact = take_head(io::Cursor::new(text), 10, Some(text.len()), true);
+    assert!(res_exact.is_ok());
+    assert_eq!(res_exact.unwrap(), (text.to_string(), false));
+
+    let res_no_peek = take_head(io::Cursor::new(text), 2, None, false);
+    assert!(res_no_peek.is_ok());
+    assert_eq!(res_no_peek.unwrap(), ("one\ntwo\r\n".to_string(), false));
+}
+
+#[test]
+fn test_head_record() {
+    let head = Head::Content {
+        content: "one\ntwo\r\nthr".to_string(),
+        truncated: true,
+    };
+    let record = HeadRecord::new("a.txt", head);
+    assert_eq!(record.lines, ["one", "two", "thr"]);
+    assert!(record.truncated);
+    assert_eq!(record.error, None);
+
+    let record = HeadRecord::new("b.txt", Head::OpenFailed("oops".to_string()));
+    assert!(record.lines.is_empty());
+    assert!(!record.truncated);
+    assert_eq!(record.error, Some("oops".to_string()));
+}
+
 #[test]
 fn test_parse_positive_int() {
     let res_positive = parse_positive_int("3");
@@ -6,6 +6,9 @@ use rand::distr::Alphanumeric;
 use rand::Rng;
 use std::fs::{self, File};
 use std::io::prelude::*;
+use std::process::Stdio;
+use std::thread;
+use std::time::{Duration, Instant};
 
 const PRG: &str = "num4_headr";
 const EMPTY: &str = "./tests/inputs/empty.txt";
@@ -424,3 +427,149 @@ fn multiple_files_c4() -> Result<()> {
         "tests/expected/all.c4.out",
     )
 }
+
+// --------------------------------------------------
+#[test]
+fn dies_zero_jobs() -> Result<()> {
+    Command::cargo_bin(PRG)?
+        .args(["-j", "0", EMPTY])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "invalid value '0' for '--jobs <JOBS>'",
+        ));
+
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn skips_bad_file_jobs() -> Result<()> {
+    let bad = gen_bad_file();
+    let expected = format!("{bad}: .* [(]os error 2[)]");
+    Command::cargo_bin(PRG)?
+        .args(["-j", "2", EMPTY, &bad, ONE])
+        .assert()
+        .success()
+        .stderr(predicate::str::is_match(expected)?);
+
+    Ok(())
+}
+
+#[test]
+fn multiple_files_jobs() -> Result<()> {
+    run(
+        &["-j", "3", EMPTY, ONE, TWO, THREE, TWELVE],
+        "tests/expected/all.out",
+    )
+}
+
+#[test]
+fn multiple_files_n2_jobs() -> Result<()> {
+    run(
+        &["--jobs", "8", EMPTY, ONE, TWO, THREE, TWELVE, "-n", "2"],
+        "tests/expected/all.n2.out",
+    )
+}
+
+#[test]
+fn multiple_files_c4_jobs() -> Result<()> {
+    run(
+        &["-j", "2", "-c", "4", EMPTY, ONE, TWO, THREE, TWELVE],
+        "tests/expected/all.c4.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_format() -> Result<()> {
+    Command::cargo_bin(PRG)?
+        .args(["--format", "yaml", EMPTY])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "invalid value 'yaml' for '--format <FORMAT>'",
+        ));
+
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn bad_file_json() -> Result<()> {
+    let bad = gen_bad_file();
+    let expected =
+        format!(r#"{{"file":"{bad}","lines":[],"truncated":false,"error":"#);
+    Command::cargo_bin(PRG)?
+        .args(["--format", "json", &bad])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains(expected)
+                .and(predicate::str::contains("(os error 2)")),
+        );
+
+    Ok(())
+}
+
+#[test]
+fn one_c2_json() -> Result<()> {
+    run(
+        &["--format", "json", ONE, "-c", "2"],
+        "tests/expected/one.txt.c2.json.out",
+    )
+}
+
+#[test]
+fn multiple_files_n2_json() -> Result<()> {
+    run(
+        &[
+            "--format", "json", "-n", "2", EMPTY, ONE, TWO, THREE, TWELVE,
+        ],
+        "tests/expected/all.n2.json.out",
+    )
+}
+
+#[test]
+fn multiple_files_n2_json_jobs() -> Result<()> {
+    run(
+        &[
+            "--format", "json", "-j", "3", "-n", "2", EMPTY, ONE, TWO, THREE,
+            TWELVE,
+        ],
+        "tests/expected/all.n2.json.out",
+    )
+}
+
+// --------------------------------------------------
+fn exits_before_pipe_closes(args: &[&str], input: &str) -> Result<String> {
+    // 書き手が次のデータを書かないまま、必要な分だけ読んで終了する
+    let mut child =
+        std::process::Command::new(assert_cmd::cargo::cargo_bin(PRG))
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+    let mut stdin = child.stdin.take().unwrap();
+    stdin.write_all(input.as_bytes())?;
+    stdin.flush()?;
+
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while child.try_wait()?.is_none() {
+        assert!(Instant::now() < deadline, "{args:?} waited for more input");
+        thread::sleep(Duration::from_millis(10));
+    }
+    drop(stdin);
+
+    let output = child.wait_with_output()?;
+    assert!(output.status.success());
+    Ok(String::from_utf8(output.stdout)?)
+}
+
+#[test]
+fn does_not_wait_for_pipe() -> Result<()> {
+    for (args, input) in [(["-n", "1"], "one\n"), (["-c", "6"], "one\ntw")] {
+        assert_eq!(exits_before_pipe_closes(&args, input)?, input);
+    }
+    Ok(())
+}
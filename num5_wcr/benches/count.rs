@@ -0,0 +1,86 @@
+//! Streaming vs. memory-mapped counting.
+//!
+//! The input size defaults to 32 MiB and can be raised to multi-GB inputs with
+//! `WCR_BENCH_BYTES`, e.g.
+//!
+//! ```sh
+//! WCR_BENCH_BYTES=4294967296 cargo bench --features mmap
+//! ```
+//!
+//! The input is written once under `target/tmp` and reused by later runs of the same size.
+
+use std::{
+    env,
+    fs::{self, File},
+    hint::black_box,
+    io::{BufReader, BufWriter, Write},
+    path::Path,
+};
+
+use criterion::{Criterion, Throughput, criterion_group, criterion_main};
+
+const DEFAULT_BYTES: u64 = 32 * 1024 * 1024;
+const SOURCES: [&str; 2] = ["tests/inputs/atlamal.txt", "tests/inputs/fox.txt"];
+
+fn bench_bytes() -> u64 {
+    env::var("WCR_BENCH_BYTES")
+        .ok()
+        .and_then(|val| val.parse().ok())
+        .unwrap_or(DEFAULT_BYTES)
+}
+
+fn generate_input(path: &Path, size: u64) -> u64 {
+    // 前回の実行で作ったものがあれば使い回す
+    if let Ok(metadata) = fs::metadata(path) {
+        return metadata.len();
+    }
+
+    let chunk: String = SOURCES
+        .iter()
+        .map(|source| fs::read_to_string(source).expect("failed to read bench source"))
+        .collect();
+
+    // 途中で止めても壊れたものを使い回さないよう、書き終えてから名前を付ける
+    let partial = path.with_extension("part");
+    let mut writer = BufWriter::new(File::create(&partial).expect("failed to create bench input"));
+    let mut written = 0;
+    while written < size {
+        writer
+            .write_all(chunk.as_bytes())
+            .expect("failed to write bench input");
+        written += chunk.len() as u64;
+    }
+    writer.flush().expect("failed to write bench input");
+    fs::rename(&partial, path).expect("failed to write bench input");
+    written
+}
+
+fn bench_count(c: &mut Criterion) {
+    let size = bench_bytes();
+    let path = Path::new(env!("CARGO_TARGET_TMPDIR")).join(format!("num5_wcr_bench_{size}.txt"));
+    let written = generate_input(&path, size);
+
+    let mut group = c.benchmark_group("count");
+    group.sample_size(10);
+    group.throughput(Throughput::Bytes(written));
+
+    group.bench_function("stream", |b| {
+        b.iter(|| {
+            let file = File::open(&path).unwrap();
+            black_box(num5_wcr::count(BufReader::new(file)).unwrap())
+        })
+    });
+
+    #[cfg(feature = "mmap")]
+    group.bench_function("mmap", |b| {
+        b.iter(|| {
+            let file = File::open(&path).unwrap();
+            black_box(num5_wcr::count_mapped(&file).unwrap())
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_count);
+criterion_main!(benches);
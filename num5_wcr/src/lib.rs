@@ -1,6 +1,9 @@
 use std::{
+    collections::{HashMap, HashSet},
+    env,
     error::Error,
-    fs::File,
+    ffi::OsString,
+    fs::{self, File},
     io::{self, BufRead, BufReader},
 };
 
@@ -16,10 +19,21 @@ pub struct Config {
     words: bool,
     bytes: bool,
     chars: bool,
+    top: Option<usize>,
+    ignore_case: bool,
+    stopwords: Option<String>,
 }
 
 pub fn get_args() -> MyResult<Config> {
-    let cli = Cli::parse();
+    get_args_from(env::args_os())
+}
+
+pub fn get_args_from<I, T>(args: I) -> MyResult<Config>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<OsString> + Clone,
+{
+    let cli = Cli::parse_from(args);
 
     let is_args_empty = [
         cli.lines,
@@ -47,25 +61,95 @@ pub fn get_args() -> MyResult<Config> {
         words,
         bytes,
         chars,
+        top: cli.top,
+        ignore_case: cli.ignore_case,
+        stopwords: cli.stopwords,
     })
 }
 
 pub fn run(config: Config) -> MyResult<()> {
-    for file_name in config.files {
-        match open(&file_name) {
+    if let Some(top) = config.top {
+        return run_top(&config, top);
+    }
+
+    let bytes_only = config.bytes && !config.lines && !config.words && !config.chars;
+    let mut total_lines = 0;
+    let mut total_words = 0;
+    let mut total_bytes = 0;
+    let mut total_chars = 0;
+
+    for file_name in &config.files {
+        match count_file(file_name, bytes_only) {
             Err(err) => eprintln!("{}: {}", file_name, err),
-            Ok(file) => match count(file) {
-                Err(err) => eprintln!("{}: {}", file_name, err),
-                Ok(file_info) => println!(
-                    "\t{}\t{}\t{} {}",
-                    file_info.num_lines, file_info.num_words, file_info.num_chars, file_name
-                ),
-            },
+            Ok(file_info) => {
+                println!(
+                    "{}{}{}{}{}",
+                    format_field(file_info.num_lines, config.lines),
+                    format_field(file_info.num_words, config.words),
+                    format_field(file_info.num_bytes, config.bytes),
+                    format_field(file_info.num_chars, config.chars),
+                    if file_name == "-" {
+                        "".to_string()
+                    } else {
+                        format!(" {}", file_name)
+                    }
+                );
+                total_lines += file_info.num_lines;
+                total_words += file_info.num_words;
+                total_bytes += file_info.num_bytes;
+                total_chars += file_info.num_chars;
+            }
         }
     }
+
+    if config.files.len() > 1 {
+        println!(
+            "{}{}{}{} total",
+            format_field(total_lines, config.lines),
+            format_field(total_words, config.words),
+            format_field(total_bytes, config.bytes),
+            format_field(total_chars, config.chars),
+        );
+    }
     Ok(())
 }
 
+fn format_field(value: usize, show: bool) -> String {
+    if show {
+        format!("{:>8}", value)
+    } else {
+        "".to_string()
+    }
+}
+
+fn count_file(file_name: &str, bytes_only: bool) -> MyResult<FileInfo> {
+    if bytes_only && file_name != "-" {
+        let metadata = fs::metadata(file_name)?;
+        // /proc のようにサイズ0を返すファイルは実際に読んで数える
+        if metadata.is_file() && metadata.len() > 0 {
+            return Ok(FileInfo {
+                num_lines: 0,
+                num_words: 0,
+                num_bytes: metadata.len() as usize,
+                num_chars: 0,
+            });
+        }
+    }
+
+    #[cfg(feature = "mmap")]
+    if file_name != "-" {
+        let file = File::open(file_name)?;
+        let metadata = file.metadata()?;
+        // パイプや特殊ファイル、長さ0のファイルはマップできないのでストリームで読む
+        if metadata.is_file() && metadata.len() > 0 {
+            return count_mapped(&file);
+        }
+        return count(BufReader::new(file));
+    }
+
+    count(open(file_name)?)
+}
+
 fn open(file_name: &str) -> MyResult<Box<dyn BufRead>> {
     match file_name {
         "-" => Ok(Box::new(BufReader::new(io::stdin()))),
@@ -74,14 +158,14 @@ fn open(file_name: &str) -> MyResult<Box<dyn BufRead>> {
 }
 
 #[derive(Debug, PartialEq)]
-struct FileInfo {
+pub struct FileInfo {
     num_lines: usize,
     num_words: usize,
     num_bytes: usize,
     num_chars: usize,
 }
 
-fn count(mut file: impl BufRead) -> MyResult<FileInfo> {
+pub fn count(mut file: impl BufRead) -> MyResult<FileInfo> {
     let mut num_lines = 0;
     let mut num_words = 0;
     let mut num_bytes = 0;
@@ -96,7 +180,7 @@ fn count(mut file: impl BufRead) -> MyResult<FileInfo> {
         }
 
         num_lines += 1;
-        num_words += buf.split_whitespace().count();
+        num_words += split_words(&buf).count();
         num_bytes += bytes;
         num_chars += buf.chars().count();
     }
@@ -109,6 +193,170 @@ fn count(mut file: impl BufRead) -> MyResult<FileInfo> {
     })
 }
 
+/// Count a regular file through a read-only memory map instead of a buffered reader.
+#[cfg(feature = "mmap")]
+pub fn count_mapped(file: &File) -> MyResult<FileInfo> {
+    // SAFETY: 読み取り専用でマップする。カウント中に他プロセスがファイルを切り詰めることは想定しない
+    let mmap = unsafe { memmap2::Mmap::map(file)? };
+    count_slice(&mmap)
+}
+
+/// Count `bytes` in a single pass, decoding UTF-8 along the way.
+#[cfg(feature = "mmap")]
+fn count_slice(bytes: &[u8]) -> MyResult<FileInfo> {
+    let invalid = || {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            "stream did not contain valid UTF-8",
+        )
+    };
+
+    let mut num_newlines = 0;
+    let mut num_words = 0;
+    let mut num_chars = 0;
+    let mut in_word = false;
+    // デコード中の文字、残りの継続バイト数、次の継続バイトが取りうる範囲
+    let mut code = 0;
+    let mut remaining = 0;
+    let mut next = (0x80, 0xBF);
+
+    for &byte in bytes {
+        if remaining == 0 {
+            let (mask, len, range) = match byte {
+                0x00..=0x7F => (0x7F, 0, (0x80, 0xBF)),
+                0xC2..=0xDF => (0x1F, 1, (0x80, 0xBF)),
+                0xE0 => (0x0F, 2, (0xA0, 0xBF)),
+                0xE1..=0xEC | 0xEE..=0xEF => (0x0F, 2, (0x80, 0xBF)),
+                0xED => (0x0F, 2, (0x80, 0x9F)),
+                0xF0 => (0x07, 3, (0x90, 0xBF)),
+                0xF1..=0xF3 => (0x07, 3, (0x80, 0xBF)),
+                0xF4 => (0x07, 3, (0x80, 0x8F)),
+                _ => return Err(invalid().into()),
+            };
+            code = u32::from(byte & mask);
+            remaining = len;
+            next = range;
+        } else {
+            // 冗長な表現やサロゲートにならないよう、2バイト目の範囲は先頭バイトで決まる
+            if byte < next.0 || byte > next.1 {
+                return Err(invalid().into());
+            }
+            code = code << 6 | u32::from(byte & 0x3F);
+            remaining -= 1;
+            next = (0x80, 0xBF);
+        }
+        if remaining > 0 {
+            continue;
+        }
+
+        let ch = char::from_u32(code).ok_or_else(invalid)?;
+        num_chars += 1;
+        if ch == '\n' {
+            num_newlines += 1;
+        }
+        if ch.is_whitespace() {
+            in_word = false;
+        } else if !in_word {
+            in_word = true;
+            num_words += 1;
+        }
+    }
+    if remaining > 0 {
+        return Err(invalid().into());
+    }
+
+    // 末尾が改行で終わらない場合は最後の行も1行として数える
+    let num_lines = match bytes.last() {
+        Some(b'\n') | None => num_newlines,
+        Some(_) => num_newlines + 1,
+    };
+
+    Ok(FileInfo {
+        num_lines,
+        num_words,
+        num_bytes: bytes.len(),
+        num_chars,
+    })
+}
+
+fn split_words(line: &str) -> impl Iterator<Item = &str> {
+    line.split_whitespace()
+}
+
+fn run_top(config: &Config, top: usize) -> MyResult<()> {
+    let stopwords = match &config.stopwords {
+        Some(file_name) => read_stopwords(file_name, config.ignore_case)
+            .map_err(|err| format!("{}: {}", file_name, err))?,
+        None => HashSet::new(),
+    };
+
+    let mut freq = HashMap::new();
+    for file_name in &config.files {
+        match open(file_name) {
+            Err(err) => eprintln!("{}: {}", file_name, err),
+            Ok(file) => {
+                if let Err(err) = count_words(file, config.ignore_case, &stopwords, &mut freq) {
+                    eprintln!("{}: {}", file_name, err);
+                }
+            }
+        }
+    }
+
+    for (word, num) in top_words(freq, top) {
+        println!("{:>8} {}", num, word);
+    }
+    Ok(())
+}
+
+fn read_stopwords(file_name: &str, ignore_case: bool) -> MyResult<HashSet<String>> {
+    let mut stopwords = HashSet::new();
+    for line in open(file_name)?.lines() {
+        for word in split_words(&line?) {
+            stopwords.insert(fold_case(word, ignore_case));
+        }
+    }
+    Ok(stopwords)
+}
+
+fn fold_case(word: &str, ignore_case: bool) -> String {
+    if ignore_case {
+        word.to_lowercase()
+    } else {
+        word.to_string()
+    }
+}
+
+fn count_words(
+    mut file: impl BufRead,
+    ignore_case: bool,
+    stopwords: &HashSet<String>,
+    freq: &mut HashMap<String, usize>,
+) -> MyResult<()> {
+    loop {
+        let mut buf = String::new();
+        let bytes = file.read_line(&mut buf)?;
+        if bytes == 0 {
+            break;
+        }
+
+        for word in split_words(&buf) {
+            let word = fold_case(word, ignore_case);
+            if !stopwords.contains(&word) {
+                *freq.entry(word).or_insert(0) += 1;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn top_words(freq: HashMap<String, usize>, top: usize) -> Vec<(String, usize)> {
+    let mut words: Vec<_> = freq.into_iter().collect();
+    // 出現回数の降順、同数の場合は単語の昇順
+    words.sort_by(|(a_word, a_num), (b_word, b_num)| b_num.cmp(a_num).then(a_word.cmp(b_word)));
+    words.truncate(top);
+    words
+}
+
 #[derive(Parser, Debug)]
 #[command(version)]
 #[command(about = "Rust wc")]
@@ -127,6 +375,18 @@ struct Cli {
     /// Show word count
     #[arg(short, long)]
     words: bool,
+
+    /// Show the N most frequent words
+    #[arg(long, value_name = "N")]
+    top: Option<usize>,
+
+    /// Ignore case when counting words
+    #[arg(short, long, requires = "top")]
+    ignore_case: bool,
+
+    /// File of words to exclude from the word frequency
+    #[arg(long, value_name = "FILE", requires = "top")]
+    stopwords: Option<String>,
 }
 
 #[derive(Args, Debug)]
@@ -216,4 +476,88 @@ mod test {
         assert_eq!(info, expected);
         Ok(())
     }
+
+    #[cfg(feature = "mmap")]
+    #[test]
+    fn test_count_slice() -> Result<()> {
+        for text in [
+            "",
+            "aaa",
+            "\n\n",
+            "I don't want the world. I just want your half.\r\n",
+            "Frétt hefir öld óvu,\nþá er endr of gerðu",
+            "全角\u{3000}空白\u{85}と🦀\n",
+        ] {
+            let info = count_slice(text.as_bytes());
+            assert!(info.is_ok());
+            assert_eq!(info.unwrap(), generate_info(text));
+        }
+
+        // 不正なバイト、途切れた文字、冗長な表現、サロゲート
+        for bytes in [
+            &b"\xff"[..],
+            b"a\xe3\x81",
+            b"\xc0\xaf",
+            b"\xe0\x80\xaf",
+            b"\xed\xa0\x80",
+            b"\xf4\x90\x80\x80",
+        ] {
+            assert!(count_slice(bytes).is_err(), "{bytes:?}");
+        }
+        Ok(())
+    }
+
+    fn generate_freq(text: &str, ignore_case: bool, stopwords: &[&str]) -> Vec<(String, usize)> {
+        let stopwords = stopwords.iter().map(|word| word.to_string()).collect();
+        let mut freq = HashMap::new();
+        let result = count_words(Cursor::new(text), ignore_case, &stopwords, &mut freq);
+        assert!(result.is_ok());
+        top_words(freq, 3)
+    }
+
+    #[test]
+    fn test_format_field() -> Result<()> {
+        assert_eq!(format_field(1, false), "");
+        assert_eq!(format_field(3, true), "       3");
+        assert_eq!(format_field(10, true), "      10");
+        Ok(())
+    }
+
+    #[test]
+    fn test_count_file_bytes_only() -> Result<()> {
+        let info = count_file("tests/inputs/atlamal.txt", true);
+        assert!(info.is_ok());
+        let expected = FileInfo {
+            num_lines: 0,
+            num_words: 0,
+            num_bytes: 177,
+            num_chars: 0,
+        };
+        assert_eq!(info.unwrap(), expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_count_words() -> Result<()> {
+        let words = generate_freq("b a the\nThe a\tthe c\n", false, &[]);
+        let expected = vec![
+            ("a".to_string(), 2),
+            ("the".to_string(), 2),
+            ("The".to_string(), 1),
+        ];
+        assert_eq!(words, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_count_words_ignore_case() -> Result<()> {
+        let words = generate_freq("b a the\nThe a\tthe c\n", true, &["a"]);
+        let expected = vec![
+            ("the".to_string(), 3),
+            ("b".to_string(), 1),
+            ("c".to_string(), 1),
+        ];
+        assert_eq!(words, expected);
+        Ok(())
+    }
 }
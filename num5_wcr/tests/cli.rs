@@ -8,6 +8,7 @@ const PRG: &str = "num5_wcr";
 const EMPTY: &str = "tests/inputs/empty.txt";
 const FOX: &str = "tests/inputs/fox.txt";
 const ATLAMAL: &str = "tests/inputs/atlamal.txt";
+const STOPWORDS: &str = "tests/inputs/stopwords.txt";
 
 // --------------------------------------------------
 fn gen_bad_file() -> String {
@@ -43,183 +44,265 @@ fn args_empty() -> Result<()> {
     Ok(())
 }
 
-// // --------------------------------------------------
-// fn run(args: &[&str], expected_file: &str) -> Result<()> {
-//     let expected = fs::read_to_string(expected_file)?;
-//     let output = Command::cargo_bin(PRG)?.args(args).output().expect("fail");
-//     assert!(output.status.success());
-
-//     let stdout = String::from_utf8(output.stdout).expect("invalid UTF-8");
-//     assert_eq!(stdout, expected);
-
-//     Ok(())
-// }
-
-// // --------------------------------------------------
-// #[test]
-// fn skips_bad_file() -> Result<()> {
-//     let bad = gen_bad_file();
-//     let expected = format!("{bad}: .* [(]os error 2[)]");
-//     Command::cargo_bin(PRG)?
-//         .arg(bad)
-//         .assert()
-//         .success()
-//         .stderr(predicate::str::is_match(expected)?);
-//     Ok(())
-// }
-
-// // --------------------------------------------------
-// #[test]
-// fn empty() -> Result<()> {
-//     run(&[EMPTY], "tests/expected/empty.txt.out")
-// }
-
-// // --------------------------------------------------
-// #[test]
-// fn fox() -> Result<()> {
-//     run(&[FOX], "tests/expected/fox.txt.out")
-// }
-
-// // --------------------------------------------------
-// #[test]
-// fn fox_bytes() -> Result<()> {
-//     run(&["--bytes", FOX], "tests/expected/fox.txt.c.out")
-// }
-
-// // --------------------------------------------------
-// #[test]
-// fn fox_chars() -> Result<()> {
-//     run(&["--chars", FOX], "tests/expected/fox.txt.m.out")
-// }
-
-// // --------------------------------------------------
-// #[test]
-// fn fox_words() -> Result<()> {
-//     run(&["--words", FOX], "tests/expected/fox.txt.w.out")
-// }
-
-// // --------------------------------------------------
-// #[test]
-// fn fox_lines() -> Result<()> {
-//     run(&["--lines", FOX], "tests/expected/fox.txt.l.out")
-// }
-
-// // --------------------------------------------------
-// #[test]
-// fn fox_words_bytes() -> Result<()> {
-//     run(&["-w", "-c", FOX], "tests/expected/fox.txt.wc.out")
-// }
-
-// // --------------------------------------------------
-// #[test]
-// fn fox_words_lines() -> Result<()> {
-//     run(&["-w", "-l", FOX], "tests/expected/fox.txt.wl.out")
-// }
-
-// // --------------------------------------------------
-// #[test]
-// fn fox_bytes_lines() -> Result<()> {
-//     run(&["-l", "-c", FOX], "tests/expected/fox.txt.cl.out")
-// }
-
-// // --------------------------------------------------
-// #[test]
-// fn atlamal() -> Result<()> {
-//     run(&[ATLAMAL], "tests/expected/atlamal.txt.out")
-// }
-
-// // --------------------------------------------------
-// #[test]
-// fn atlamal_bytes() -> Result<()> {
-//     run(&["-c", ATLAMAL], "tests/expected/atlamal.txt.c.out")
-// }
-
-// // --------------------------------------------------
-// #[test]
-// fn atlamal_words() -> Result<()> {
-//     run(&["-w", ATLAMAL], "tests/expected/atlamal.txt.w.out")
-// }
-
-// // --------------------------------------------------
-// #[test]
-// fn atlamal_lines() -> Result<()> {
-//     run(&["-l", ATLAMAL], "tests/expected/atlamal.txt.l.out")
-// }
-
-// // --------------------------------------------------
-// #[test]
-// fn atlamal_words_bytes() -> Result<()> {
-//     run(&["-w", "-c", ATLAMAL], "tests/expected/atlamal.txt.wc.out")
-// }
-
-// // --------------------------------------------------
-// #[test]
-// fn atlamal_words_lines() -> Result<()> {
-//     run(&["-w", "-l", ATLAMAL], "tests/expected/atlamal.txt.wl.out")
-// }
-
-// // --------------------------------------------------
-// #[test]
-// fn atlamal_bytes_lines() -> Result<()> {
-//     run(&["-l", "-c", ATLAMAL], "tests/expected/atlamal.txt.cl.out")
-// }
-
-// // --------------------------------------------------
-// #[test]
-// fn atlamal_stdin() -> Result<()> {
-//     let input = fs::read_to_string(ATLAMAL)?;
-//     let expected =
-//         fs::read_to_string("tests/expected/atlamal.txt.stdin.out")?;
-
-//     let output = Command::cargo_bin(PRG)?
-//         .write_stdin(input)
-//         .output()
-//         .expect("fail");
-//     assert!(output.status.success());
-
-//     let stdout = String::from_utf8(output.stdout).expect("invalid UTF-8");
-//     assert_eq!(stdout, expected);
-//     Ok(())
-// }
-
-// // --------------------------------------------------
-// #[test]
-// fn test_all() -> Result<()> {
-//     run(&[EMPTY, FOX, ATLAMAL], "tests/expected/all.out")
-// }
-
-// // --------------------------------------------------
-// #[test]
-// fn test_all_lines() -> Result<()> {
-//     run(&["-l", EMPTY, FOX, ATLAMAL], "tests/expected/all.l.out")
-// }
-
-// // --------------------------------------------------
-// #[test]
-// fn test_all_words() -> Result<()> {
-//     run(&["-w", EMPTY, FOX, ATLAMAL], "tests/expected/all.w.out")
-// }
-
-// // --------------------------------------------------
-// #[test]
-// fn test_all_bytes() -> Result<()> {
-//     run(&["-c", EMPTY, FOX, ATLAMAL], "tests/expected/all.c.out")
-// }
-
-// // --------------------------------------------------
-// #[test]
-// fn test_all_words_bytes() -> Result<()> {
-//     run(&["-cw", EMPTY, FOX, ATLAMAL], "tests/expected/all.wc.out")
-// }
-
-// // --------------------------------------------------
-// #[test]
-// fn test_all_words_lines() -> Result<()> {
-//     run(&["-wl", EMPTY, FOX, ATLAMAL], "tests/expected/all.wl.out")
-// }
-
-// // --------------------------------------------------
-// #[test]
-// fn test_all_bytes_lines() -> Result<()> {
-//     run(&["-cl", EMPTY, FOX, ATLAMAL], "tests/expected/all.cl.out")
-// }
+// --------------------------------------------------
+fn run(args: &[&str], expected_file: &str) -> Result<()> {
+    let expected = fs::read_to_string(expected_file)?;
+    let output = Command::cargo_bin(PRG)?.args(args).output().expect("fail");
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).expect("invalid UTF-8");
+    assert_eq!(stdout, expected);
+
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn skips_bad_file() -> Result<()> {
+    let bad = gen_bad_file();
+    let expected = format!("{bad}: .* [(]os error 2[)]");
+    Command::cargo_bin(PRG)?
+        .arg(bad)
+        .assert()
+        .success()
+        .stderr(predicate::str::is_match(expected)?);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn empty() -> Result<()> {
+    run(&[EMPTY], "tests/expected/empty.txt.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn fox() -> Result<()> {
+    run(&[FOX], "tests/expected/fox.txt.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn fox_bytes() -> Result<()> {
+    run(&["--bytes", FOX], "tests/expected/fox.txt.c.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn fox_chars() -> Result<()> {
+    run(&["--chars", FOX], "tests/expected/fox.txt.m.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn fox_words() -> Result<()> {
+    run(&["--words", FOX], "tests/expected/fox.txt.w.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn fox_lines() -> Result<()> {
+    run(&["--lines", FOX], "tests/expected/fox.txt.l.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn fox_words_bytes() -> Result<()> {
+    run(&["-w", "-c", FOX], "tests/expected/fox.txt.wc.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn fox_words_lines() -> Result<()> {
+    run(&["-w", "-l", FOX], "tests/expected/fox.txt.wl.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn fox_bytes_lines() -> Result<()> {
+    run(&["-l", "-c", FOX], "tests/expected/fox.txt.cl.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn atlamal() -> Result<()> {
+    run(&[ATLAMAL], "tests/expected/atlamal.txt.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn atlamal_bytes() -> Result<()> {
+    run(&["-c", ATLAMAL], "tests/expected/atlamal.txt.c.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn atlamal_words() -> Result<()> {
+    run(&["-w", ATLAMAL], "tests/expected/atlamal.txt.w.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn atlamal_lines() -> Result<()> {
+    run(&["-l", ATLAMAL], "tests/expected/atlamal.txt.l.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn atlamal_words_bytes() -> Result<()> {
+    run(&["-w", "-c", ATLAMAL], "tests/expected/atlamal.txt.wc.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn atlamal_words_lines() -> Result<()> {
+    run(&["-w", "-l", ATLAMAL], "tests/expected/atlamal.txt.wl.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn atlamal_bytes_lines() -> Result<()> {
+    run(&["-l", "-c", ATLAMAL], "tests/expected/atlamal.txt.cl.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn atlamal_stdin() -> Result<()> {
+    let input = fs::read_to_string(ATLAMAL)?;
+    let expected = fs::read_to_string("tests/expected/atlamal.txt.stdin.out")?;
+
+    let output = Command::cargo_bin(PRG)?
+        .write_stdin(input)
+        .output()
+        .expect("fail");
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).expect("invalid UTF-8");
+    assert_eq!(stdout, expected);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn test_all() -> Result<()> {
+    run(&[EMPTY, FOX, ATLAMAL], "tests/expected/all.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn test_all_lines() -> Result<()> {
+    run(&["-l", EMPTY, FOX, ATLAMAL], "tests/expected/all.l.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn test_all_words() -> Result<()> {
+    run(&["-w", EMPTY, FOX, ATLAMAL], "tests/expected/all.w.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn test_all_bytes() -> Result<()> {
+    run(&["-c", EMPTY, FOX, ATLAMAL], "tests/expected/all.c.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn test_all_words_bytes() -> Result<()> {
+    run(&["-cw", EMPTY, FOX, ATLAMAL], "tests/expected/all.wc.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn test_all_words_lines() -> Result<()> {
+    run(&["-wl", EMPTY, FOX, ATLAMAL], "tests/expected/all.wl.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn test_all_bytes_lines() -> Result<()> {
+    run(&["-cl", EMPTY, FOX, ATLAMAL], "tests/expected/all.cl.out")
+}
+
+// --------------------------------------------------
+#[cfg(target_os = "linux")]
+#[test]
+fn proc_bytes() -> Result<()> {
+    // /proc のファイルはサイズ0を返すため、実際に読んだバイト数になること
+    let output = Command::cargo_bin(PRG)?
+        .args(["-c", "/proc/self/status"])
+        .output()
+        .expect("fail");
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).expect("invalid UTF-8");
+    let bytes: usize = stdout
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .parse()?;
+    assert!(bytes > 0);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_ignore_case_without_top() -> Result<()> {
+    Command::cargo_bin(PRG)?
+        .args(["-i", FOX])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--top <N>"));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_stopwords() -> Result<()> {
+    let bad = gen_bad_file();
+    let expected = format!("{bad}: .* [(]os error 2[)]");
+    Command::cargo_bin(PRG)?
+        .args(["--top", "5", "--stopwords", &bad, FOX])
+        .assert()
+        .failure()
+        .stderr(predicate::str::is_match(expected)?);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn empty_top() -> Result<()> {
+    run(&["--top", "5", EMPTY], "tests/expected/empty.txt.top5.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn fox_top() -> Result<()> {
+    run(&["--top", "5", FOX], "tests/expected/fox.txt.top5.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn fox_top_ignore_case() -> Result<()> {
+    run(
+        &["--top", "5", "-i", FOX],
+        "tests/expected/fox.txt.top5.i.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn all_top_stopwords() -> Result<()> {
+    run(
+        &[
+            "--top",
+            "5",
+            "--ignore-case",
+            "--stopwords",
+            STOPWORDS,
+            ATLAMAL,
+            FOX,
+        ],
+        "tests/expected/all.top5.i.stopwords.out",
+    )
+}
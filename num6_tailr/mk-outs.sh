@@ -0,0 +1,26 @@
+#!/usr/bin/env bash
+
+# GNU coreutils の tail で期待値を生成する
+TAIL="/usr/bin/tail"
+ROOT="tests/inputs"
+OUT_DIR="tests/expected"
+
+[[ ! -d "$OUT_DIR" ]] && mkdir -p "$OUT_DIR"
+rm -f "$OUT_DIR"/*
+
+for FILE in $ROOT/*.txt; do
+    BASENAME=$(basename "$FILE")
+    $TAIL        "$FILE" > "${OUT_DIR}/${BASENAME}.out"
+    for NUM in 0 1 3 +0 +1 +2 +10; do
+        NAME=${NUM/+/plus}
+        $TAIL -n $NUM "$FILE" > "${OUT_DIR}/${BASENAME}.n${NAME}.out"
+        $TAIL -c $NUM "$FILE" > "${OUT_DIR}/${BASENAME}.c${NAME}.out"
+    done
+done
+
+ALL="$ROOT/empty.txt $ROOT/one.txt $ROOT/two.txt $ROOT/three.txt $ROOT/twelve.txt $ROOT/noeol.txt"
+$TAIL       $ALL > $OUT_DIR/all.out
+$TAIL -n 1  $ALL > $OUT_DIR/all.n1.out
+$TAIL -n +2 $ALL > $OUT_DIR/all.nplus2.out
+$TAIL -c 3  $ALL > $OUT_DIR/all.c3.out
+$TAIL -q    $ALL > $OUT_DIR/all.q.out
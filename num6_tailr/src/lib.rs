@@ -0,0 +1,367 @@
+use std::{
+    collections::VecDeque,
+    env,
+    error::Error,
+    ffi::OsString,
+    fs::File,
+    io::{self, BufRead, BufReader, Read, Seek, SeekFrom, Write},
+    mem,
+};
+
+use clap::{ArgGroup, Parser};
+
+type MyResult<T> = Result<T, Box<dyn Error>>;
+
+/// 末尾から読み戻すときや、パイプから読むときの単位
+const CHUNK_SIZE: usize = 64 * 1024;
+
+#[derive(Debug)]
+pub struct Config {
+    files: Vec<String>,
+    lines: TakeValue,
+    bytes: Option<TakeValue>,
+    quiet: bool,
+}
+
+/// Where output starts, as given to `-n` or `-c`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum TakeValue {
+    /// `+N`: from the Nth line or byte, counting from 1.
+    FromStart(u64),
+    /// `N` or `-N`: the last N lines or bytes.
+    FromEnd(u64),
+}
+
+enum Input {
+    /// A regular file, where the tail can be found by seeking from the end.
+    Seekable(File),
+    Stream(Box<dyn BufRead>),
+}
+
+pub fn get_args() -> MyResult<Config> {
+    get_args_from(env::args_os())
+}
+
+pub fn get_args_from<I, T>(args: I) -> MyResult<Config>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<OsString> + Clone,
+{
+    let args = Cli::parse_from(args);
+    Ok(Config {
+        files: args.files,
+        lines: args.lines,
+        bytes: args.bytes,
+        quiet: args.quiet,
+    })
+}
+
+pub fn run(config: Config) -> MyResult<()> {
+    let mut stdout = io::stdout().lock();
+    let print_headers = config.files.len() > 1 && !config.quiet;
+    let mut is_first_file = true;
+
+    for file_name in &config.files {
+        let input = match open(file_name) {
+            Err(err) => {
+                eprintln!("{}: {}", file_name, err);
+                continue;
+            }
+            Ok(input) => input,
+        };
+
+        if print_headers {
+            // 二つ目以降のファイル出力の場合は空行を空ける
+            if !is_first_file {
+                writeln!(stdout)?;
+            }
+            writeln!(stdout, "==> {} <==", file_name)?;
+        }
+        is_first_file = false;
+
+        tail(input, config.lines, config.bytes, &mut stdout)
+            .map_err(|e| format!("{}: {}", file_name, e))?;
+    }
+    Ok(())
+}
+
+fn tail(
+    input: Input,
+    lines: TakeValue,
+    bytes: Option<TakeValue>,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    match input {
+        Input::Seekable(mut file) => {
+            let start = match (bytes, lines) {
+                (Some(TakeValue::FromEnd(num)), _) => {
+                    file.seek(SeekFrom::End(0))?.saturating_sub(num)
+                }
+                (Some(TakeValue::FromStart(num)), _) => num.saturating_sub(1),
+                (None, TakeValue::FromEnd(num)) => find_tail_lines(&mut file, num)?,
+                (None, TakeValue::FromStart(num)) => {
+                    return skip_lines(BufReader::new(file), num, out);
+                }
+            };
+            file.seek(SeekFrom::Start(start))?;
+            io::copy(&mut file, out)?;
+            Ok(())
+        }
+        Input::Stream(reader) => match (bytes, lines) {
+            (Some(TakeValue::FromEnd(num)), _) => last_bytes(reader, num, out),
+            (Some(TakeValue::FromStart(num)), _) => skip_bytes(reader, num, out),
+            (None, TakeValue::FromEnd(num)) => last_lines(reader, num, out),
+            (None, TakeValue::FromStart(num)) => skip_lines(reader, num, out),
+        },
+    }
+}
+
+/// 末尾から`num`行分の先頭の位置を、ファイルの後ろからチャンク単位で読み戻して探す
+fn find_tail_lines<R: Read + Seek>(reader: &mut R, num: u64) -> io::Result<u64> {
+    let len = reader.seek(SeekFrom::End(0))?;
+    if num == 0 {
+        return Ok(len);
+    }
+
+    let mut buffer = vec![0; CHUNK_SIZE];
+    let mut newlines = 0;
+    let mut end = len;
+    while end > 0 {
+        let start = end.saturating_sub(CHUNK_SIZE as u64);
+        let chunk = &mut buffer[..(end - start) as usize];
+        reader.seek(SeekFrom::Start(start))?;
+        reader.read_exact(chunk)?;
+
+        for (i, _) in chunk.iter().enumerate().rev().filter(|(_, b)| **b == b'\n') {
+            let pos = start + i as u64;
+            // 最終行の末尾の改行は行の区切りとして数えない
+            if pos + 1 == len {
+                continue;
+            }
+            newlines += 1;
+            if newlines == num {
+                return Ok(pos + 1);
+            }
+        }
+        end = start;
+    }
+    Ok(0)
+}
+
+/// パイプなど位置を移動できない入力は、末尾`num`行だけをリングバッファに保持しながら読み進める
+fn last_lines(mut reader: impl BufRead, num: u64, out: &mut impl Write) -> io::Result<()> {
+    if num == 0 {
+        io::copy(&mut reader, &mut io::sink())?;
+        return Ok(());
+    }
+
+    let mut ring = VecDeque::new();
+    let mut line = Vec::new();
+    while reader.read_until(b'\n', &mut line)? > 0 {
+        // 保持する行数を超えたら最も古い行のバッファを再利用する
+        let mut recycled = if ring.len() as u64 == num {
+            ring.pop_front().unwrap_or_default()
+        } else {
+            Vec::new()
+        };
+        recycled.clear();
+        ring.push_back(mem::replace(&mut line, recycled));
+    }
+
+    for line in ring {
+        out.write_all(&line)?;
+    }
+    Ok(())
+}
+
+/// パイプなど位置を移動できない入力の末尾`num`バイト
+fn last_bytes(mut reader: impl Read, num: u64, out: &mut impl Write) -> io::Result<()> {
+    let mut ring = VecDeque::new();
+    let mut buffer = vec![0; CHUNK_SIZE];
+    loop {
+        let size = match reader.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(size) => size,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        ring.extend(&buffer[..size]);
+        let excess = (ring.len() as u64).saturating_sub(num) as usize;
+        ring.drain(..excess);
+    }
+
+    let (front, back) = ring.as_slices();
+    out.write_all(front)?;
+    out.write_all(back)
+}
+
+/// `+N`: 先頭の N-1 行を読み飛ばして残りを出力する
+fn skip_lines(mut reader: impl BufRead, num: u64, out: &mut impl Write) -> io::Result<()> {
+    for _ in 1..num {
+        if reader.skip_until(b'\n')? == 0 {
+            break;
+        }
+    }
+    io::copy(&mut reader, out)?;
+    Ok(())
+}
+
+/// `+N`: 先頭の N-1 バイトを読み飛ばして残りを出力する
+fn skip_bytes(mut reader: impl Read, num: u64, out: &mut impl Write) -> io::Result<()> {
+    io::copy(
+        &mut reader.by_ref().take(num.saturating_sub(1)),
+        &mut io::sink(),
+    )?;
+    io::copy(&mut reader, out)?;
+    Ok(())
+}
+
+#[derive(Parser, Debug)]
+#[command(version)]
+#[command(about = "Rust tail")]
+#[command(group(ArgGroup::new("inputs").args(["lines", "bytes"])))]
+struct Cli {
+    /// Target files.
+    #[arg(num_args = 1.., default_values_t = ["-".to_string()])]
+    files: Vec<String>,
+
+    /// Number of lines, or +N to start from line N.
+    #[arg(
+        short('n'),
+        long,
+        default_value = "10",
+        value_parser = parse_take_value,
+        allow_hyphen_values = true
+    )]
+    lines: TakeValue,
+
+    /// Number of bytes, or +N to start from byte N.
+    #[arg(
+        short('c'),
+        long,
+        value_parser = parse_take_value,
+        allow_hyphen_values = true
+    )]
+    bytes: Option<TakeValue>,
+
+    /// Never print headers with file names.
+    #[arg(short('q'), long)]
+    quiet: bool,
+}
+
+fn parse_take_value(val: &str) -> Result<TakeValue, String> {
+    let (digits, take): (_, fn(u64) -> TakeValue) = match val.strip_prefix('+') {
+        Some(digits) => (digits, TakeValue::FromStart),
+        None => (val.strip_prefix('-').unwrap_or(val), TakeValue::FromEnd),
+    };
+    // u64 の parse は先頭の + を受け付けるため、符号が重なる場合はここで弾く
+    if digits.starts_with(['+', '-']) {
+        return Err("invalid digit found in string".to_string());
+    }
+    digits.parse().map(take).map_err(|e| e.to_string())
+}
+
+fn open(file_name: &str) -> MyResult<Input> {
+    match file_name {
+        "-" => Ok(Input::Stream(Box::new(BufReader::new(io::stdin())))),
+        _ => {
+            let file = File::open(file_name)?;
+            // /proc などサイズが 0 と報告されるファイルは読み進めて扱う
+            let metadata = file.metadata()?;
+            if metadata.is_file() && metadata.len() > 0 {
+                Ok(Input::Seekable(file))
+            } else {
+                Ok(Input::Stream(Box::new(BufReader::new(file))))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+fn collect(f: impl FnOnce(&mut Vec<u8>) -> io::Result<()>) -> String {
+    let mut out = Vec::new();
+    f(&mut out).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn test_parse_take_value() {
+    assert_eq!(parse_take_value("3"), Ok(TakeValue::FromEnd(3)));
+    assert_eq!(parse_take_value("-3"), Ok(TakeValue::FromEnd(3)));
+    assert_eq!(parse_take_value("+3"), Ok(TakeValue::FromStart(3)));
+    assert_eq!(parse_take_value("+0"), Ok(TakeValue::FromStart(0)));
+    assert_eq!(parse_take_value("0"), Ok(TakeValue::FromEnd(0)));
+
+    for bad in ["", "+", "foo", "3.5", "+-3", "--3", "-+3", "++3"] {
+        assert!(parse_take_value(bad).is_err(), "{}", bad);
+    }
+    assert_eq!(
+        parse_take_value("foo").unwrap_err(),
+        "invalid digit found in string"
+    );
+}
+
+#[test]
+fn test_find_tail_lines() {
+    let text = "one\ntwo\nthree\n";
+    let find = |text: &str, num| find_tail_lines(&mut io::Cursor::new(text), num).unwrap();
+    assert_eq!(find(text, 0), 14);
+    assert_eq!(find(text, 1), 8);
+    assert_eq!(find(text, 2), 4);
+    assert_eq!(find(text, 3), 0);
+    assert_eq!(find(text, 10), 0);
+
+    // 最終行に改行がない場合もその行を 1 行として数える
+    assert_eq!(find("one\ntwo", 1), 4);
+    assert_eq!(find("\n\n", 1), 1);
+    assert_eq!(find("", 1), 0);
+
+    // チャンクの境界をまたぐ場合
+    let long = format!(
+        "{}\n{}\nlast\n",
+        "a".repeat(CHUNK_SIZE),
+        "b".repeat(CHUNK_SIZE)
+    );
+    assert_eq!(find(&long, 1), (2 * CHUNK_SIZE + 2) as u64);
+    assert_eq!(find(&long, 2), (CHUNK_SIZE + 1) as u64);
+    assert_eq!(find(&long, 3), 0);
+}
+
+#[test]
+fn test_last_lines() {
+    let text = "one\ntwo\r\nthree";
+    let last = |num| collect(|out| last_lines(io::Cursor::new(text), num, out));
+    assert_eq!(last(0), "");
+    assert_eq!(last(1), "three");
+    assert_eq!(last(2), "two\r\nthree");
+    assert_eq!(last(10), text);
+}
+
+#[test]
+fn test_last_bytes() {
+    let text = "one\ntwo\n";
+    let last = |num| collect(|out| last_bytes(io::Cursor::new(text), num, out));
+    assert_eq!(last(0), "");
+    assert_eq!(last(3), "wo\n");
+    assert_eq!(last(100), text);
+
+    let long = "x".repeat(CHUNK_SIZE * 2 + 10) + "end";
+    let tail = collect(|out| last_bytes(io::Cursor::new(&long), 5, out));
+    assert_eq!(tail, "xxend");
+}
+
+#[test]
+fn test_skip() {
+    let text = "one\ntwo\nthree";
+    let lines = |num| collect(|out| skip_lines(io::Cursor::new(text), num, out));
+    assert_eq!(lines(0), text);
+    assert_eq!(lines(1), text);
+    assert_eq!(lines(2), "two\nthree");
+    assert_eq!(lines(4), "");
+
+    let bytes = |num| collect(|out| skip_bytes(io::Cursor::new(text), num, out));
+    assert_eq!(bytes(0), text);
+    assert_eq!(bytes(1), text);
+    assert_eq!(bytes(5), "two\nthree");
+    assert_eq!(bytes(100), "");
+}
@@ -0,0 +1,6 @@
+fn main() {
+    if let Err(e) = num6_tailr::get_args().and_then(num6_tailr::run) {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,236 @@
+use anyhow::Result;
+use assert_cmd::Command;
+use predicates::prelude::*;
+use pretty_assertions::assert_eq;
+use rand::distr::Alphanumeric;
+use rand::Rng;
+use std::fs;
+
+const PRG: &str = "num6_tailr";
+const EMPTY: &str = "tests/inputs/empty.txt";
+const ONE: &str = "tests/inputs/one.txt";
+const TWO: &str = "tests/inputs/two.txt";
+const THREE: &str = "tests/inputs/three.txt";
+const TWELVE: &str = "tests/inputs/twelve.txt";
+const NOEOL: &str = "tests/inputs/noeol.txt";
+const ALL: &[&str] = &[EMPTY, ONE, TWO, THREE, TWELVE, NOEOL];
+
+// mk-outs.sh で生成した期待値のうち、ファイルごとに用意している引数
+const NUMS: &[&str] = &["0", "1", "3", "+0", "+1", "+2", "+10"];
+
+// --------------------------------------------------
+fn random_string() -> String {
+    rand::rng()
+        .sample_iter(&Alphanumeric)
+        .take(7)
+        .map(char::from)
+        .collect()
+}
+
+// --------------------------------------------------
+fn gen_bad_file() -> String {
+    loop {
+        let filename = random_string();
+        if fs::metadata(&filename).is_err() {
+            return filename;
+        }
+    }
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_bytes() -> Result<()> {
+    let bad = random_string();
+    let expected = format!(
+        "invalid value '{bad}' for \
+        '--bytes <BYTES>': invalid digit found in string"
+    );
+
+    Command::cargo_bin(PRG)?
+        .args(["-c", &bad, EMPTY])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(expected));
+
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_lines() -> Result<()> {
+    let bad = random_string();
+    let expected = format!(
+        "error: invalid value '{bad}' for \
+        '--lines <LINES>': invalid digit found in string"
+    );
+    Command::cargo_bin(PRG)?
+        .args(["-n", &bad, EMPTY])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(expected));
+
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bytes_and_lines() -> Result<()> {
+    let msg = "the argument '--lines <LINES>' cannot be \
+               used with '--bytes <BYTES>'";
+
+    Command::cargo_bin(PRG)?
+        .args(["-n", "1", "-c", "2"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(msg));
+
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn skips_bad_file() -> Result<()> {
+    let bad = gen_bad_file();
+    let expected = format!("{bad}: .* [(]os error 2[)]");
+    Command::cargo_bin(PRG)?
+        .args([ONE, &bad, TWO])
+        .assert()
+        .success()
+        .stderr(predicate::str::is_match(expected)?);
+
+    Ok(())
+}
+
+// --------------------------------------------------
+fn run(args: &[&str], expected_file: &str) -> Result<()> {
+    let expected = fs::read(expected_file)?;
+    let output = Command::cargo_bin(PRG)?.args(args).output().expect("fail");
+    assert!(output.status.success());
+    assert_eq!(output.stdout, expected, "{args:?}");
+
+    Ok(())
+}
+
+// --------------------------------------------------
+fn run_stdin(
+    args: &[&str],
+    input_file: &str,
+    expected_file: &str,
+) -> Result<()> {
+    let expected = fs::read(expected_file)?;
+    let input = fs::read(input_file)?;
+
+    let output = Command::cargo_bin(PRG)?
+        .write_stdin(input)
+        .args(args)
+        .output()
+        .expect("fail");
+    assert!(output.status.success());
+    assert_eq!(output.stdout, expected, "{args:?} < {input_file}");
+
+    Ok(())
+}
+
+// --------------------------------------------------
+/// 通常のファイルとして読む場合と、標準入力から読む場合の両方を確認する
+fn run_all(input_file: &str) -> Result<()> {
+    let basename = input_file.rsplit('/').next().unwrap();
+    let expected = format!("tests/expected/{basename}.out");
+    run(&[input_file], &expected)?;
+    run_stdin(&[], input_file, &expected)?;
+
+    for num in NUMS {
+        let name = num.replace('+', "plus");
+        for flag in ["-n", "-c"] {
+            let expected =
+                format!("tests/expected/{basename}.{}{name}.out", &flag[1..]);
+            run(&[input_file, flag, num], &expected)?;
+            run_stdin(&[flag, num], input_file, &expected)?;
+        }
+    }
+
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn empty() -> Result<()> {
+    run_all(EMPTY)
+}
+
+#[test]
+fn one() -> Result<()> {
+    run_all(ONE)
+}
+
+#[test]
+fn two() -> Result<()> {
+    run_all(TWO)
+}
+
+#[test]
+fn three() -> Result<()> {
+    run_all(THREE)
+}
+
+#[test]
+fn twelve() -> Result<()> {
+    run_all(TWELVE)
+}
+
+#[test]
+fn noeol() -> Result<()> {
+    run_all(NOEOL)
+}
+
+// --------------------------------------------------
+#[test]
+fn minus_sign() -> Result<()> {
+    run(&[TWELVE, "-n", "-3"], "tests/expected/twelve.txt.n3.out")?;
+    run(&["-c", "-3", TWELVE], "tests/expected/twelve.txt.c3.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn multiple_files() -> Result<()> {
+    run(ALL, "tests/expected/all.out")
+}
+
+#[test]
+fn multiple_files_n1() -> Result<()> {
+    run(&[&["-n", "1"], ALL].concat(), "tests/expected/all.n1.out")
+}
+
+#[test]
+fn multiple_files_nplus2() -> Result<()> {
+    run(
+        &[ALL, &["-n", "+2"]].concat(),
+        "tests/expected/all.nplus2.out",
+    )
+}
+
+#[test]
+fn multiple_files_c3() -> Result<()> {
+    run(&[&["-c", "3"], ALL].concat(), "tests/expected/all.c3.out")
+}
+
+#[test]
+fn multiple_files_quiet() -> Result<()> {
+    run(&[&["-q"], ALL].concat(), "tests/expected/all.q.out")
+}
+
+// --------------------------------------------------
+#[cfg(target_os = "linux")]
+#[test]
+fn proc_file() -> Result<()> {
+    // /proc のファイルはサイズが 0 と報告されるため、読み進めて末尾を得る
+    let expected = fs::read_to_string("/proc/self/status")?;
+    let last = expected.lines().last().unwrap();
+    Command::cargo_bin(PRG)?
+        .args(["-n", "1", "/proc/self/status"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(last.split(':').next().unwrap()));
+
+    Ok(())
+}
@@ -0,0 +1,31 @@
+#!/usr/bin/env bash
+
+# GNU coreutils の uniq で期待値を生成する
+UNIQ="/usr/bin/uniq"
+ROOT="tests/inputs"
+OUT_DIR="tests/expected"
+
+[[ ! -d "$OUT_DIR" ]] && mkdir -p "$OUT_DIR"
+rm -f "$OUT_DIR"/*
+
+# GNU uniq は最終行に改行を補うため、改行で終わらない入力の期待値はテストに直接書く
+for FILE in $ROOT/*.txt; do
+    BASENAME=$(basename "$FILE")
+    [[ "$BASENAME" == "noeol.txt" || "$BASENAME" == "zero.txt" ]] && continue
+    $UNIQ                "$FILE" > "$OUT_DIR/$BASENAME.out"
+    $UNIQ -c             "$FILE" > "$OUT_DIR/$BASENAME.c.out"
+    $UNIQ -d             "$FILE" > "$OUT_DIR/$BASENAME.d.out"
+    $UNIQ -u             "$FILE" > "$OUT_DIR/$BASENAME.u.out"
+    $UNIQ -i -c          "$FILE" > "$OUT_DIR/$BASENAME.i.c.out"
+    $UNIQ -d -u          "$FILE" > "$OUT_DIR/$BASENAME.d.u.out"
+done
+
+FIELDS="$ROOT/fields.txt"
+$UNIQ -c -f 1           $FIELDS > "$OUT_DIR/fields.txt.f1.out"
+$UNIQ -c -f 2           $FIELDS > "$OUT_DIR/fields.txt.f2.out"
+$UNIQ -c -s 2           $FIELDS > "$OUT_DIR/fields.txt.s2.out"
+$UNIQ -c -f 1 -s 1 -w 3 $FIELDS > "$OUT_DIR/fields.txt.f1.s1.w3.out"
+$UNIQ -c -f 2 -i        $FIELDS > "$OUT_DIR/fields.txt.f2.i.out"
+
+$UNIQ -z                $ROOT/zero.txt > "$OUT_DIR/zero.txt.z.out"
+$UNIQ -z -c             $ROOT/zero.txt > "$OUT_DIR/zero.txt.z.c.out"
@@ -0,0 +1,235 @@
+use std::{
+    env,
+    error::Error,
+    ffi::OsString,
+    fs::File,
+    io::{self, BufRead, BufReader, BufWriter, Write},
+    mem,
+};
+
+use clap::Parser;
+
+#[derive(Debug)]
+pub struct Config {
+    in_file: String,
+    out_file: Option<String>,
+    count: bool,
+    repeated: bool,
+    unique: bool,
+    ignore_case: bool,
+    skip_fields: usize,
+    skip_chars: usize,
+    check_chars: Option<usize>,
+    zero_terminated: bool,
+}
+
+#[derive(Parser, Debug)]
+#[command(version)]
+#[command(about = "Rust uniq")]
+struct Cli {
+    /// Input file.
+    #[arg(default_value = "-")]
+    in_file: String,
+
+    /// Output file.
+    out_file: Option<String>,
+
+    /// Prefix lines by the number of occurrences.
+    #[arg(short, long)]
+    count: bool,
+
+    /// Only print duplicate lines, one for each group.
+    #[arg(short('d'), long)]
+    repeated: bool,
+
+    /// Only print unique lines.
+    #[arg(short, long)]
+    unique: bool,
+
+    /// Ignore differences in case when comparing.
+    #[arg(short, long)]
+    ignore_case: bool,
+
+    /// Avoid comparing the first N fields.
+    #[arg(short('f'), long, value_name = "N", default_value_t = 0)]
+    skip_fields: usize,
+
+    /// Avoid comparing the first N characters.
+    #[arg(short('s'), long, value_name = "N", default_value_t = 0)]
+    skip_chars: usize,
+
+    /// Compare no more than N characters in lines.
+    #[arg(short('w'), long, value_name = "N")]
+    check_chars: Option<usize>,
+
+    /// Line delimiter is NUL, not newline.
+    #[arg(short, long)]
+    zero_terminated: bool,
+}
+
+type MyResult<T> = Result<T, Box<dyn Error>>;
+
+pub fn run(config: Config) -> MyResult<()> {
+    let mut input = open(&config.in_file).map_err(|e| format!("{}: {}", config.in_file, e))?;
+    let mut output: Box<dyn Write> = match &config.out_file {
+        Some(out_file) if out_file != "-" => Box::new(BufWriter::new(
+            File::create(out_file).map_err(|e| format!("{}: {}", out_file, e))?,
+        )),
+        _ => Box::new(BufWriter::new(io::stdout())),
+    };
+    let delimiter = if config.zero_terminated { b'\0' } else { b'\n' };
+
+    // 行は区切り文字も含めて読み込んだまま出力するため、最終行に改行がなくてもそのまま残る
+    let mut line = Vec::new();
+    let mut previous = Vec::new();
+    let mut count = 0;
+    loop {
+        line.clear();
+        if input.read_until(delimiter, &mut line)? == 0 {
+            break;
+        }
+        if count > 0 && is_same(&config, &previous, &line, delimiter) {
+            count += 1;
+            continue;
+        }
+        print_group(&config, &previous, count, &mut output)?;
+        mem::swap(&mut previous, &mut line);
+        count = 1;
+    }
+    print_group(&config, &previous, count, &mut output)?;
+    output.flush()?;
+    Ok(())
+}
+
+pub fn get_args() -> MyResult<Config> {
+    get_args_from(env::args_os())
+}
+
+pub fn get_args_from<I, T>(args: I) -> MyResult<Config>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<OsString> + Clone,
+{
+    let args = Cli::parse_from(args);
+    Ok(Config {
+        in_file: args.in_file,
+        out_file: args.out_file,
+        count: args.count,
+        repeated: args.repeated,
+        unique: args.unique,
+        ignore_case: args.ignore_case,
+        skip_fields: args.skip_fields,
+        skip_chars: args.skip_chars,
+        check_chars: args.check_chars,
+        zero_terminated: args.zero_terminated,
+    })
+}
+
+fn open(file_name: &str) -> MyResult<Box<dyn BufRead>> {
+    match file_name {
+        "-" => Ok(Box::new(BufReader::new(io::stdin()))),
+        _ => Ok(Box::new(BufReader::new(File::open(file_name)?))),
+    }
+}
+
+fn is_same(config: &Config, previous: &[u8], line: &[u8], delimiter: u8) -> bool {
+    let previous = compare_key(config, previous, delimiter);
+    let line = compare_key(config, line, delimiter);
+    if config.ignore_case {
+        previous.eq_ignore_ascii_case(line)
+    } else {
+        previous == line
+    }
+}
+
+/// The part of `line` that is compared, after `-f` and `-s` skip its start and `-w` limits its
+/// length. The delimiter is never compared, so a last line without one matches its duplicates.
+fn compare_key<'a>(config: &Config, line: &'a [u8], delimiter: u8) -> &'a [u8] {
+    let mut key = line.strip_suffix(&[delimiter]).unwrap_or(line);
+
+    // フィールドは空白の並びとそれに続く空白以外の並び
+    for _ in 0..config.skip_fields {
+        let start = key.iter().position(|b| !is_blank(*b)).unwrap_or(key.len());
+        key = &key[start..];
+        let end = key.iter().position(|b| is_blank(*b)).unwrap_or(key.len());
+        key = &key[end..];
+    }
+
+    key = &key[config.skip_chars.min(key.len())..];
+    match config.check_chars {
+        Some(check_chars) => &key[..check_chars.min(key.len())],
+        None => key,
+    }
+}
+
+fn is_blank(b: u8) -> bool {
+    b == b' ' || b == b'\t'
+}
+
+fn print_group(config: &Config, line: &[u8], count: u64, output: &mut impl Write) -> MyResult<()> {
+    if count == 0 || (config.repeated && count == 1) || (config.unique && count > 1) {
+        return Ok(());
+    }
+    if config.count {
+        write!(output, "{:>7} ", count)?;
+    }
+    output.write_all(line)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn config(skip_fields: usize, skip_chars: usize, check_chars: Option<usize>) -> Config {
+        Config {
+            in_file: "-".to_string(),
+            out_file: None,
+            count: false,
+            repeated: false,
+            unique: false,
+            ignore_case: false,
+            skip_fields,
+            skip_chars,
+            check_chars,
+            zero_terminated: false,
+        }
+    }
+
+    #[test]
+    fn test_compare_key() {
+        let key = |config: &Config, line: &'static str| {
+            String::from_utf8(compare_key(config, line.as_bytes(), b'\n').to_vec()).unwrap()
+        };
+
+        assert_eq!(key(&config(0, 0, None), "abc\n"), "abc");
+        assert_eq!(key(&config(0, 0, None), "abc"), "abc");
+        assert_eq!(key(&config(0, 0, None), "abc\r\n"), "abc\r");
+
+        assert_eq!(key(&config(1, 0, None), "x\tfoo bar\n"), "\tfoo bar");
+        assert_eq!(key(&config(1, 0, None), "  w  foo baz\n"), "  foo baz");
+        assert_eq!(key(&config(5, 0, None), "a b\n"), "");
+
+        assert_eq!(key(&config(0, 2, None), "abcd\n"), "cd");
+        assert_eq!(key(&config(0, 9, None), "abcd\n"), "");
+        assert_eq!(key(&config(1, 1, Some(3)), "x foo bar\n"), "foo");
+        assert_eq!(key(&config(0, 0, Some(0)), "abcd\n"), "");
+
+        // -z の場合は改行も比較の対象になる
+        assert_eq!(compare_key(&config(0, 0, None), b"a\n\0", b'\0'), b"a\n");
+    }
+
+    #[test]
+    fn test_is_same() {
+        let mut ignore_case = config(0, 0, None);
+        ignore_case.ignore_case = true;
+        assert!(is_same(&ignore_case, b"Abc\n", b"aBC", b'\n'));
+        assert!(!is_same(&config(0, 0, None), b"Abc\n", b"aBC", b'\n'));
+        assert!(is_same(
+            &config(0, 0, None),
+            b"caf\xe9\n",
+            b"caf\xe9\n",
+            b'\n'
+        ));
+    }
+}
@@ -0,0 +1,6 @@
+fn main() {
+    if let Err(e) = num7_uniqr::get_args().and_then(num7_uniqr::run) {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+}
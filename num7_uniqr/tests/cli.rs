@@ -0,0 +1,197 @@
+use anyhow::Result;
+use assert_cmd::Command;
+use predicates::prelude::*;
+use pretty_assertions::assert_eq;
+use rand::{distr::Alphanumeric, Rng};
+use std::fs;
+
+const PRG: &str = "num7_uniqr";
+const FIELDS: &str = "tests/inputs/fields.txt";
+const ZERO: &str = "tests/inputs/zero.txt";
+const NOEOL: &str = "tests/inputs/noeol.txt";
+const NOEOL_DUP: &str = "tests/inputs/noeol_dup.txt";
+const INPUTS: &[&str] = &[
+    "tests/inputs/empty.txt",
+    "tests/inputs/blank.txt",
+    "tests/inputs/dup.txt",
+    "tests/inputs/nodup.txt",
+    "tests/inputs/noeol_dup.txt",
+    "tests/inputs/mixed.txt",
+    "tests/inputs/fields.txt",
+    "tests/inputs/latin1.txt",
+];
+
+// mk-outs.sh で生成した期待値のうち、入力ごとに用意している引数
+const FLAGS: &[(&[&str], &str)] = &[
+    (&[], ""),
+    (&["-c"], ".c"),
+    (&["-d"], ".d"),
+    (&["-u"], ".u"),
+    (&["-i", "-c"], ".i.c"),
+    (&["-d", "-u"], ".d.u"),
+];
+
+// --------------------------------------------------
+fn gen_bad_file() -> String {
+    loop {
+        let filename: String = rand::rng()
+            .sample_iter(&Alphanumeric)
+            .take(7)
+            .map(char::from)
+            .collect();
+
+        if fs::metadata(&filename).is_err() {
+            return filename;
+        }
+    }
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_file() -> Result<()> {
+    let bad = gen_bad_file();
+    let expected = format!("{bad}: .* [(]os error 2[)]");
+    Command::cargo_bin(PRG)?
+        .arg(&bad)
+        .assert()
+        .failure()
+        .stderr(predicate::str::is_match(expected)?);
+    Ok(())
+}
+
+// --------------------------------------------------
+fn run(args: &[&str], expected_file: &str) -> Result<()> {
+    let expected = fs::read(expected_file)?;
+    let output = Command::cargo_bin(PRG)?.args(args).output()?;
+    assert!(output.status.success());
+    assert_eq!(output.stdout, expected, "{args:?}");
+    Ok(())
+}
+
+// --------------------------------------------------
+fn run_stdin(
+    input_file: &str,
+    args: &[&str],
+    expected_file: &str,
+) -> Result<()> {
+    let input = fs::read(input_file)?;
+    let expected = fs::read(expected_file)?;
+    let output = Command::cargo_bin(PRG)?
+        .write_stdin(input)
+        .args(args)
+        .output()?;
+    assert!(output.status.success());
+    assert_eq!(output.stdout, expected, "{args:?} < {input_file}");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn inputs() -> Result<()> {
+    for input in INPUTS {
+        let basename = input.rsplit('/').next().unwrap();
+        for (flags, suffix) in FLAGS {
+            let expected = format!("tests/expected/{basename}{suffix}.out");
+            run(&[*flags, &[input]].concat(), &expected)?;
+            run_stdin(input, flags, &expected)?;
+            run_stdin(input, &[*flags, &["-"]].concat(), &expected)?;
+        }
+    }
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn fields() -> Result<()> {
+    let cases: &[(&[&str], &str)] = &[
+        (&["-f", "1"], "f1"),
+        (&["-f", "2"], "f2"),
+        (&["-s", "2"], "s2"),
+        (&["--skip-fields", "1", "-s", "1", "-w", "3"], "f1.s1.w3"),
+        (&["-f", "2", "-i"], "f2.i"),
+    ];
+    for (args, name) in cases {
+        run(
+            &[&["-c"], *args, &[FIELDS]].concat(),
+            &format!("tests/expected/fields.txt.{name}.out"),
+        )?;
+    }
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn zero_terminated() -> Result<()> {
+    run(&["-z", ZERO], "tests/expected/zero.txt.z.out")?;
+    run(&["-z", "-c", ZERO], "tests/expected/zero.txt.z.c.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn keeps_missing_newline() -> Result<()> {
+    // GNU uniq と異なり、最終行に改行を補わない
+    let cases: &[(&[&str], &str)] = &[
+        (&[NOEOL], "a\nb"),
+        (&["-c", NOEOL], "      1 a\n      1 b"),
+        (&["-u", NOEOL], "a\nb"),
+        (&["-d", NOEOL], ""),
+        (&[NOEOL_DUP], "a\n"),
+        (&["-c", NOEOL_DUP], "      2 a\n"),
+    ];
+    for (args, expected) in cases {
+        Command::cargo_bin(PRG)?
+            .args(*args)
+            .assert()
+            .success()
+            .stdout(*expected);
+    }
+    Command::cargo_bin(PRG)?
+        .write_stdin("x\r\nx\r\ny")
+        .assert()
+        .success()
+        .stdout("x\r\ny");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn outfile() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let outfile = dir.path().join("out.txt");
+    let input = "tests/inputs/mixed.txt";
+
+    Command::cargo_bin(PRG)?
+        .args(["-c", input, outfile.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout("");
+    assert_eq!(
+        fs::read(&outfile)?,
+        fs::read("tests/expected/mixed.txt.c.out")?
+    );
+
+    // 標準入力から読み込んでファイルへ書き出す
+    Command::cargo_bin(PRG)?
+        .args(["-u", "-", outfile.to_str().unwrap()])
+        .write_stdin(fs::read(input)?)
+        .assert()
+        .success();
+    assert_eq!(
+        fs::read(&outfile)?,
+        fs::read("tests/expected/mixed.txt.u.out")?
+    );
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_outfile() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let outfile = dir.path().join("missing").join("out.txt");
+    Command::cargo_bin(PRG)?
+        .args(["tests/inputs/dup.txt", outfile.to_str().unwrap()])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("out.txt: "));
+    Ok(())
+}
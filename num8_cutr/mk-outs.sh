@@ -0,0 +1,38 @@
+#!/usr/bin/env bash
+
+# GNU coreutils の cut で期待値を生成する
+CUT="/usr/bin/cut"
+ROOT="tests/inputs"
+OUT_DIR="tests/expected"
+
+[[ ! -d "$OUT_DIR" ]] && mkdir -p "$OUT_DIR"
+rm -f "$OUT_DIR"/*
+
+# GNU cut の -c はバイト単位で数えるため、マルチバイト文字の期待値はテストに直接書く
+# --csv は GNU cut にないため、その期待値もテストに直接書く
+TSV="$ROOT/books.tsv"
+$CUT -f 1                          $TSV > "$OUT_DIR/books.tsv.f1.out"
+$CUT -f 2,3                        $TSV > "$OUT_DIR/books.tsv.f2,3.out"
+$CUT -f 3,1                        $TSV > "$OUT_DIR/books.tsv.f3,1.out"
+$CUT -f 2-                         $TSV > "$OUT_DIR/books.tsv.f2-.out"
+$CUT -f -2                         $TSV > "$OUT_DIR/books.tsv.f-2.out"
+$CUT -f 2 --complement             $TSV > "$OUT_DIR/books.tsv.f2.complement.out"
+$CUT -f 1,3 --output-delimiter=::  $TSV > "$OUT_DIR/books.tsv.f1,3.od.out"
+$CUT -d , -f 1                     $TSV > "$OUT_DIR/books.tsv.dcomma.f1.out"
+$CUT -d , -f 1 -s                  $TSV > "$OUT_DIR/books.tsv.dcomma.f1.s.out"
+$CUT -b 1-5                        $TSV > "$OUT_DIR/books.tsv.b1-5.out"
+$CUT -b 1-2,4- --output-delimiter=: $TSV > "$OUT_DIR/books.tsv.b1-2,4-.od.out"
+$CUT -b 3 --complement             $TSV > "$OUT_DIR/books.tsv.b3.complement.out"
+
+COLON="$ROOT/colon.txt"
+$CUT -d : -f 1                     $COLON > "$OUT_DIR/colon.txt.f1.out"
+$CUT -d : -f 2-                    $COLON > "$OUT_DIR/colon.txt.f2-.out"
+$CUT -d : -f 2 -s                  $COLON > "$OUT_DIR/colon.txt.f2.s.out"
+$CUT -d : -f 5                     $COLON > "$OUT_DIR/colon.txt.f5.out"
+$CUT -d : -f 1,3 --complement      $COLON > "$OUT_DIR/colon.txt.f1,3.complement.out"
+$CUT -c 1-2,4                      $COLON > "$OUT_DIR/colon.txt.c1-2,4.out"
+$CUT -c 1-2,3 --output-delimiter=_ $COLON > "$OUT_DIR/colon.txt.c1-2,3.od.out"
+$CUT -c 3-                         $COLON > "$OUT_DIR/colon.txt.c3-.out"
+
+$CUT -f 1                          $ROOT/empty.txt > "$OUT_DIR/empty.txt.f1.out"
+$CUT -b 1                          $ROOT/empty.txt > "$OUT_DIR/empty.txt.b1.out"
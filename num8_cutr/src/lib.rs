@@ -0,0 +1,400 @@
+use std::{
+    env,
+    error::Error,
+    ffi::OsString,
+    fs::File,
+    io::{self, BufRead, BufReader, BufWriter, Write},
+    ops::Range,
+};
+
+use clap::{ArgGroup, Parser};
+
+type MyResult<T> = Result<T, Box<dyn Error>>;
+
+/// Zero-based, half-open ranges sorted by start, with overlapping ones merged.
+type PositionList = Vec<Range<usize>>;
+
+#[derive(Debug)]
+pub struct Config {
+    files: Vec<String>,
+    delimiter: u8,
+    output_delimiter: Option<Vec<u8>>,
+    only_delimited: bool,
+    complement: bool,
+    csv: bool,
+    extract: Extract,
+}
+
+#[derive(Debug, PartialEq)]
+enum Extract {
+    Fields(PositionList),
+    Bytes(PositionList),
+    Chars(PositionList),
+}
+
+pub fn get_args() -> MyResult<Config> {
+    get_args_from(env::args_os())
+}
+
+pub fn get_args_from<I, T>(args: I) -> MyResult<Config>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<OsString> + Clone,
+{
+    let args = Cli::parse_from(args);
+    let extract = match (args.fields, args.bytes, args.chars) {
+        (Some(fields), _, _) => Extract::Fields(fields),
+        (_, Some(bytes), _) => Extract::Bytes(bytes),
+        (_, _, Some(chars)) => Extract::Chars(chars),
+        _ => unreachable!("clap requires one of --fields, --bytes and --chars"),
+    };
+    let output_delimiter = args.output_delimiter.map(String::into_bytes);
+    if args.csv
+        && output_delimiter
+            .as_ref()
+            .is_some_and(|delim| delim.len() != 1)
+    {
+        return Err(From::from(
+            "--output-delimiter must be a single byte with --csv",
+        ));
+    }
+
+    Ok(Config {
+        files: args.files,
+        // -d がなければ GNU cut と同じくタブで区切る
+        delimiter: args.delimiter.unwrap_or(b'\t'),
+        output_delimiter,
+        only_delimited: args.only_delimited,
+        complement: args.complement,
+        csv: args.csv,
+        extract,
+    })
+}
+
+pub fn run(config: Config) -> MyResult<()> {
+    let mut stdout = BufWriter::new(io::stdout().lock());
+    for file_name in &config.files {
+        match open(file_name) {
+            Err(err) => eprintln!("{}: {}", file_name, err),
+            Ok(read_buf) => {
+                let result = if config.csv {
+                    cut_csv(read_buf, &config, &mut stdout)
+                } else {
+                    cut_lines(read_buf, &config, &mut stdout)
+                };
+                result.map_err(|e| format!("{}: {}", file_name, e))?;
+            }
+        }
+    }
+    stdout.flush()?;
+    Ok(())
+}
+
+fn cut_lines(mut read_buf: impl BufRead, config: &Config, out: &mut impl Write) -> MyResult<()> {
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        if read_buf.read_until(b'\n', &mut line)? == 0 {
+            return Ok(());
+        }
+        // 最終行に改行がなくても、GNU cut と同様に改行を付けて出力する
+        let line = line.strip_suffix(b"\n").unwrap_or(&line);
+
+        let cut = match &config.extract {
+            Extract::Bytes(positions) => {
+                let spans = selected_spans(positions, config.complement, line.len());
+                join_spans(spans.iter().map(|span| &line[span.clone()]), config)
+            }
+            Extract::Chars(positions) => {
+                let text = String::from_utf8_lossy(line);
+                let chars: Vec<_> = text.chars().collect();
+                let spans = selected_spans(positions, config.complement, chars.len());
+                let texts: Vec<String> = spans
+                    .into_iter()
+                    .map(|span| chars[span].iter().collect())
+                    .collect();
+                join_spans(texts.iter().map(String::as_bytes), config)
+            }
+            Extract::Fields(positions) => {
+                // 区切り文字を含まない行は、-s がなければそのまま出力する
+                if !line.contains(&config.delimiter) {
+                    if config.only_delimited {
+                        continue;
+                    }
+                    line.to_vec()
+                } else {
+                    let fields: Vec<&[u8]> = line.split(|b| *b == config.delimiter).collect();
+                    let delimiter = config
+                        .output_delimiter
+                        .clone()
+                        .unwrap_or_else(|| vec![config.delimiter]);
+                    selected_spans(positions, config.complement, fields.len())
+                        .into_iter()
+                        .flatten()
+                        .map(|index| fields[index])
+                        .collect::<Vec<_>>()
+                        .join(&delimiter[..])
+                }
+            }
+        };
+        out.write_all(&cut)?;
+        out.write_all(b"\n")?;
+    }
+}
+
+/// `-b`/`-c`: selected ranges are joined by `--output-delimiter`, or simply concatenated.
+fn join_spans<'a>(spans: impl Iterator<Item = &'a [u8]>, config: &Config) -> Vec<u8> {
+    let spans: Vec<_> = spans.collect();
+    match &config.output_delimiter {
+        Some(delimiter) => spans.join(&delimiter[..]),
+        None => spans.concat(),
+    }
+}
+
+/// `--csv`: split records the way a CSV parser does, so quoted delimiters and newlines stay
+/// inside their field, and quote the output fields again where needed.
+fn cut_csv(read_buf: impl BufRead, config: &Config, out: &mut impl Write) -> MyResult<()> {
+    let Extract::Fields(positions) = &config.extract else {
+        unreachable!("clap requires --fields with --csv");
+    };
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(config.delimiter)
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(read_buf);
+    let output_delimiter = match &config.output_delimiter {
+        Some(delimiter) => delimiter[0],
+        None => config.delimiter,
+    };
+    let mut builder = csv::WriterBuilder::new();
+    builder.delimiter(output_delimiter).flexible(true);
+    let mut writer = builder.from_writer(out);
+
+    for record in reader.byte_records() {
+        let record = record?;
+        if record.len() < 2 && config.only_delimited {
+            continue;
+        }
+        let fields: Vec<&[u8]> = if record.len() < 2 {
+            record.iter().collect()
+        } else {
+            selected_spans(positions, config.complement, record.len())
+                .into_iter()
+                .flatten()
+                .map(|index| &record[index])
+                .collect()
+        };
+        // 何も選ばれなかったレコードは空行として出力する。csv クレートは空のレコードを
+        // `""` と書くので、書き手から出力を取り戻して改行だけを書き込む
+        if matches!(fields.as_slice(), [] | [b""]) {
+            let out = writer.into_inner().map_err(|err| err.into_error())?;
+            out.write_all(b"\n")?;
+            writer = builder.from_writer(out);
+        } else {
+            writer.write_record(fields)?;
+        }
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Selected ranges of `0..len`, or the gaps between them with `--complement`.
+fn selected_spans(positions: &[Range<usize>], complement: bool, len: usize) -> Vec<Range<usize>> {
+    let clipped = positions
+        .iter()
+        .map(|range| range.start.min(len)..range.end.min(len))
+        .filter(|range| !range.is_empty());
+    if !complement {
+        return clipped.collect();
+    }
+
+    let mut spans = vec![];
+    let mut start = 0;
+    for range in clipped {
+        if start < range.start {
+            spans.push(start..range.start);
+        }
+        start = range.end;
+    }
+    if start < len {
+        spans.push(start..len);
+    }
+    spans
+}
+
+/// Parse a list such as `1,3-5,7-` or `-2` into zero-based ranges.
+fn parse_pos(list: &str) -> Result<PositionList, String> {
+    let mut positions = list
+        .split(',')
+        .map(parse_range)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // GNU cut と同様に、重なる範囲だけをまとめ、隣り合う範囲は別に扱う
+    positions.sort_by_key(|range| range.start);
+    let mut merged: PositionList = vec![];
+    for range in positions {
+        match merged.last_mut() {
+            Some(last) if range.start < last.end => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
+    Ok(merged)
+}
+
+fn parse_range(range: &str) -> Result<Range<usize>, String> {
+    let parse_num = |value: &str| -> Result<usize, String> {
+        // usize の parse は先頭の + を受け付けるため、数字だけかを先に確認する
+        if value.is_empty() || !value.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(format!("invalid list value: '{}'", range));
+        }
+        match value.parse() {
+            Ok(0) => Err("fields and positions are numbered from 1".to_string()),
+            Ok(num) => Ok(num),
+            Err(_) => Err(format!("invalid list value: '{}'", range)),
+        }
+    };
+
+    match range.split_once('-') {
+        None => parse_num(range).map(|num| num - 1..num),
+        Some(("", "")) => Err(format!("invalid range with no endpoint: {}", range)),
+        Some(("", end)) => parse_num(end).map(|end| 0..end),
+        Some((start, "")) => parse_num(start).map(|start| start - 1..usize::MAX),
+        Some((start, end)) => {
+            let (start, end) = (parse_num(start)?, parse_num(end)?);
+            if start > end {
+                return Err("invalid decreasing range".to_string());
+            }
+            Ok(start - 1..end)
+        }
+    }
+}
+
+fn parse_delimiter(delimiter: &str) -> Result<u8, String> {
+    match delimiter.as_bytes() {
+        [byte] => Ok(*byte),
+        _ => Err("the delimiter must be a single byte".to_string()),
+    }
+}
+
+#[derive(Parser, Debug)]
+#[command(version)]
+#[command(about = "Rust cut")]
+#[command(group(ArgGroup::new("extract").required(true).args(["fields", "bytes", "chars"])))]
+struct Cli {
+    /// Target files.
+    #[arg(num_args = 1.., default_values_t = ["-".to_string()])]
+    files: Vec<String>,
+
+    /// Select only these fields, e.g. 1,3-5,7-.
+    #[arg(
+        short('f'),
+        long,
+        value_name = "LIST",
+        value_parser = parse_pos,
+        allow_hyphen_values = true
+    )]
+    fields: Option<PositionList>,
+
+    /// Select only these bytes.
+    #[arg(
+        short('b'),
+        long,
+        value_name = "LIST",
+        value_parser = parse_pos,
+        allow_hyphen_values = true
+    )]
+    bytes: Option<PositionList>,
+
+    /// Select only these characters.
+    #[arg(
+        short('c'),
+        long,
+        value_name = "LIST",
+        value_parser = parse_pos,
+        allow_hyphen_values = true
+    )]
+    chars: Option<PositionList>,
+
+    /// Field delimiter, TAB by default.
+    #[arg(
+        short('d'),
+        long,
+        value_name = "DELIM",
+        conflicts_with_all = ["bytes", "chars"],
+        value_parser = parse_delimiter
+    )]
+    delimiter: Option<u8>,
+
+    /// Use STRING as the output delimiter.
+    #[arg(long, value_name = "STRING")]
+    output_delimiter: Option<String>,
+
+    /// Do not print lines not containing delimiters.
+    #[arg(short('s'), long, conflicts_with_all = ["bytes", "chars"])]
+    only_delimited: bool,
+
+    /// Select the fields, bytes or characters not in the list.
+    #[arg(long)]
+    complement: bool,
+
+    /// Treat input as CSV, so quoted fields may contain delimiters and newlines.
+    #[arg(long, conflicts_with_all = ["bytes", "chars"])]
+    csv: bool,
+}
+
+fn open(file_name: &str) -> MyResult<Box<dyn BufRead>> {
+    match file_name {
+        "-" => Ok(Box::new(BufReader::new(io::stdin()))),
+        _ => Ok(Box::new(BufReader::new(File::open(file_name)?))),
+    }
+}
+
+#[test]
+// 範囲が一つだけの Vec は意図したもの
+#[allow(clippy::single_range_in_vec_init)]
+fn test_parse_pos() {
+    assert_eq!(parse_pos("1"), Ok(vec![0..1]));
+    assert_eq!(parse_pos("1,3-5,7-"), Ok(vec![0..1, 2..5, 6..usize::MAX]));
+    assert_eq!(parse_pos("-3"), Ok(vec![0..3]));
+    assert_eq!(parse_pos("5,1-2"), Ok(vec![0..2, 4..5]));
+    // 重なる範囲はまとめるが、隣り合う範囲はまとめない
+    assert_eq!(parse_pos("1-3,2-5,4"), Ok(vec![0..5]));
+    assert_eq!(parse_pos("1-2,3"), Ok(vec![0..2, 2..3]));
+    assert_eq!(parse_pos("2-,4"), Ok(vec![1..usize::MAX]));
+
+    let numbered_from_1 = Err("fields and positions are numbered from 1".to_string());
+    assert_eq!(parse_pos("0"), numbered_from_1);
+    assert_eq!(parse_pos("0-2"), numbered_from_1);
+    assert_eq!(
+        parse_pos("3-1"),
+        Err("invalid decreasing range".to_string())
+    );
+    assert_eq!(
+        parse_pos("-"),
+        Err("invalid range with no endpoint: -".to_string())
+    );
+    for bad in ["", "a", "1,", "+1", "1-a", "1-2-3", "1.5"] {
+        assert!(parse_pos(bad).is_err(), "{}", bad);
+    }
+}
+
+#[test]
+// 範囲が一つだけの Vec は意図したもの
+#[allow(clippy::single_range_in_vec_init)]
+fn test_selected_spans() {
+    let positions = vec![0..2, 2..3, 5..usize::MAX];
+    assert_eq!(selected_spans(&positions, false, 10), [0..2, 2..3, 5..10]);
+    assert_eq!(selected_spans(&positions, false, 4), [0..2, 2..3]);
+    assert_eq!(selected_spans(&positions, false, 0), []);
+    assert_eq!(selected_spans(&positions, true, 10), [3..5]);
+    assert_eq!(selected_spans(&positions, true, 4), [3..4]);
+    assert_eq!(selected_spans(&[1..2], true, 3), [0..1, 2..3]);
+}
+
+#[test]
+fn test_parse_delimiter() {
+    assert_eq!(parse_delimiter(","), Ok(b','));
+    assert!(parse_delimiter("").is_err());
+    assert!(parse_delimiter(",,").is_err());
+    assert!(parse_delimiter("é").is_err());
+}
@@ -0,0 +1,6 @@
+fn main() {
+    if let Err(e) = num8_cutr::get_args().and_then(num8_cutr::run) {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+}
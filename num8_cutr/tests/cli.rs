@@ -0,0 +1,321 @@
+use anyhow::Result;
+use assert_cmd::Command;
+use predicates::prelude::*;
+use pretty_assertions::assert_eq;
+use rand::{distr::Alphanumeric, Rng};
+use std::fs;
+
+const PRG: &str = "num8_cutr";
+const TSV: &str = "tests/inputs/books.tsv";
+const CSV: &str = "tests/inputs/books.csv";
+const COLON: &str = "tests/inputs/colon.txt";
+const EMPTY: &str = "tests/inputs/empty.txt";
+
+// --------------------------------------------------
+fn gen_bad_file() -> String {
+    loop {
+        let filename: String = rand::rng()
+            .sample_iter(&Alphanumeric)
+            .take(7)
+            .map(char::from)
+            .collect();
+
+        if fs::metadata(&filename).is_err() {
+            return filename;
+        }
+    }
+}
+
+// --------------------------------------------------
+#[test]
+fn skips_bad_file() -> Result<()> {
+    let bad = gen_bad_file();
+    let expected = format!("{bad}: .* [(]os error 2[)]");
+    Command::cargo_bin(PRG)?
+        .args(["-f", "1", &bad, TSV])
+        .assert()
+        .success()
+        .stderr(predicate::str::is_match(expected)?)
+        .stdout(fs::read_to_string("tests/expected/books.tsv.f1.out")?);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_no_list() -> Result<()> {
+    Command::cargo_bin(PRG)?
+        .arg(TSV)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("required"));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_multiple_lists() -> Result<()> {
+    Command::cargo_bin(PRG)?
+        .args(["-f", "1", "-b", "1", TSV])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_list() -> Result<()> {
+    let cases = [
+        ("0", "fields and positions are numbered from 1"),
+        ("1,0-2", "fields and positions are numbered from 1"),
+        ("3-1", "invalid decreasing range"),
+        ("-", "invalid range with no endpoint: -"),
+        ("a", "invalid list value: 'a'"),
+        ("1,+2", "invalid list value: '+2'"),
+    ];
+    for (list, expected) in cases {
+        for flag in ["-f", "-b", "-c"] {
+            Command::cargo_bin(PRG)?
+                .args([flag, list, TSV])
+                .assert()
+                .failure()
+                .stderr(predicate::str::contains(expected));
+        }
+    }
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_delimiter() -> Result<()> {
+    for delimiter in ["", ",,"] {
+        Command::cargo_bin(PRG)?
+            .args(["-f", "1", "-d", delimiter, TSV])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "the delimiter must be a single byte",
+            ));
+    }
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_field_options_without_fields() -> Result<()> {
+    for option in ["-s", "--csv", "-d,"] {
+        Command::cargo_bin(PRG)?
+            .args(["-b", "1", option, TSV])
+            .assert()
+            .failure();
+    }
+    Ok(())
+}
+
+// --------------------------------------------------
+fn run(args: &[&str], expected_file: &str) -> Result<()> {
+    let expected = fs::read(expected_file)?;
+    let output = Command::cargo_bin(PRG)?.args(args).output()?;
+    assert!(output.status.success());
+    assert_eq!(output.stdout, expected, "{args:?}");
+    Ok(())
+}
+
+// --------------------------------------------------
+fn run_stdin(
+    input_file: &str,
+    args: &[&str],
+    expected_file: &str,
+) -> Result<()> {
+    let input = fs::read(input_file)?;
+    let expected = fs::read(expected_file)?;
+    let output = Command::cargo_bin(PRG)?
+        .write_stdin(input)
+        .args(args)
+        .output()?;
+    assert!(output.status.success());
+    assert_eq!(output.stdout, expected, "{args:?} < {input_file}");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn books_tsv() -> Result<()> {
+    let cases: &[(&[&str], &str)] = &[
+        (&["-f", "1"], "f1"),
+        (&["-f", "2,3"], "f2,3"),
+        (&["--fields", "3,1"], "f3,1"),
+        (&["-f", "2-"], "f2-"),
+        (&["-f", "-2"], "f-2"),
+        (&["-f", "2", "--complement"], "f2.complement"),
+        (&["-f", "1,3", "--output-delimiter=::"], "f1,3.od"),
+        (&["-d", ",", "-f", "1"], "dcomma.f1"),
+        (&["-d", ",", "-f", "1", "-s"], "dcomma.f1.s"),
+        (&["-b", "1-5"], "b1-5"),
+        (&["-b", "1-2,4-", "--output-delimiter=:"], "b1-2,4-.od"),
+        (&["--bytes", "3", "--complement"], "b3.complement"),
+    ];
+    for (args, name) in cases {
+        let expected = format!("tests/expected/books.tsv.{name}.out");
+        run(&[*args, &[TSV]].concat(), &expected)?;
+        run_stdin(TSV, args, &expected)?;
+        run_stdin(TSV, &[*args, &["-"]].concat(), &expected)?;
+    }
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn colon() -> Result<()> {
+    let cases: &[(&[&str], &str)] = &[
+        (&["-d", ":", "-f", "1"], "f1"),
+        (&["-d", ":", "-f", "2-"], "f2-"),
+        (&["-d", ":", "-f", "2", "-s"], "f2.s"),
+        (&["-d", ":", "-f", "5"], "f5"),
+        (&["-d", ":", "-f", "1,3", "--complement"], "f1,3.complement"),
+        (&["-c", "1-2,4"], "c1-2,4"),
+        (&["-c", "1-2,3", "--output-delimiter=_"], "c1-2,3.od"),
+        (&["-c", "3-"], "c3-"),
+    ];
+    for (args, name) in cases {
+        let expected = format!("tests/expected/colon.txt.{name}.out");
+        run(&[*args, &[COLON]].concat(), &expected)?;
+        run_stdin(COLON, args, &expected)?;
+    }
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn empty() -> Result<()> {
+    run(&["-f", "1", EMPTY], "tests/expected/empty.txt.f1.out")?;
+    run(&["-b", "1", EMPTY], "tests/expected/empty.txt.b1.out")?;
+    run(
+        &["-f", "1", "--csv", EMPTY],
+        "tests/expected/empty.txt.f1.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn multiple_files() -> Result<()> {
+    let expected = [
+        fs::read_to_string("tests/expected/books.tsv.f1.out")?,
+        fs::read_to_string("tests/expected/books.tsv.f1.out")?,
+    ]
+    .concat();
+    Command::cargo_bin(PRG)?
+        .args(["-f", "1", TSV, EMPTY, TSV])
+        .assert()
+        .success()
+        .stdout(expected);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn chars_are_not_bytes() -> Result<()> {
+    // GNU cut と異なり、-c はマルチバイト文字を一文字として数える
+    Command::cargo_bin(PRG)?
+        .args(["-c", "1-5", TSV])
+        .assert()
+        .success()
+        .stdout("Autho\nÉmile\nSamue\nJules\n");
+    Command::cargo_bin(PRG)?
+        .args(["-c", "2", "--complement"])
+        .write_stdin("日本語\n")
+        .assert()
+        .success()
+        .stdout("日語\n");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn csv_fields() -> Result<()> {
+    // 引用符で囲まれた区切り文字や改行はフィールドの一部として扱う
+    let cases: &[(&[&str], &str)] = &[
+        (
+            &["-f", "3"],
+            "Title\n\
+             La Confession de Claude\n\
+             Waiting for Godot\n\
+             \"20,000 Leagues Under the Sea\"\n\
+             \"Alice's Adventures\nin Wonderland\"\n",
+        ),
+        (
+            &["-f", "1,2"],
+            "Author,Year\n\
+             Émile Zola,1865\n\
+             Samuel Beckett,1952\n\
+             Jules Verne,1870\n\
+             \"Lewis \"\"Lewis\"\" Carroll\",1865\n",
+        ),
+        (
+            &["-f", "1", "--complement", "--output-delimiter=\t"],
+            "Year\tTitle\n\
+             1865\tLa Confession de Claude\n\
+             1952\tWaiting for Godot\n\
+             1870\t20,000 Leagues Under the Sea\n\
+             1865\t\"Alice's Adventures\nin Wonderland\"\n",
+        ),
+    ];
+    for (args, expected) in cases {
+        Command::cargo_bin(PRG)?
+            .args([&["-d", ",", "--csv"], *args, &[CSV]].concat())
+            .assert()
+            .success()
+            .stdout(*expected);
+    }
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn csv_only_delimited() -> Result<()> {
+    let input = "a,b\nno delimiter\n\"quoted, not split\"\nc,d\n";
+    Command::cargo_bin(PRG)?
+        .args(["-d", ",", "--csv", "-f", "2"])
+        .write_stdin(input)
+        .assert()
+        .success()
+        .stdout("b\nno delimiter\n\"quoted, not split\"\nd\n");
+    Command::cargo_bin(PRG)?
+        .args(["-d", ",", "--csv", "-f", "2", "-s"])
+        .write_stdin(input)
+        .assert()
+        .success()
+        .stdout("b\nd\n");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn csv_empty_record() -> Result<()> {
+    // 何も選ばれなかったレコードや空のフィールドだけのレコードは空行になる
+    let input = "a,b\n,c\n\"\",d\n";
+    for (field, expected) in
+        [("5", "\n\n\n"), ("1", "a\n\n\n"), ("1,2", "a,b\n,c\n,d\n")]
+    {
+        Command::cargo_bin(PRG)?
+            .args(["-d", ",", "--csv", "-f", field])
+            .write_stdin(input)
+            .assert()
+            .success()
+            .stdout(expected);
+    }
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_csv_long_output_delimiter() -> Result<()> {
+    Command::cargo_bin(PRG)?
+        .args(["-d", ",", "--csv", "-f", "1", "--output-delimiter=::", CSV])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "--output-delimiter must be a single byte with --csv",
+        ));
+    Ok(())
+}
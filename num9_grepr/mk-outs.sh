@@ -0,0 +1,45 @@
+#!/usr/bin/env bash
+
+# GNU grep で期待値を生成する
+# grepr のパターンは拡張正規表現に近いため、GNU grep には -E を付ける
+GREP="/usr/bin/grep -E"
+FGREP="/usr/bin/grep -F"
+ROOT="tests/inputs"
+OUT_DIR="tests/expected"
+
+[[ ! -d "$OUT_DIR" ]] && mkdir -p "$OUT_DIR"
+rm -f "$OUT_DIR"/*
+
+BUSTLE="$ROOT/bustle.txt"
+EMPTY="$ROOT/empty.txt"
+FOX="$ROOT/fox.txt"
+NOBODY="$ROOT/nobody.txt"
+NOEOL="$ROOT/noeol.txt"
+
+$GREP foo                      $EMPTY   > "$OUT_DIR/foo.empty"
+$GREP ""                       $FOX     > "$OUT_DIR/empty_regex.fox"
+$GREP The                      $BUSTLE  > "$OUT_DIR/bustle.txt.the.capitalized"
+$GREP the                      $BUSTLE  > "$OUT_DIR/bustle.txt.the.lowercase"
+$GREP -i the                   $BUSTLE  > "$OUT_DIR/bustle.txt.the.lowercase.insensitive"
+$GREP -n -v the                $BUSTLE  > "$OUT_DIR/bustle.txt.the.n.v"
+$GREP Nobody                   $NOBODY  > "$OUT_DIR/nobody.txt"
+$GREP -v Nobody                $NOBODY  > "$OUT_DIR/nobody.txt.v"
+$GREP -c Nobody                $NOBODY  > "$OUT_DIR/nobody.txt.c"
+$GREP -c -v Nobody             $NOBODY  > "$OUT_DIR/nobody.txt.c.v"
+$GREP fox                      $NOEOL   > "$OUT_DIR/noeol.txt"
+$GREP The                      $BUSTLE $EMPTY $FOX $NOBODY > "$OUT_DIR/all.the"
+$GREP -n -i the                $BUSTLE $EMPTY $FOX $NOBODY > "$OUT_DIR/all.the.n.i"
+$GREP -c The                   $BUSTLE $EMPTY $FOX $NOBODY > "$OUT_DIR/all.the.c"
+$GREP -l The                   $BUSTLE $EMPTY $FOX $NOBODY > "$OUT_DIR/all.the.l"
+$GREP -l -v The                $BUSTLE $EMPTY $FOX $NOBODY > "$OUT_DIR/all.the.l.v"
+$GREP -e fox -e Nobody         $BUSTLE $FOX $NOBODY > "$OUT_DIR/all.e.fox.nobody"
+$FGREP -e "you?" -e "us!"   $NOBODY  > "$OUT_DIR/nobody.txt.F"
+$GREP -n -A 1 How              $NOBODY  > "$OUT_DIR/nobody.txt.A1"
+$GREP -n -B 2 "^To"            $NOBODY  > "$OUT_DIR/nobody.txt.B2"
+$GREP -n -C 1 "tell|Bog"       $NOBODY  > "$OUT_DIR/nobody.txt.C1"
+$GREP -n -C 1 The              $BUSTLE $NOBODY > "$OUT_DIR/all.C1"
+$GREP -v -A 1 -B 2 e           $BUSTLE  > "$OUT_DIR/bustle.txt.v.A1.B2"
+$GREP --color=always -n the    $BUSTLE $FOX > "$OUT_DIR/all.the.n.color"
+$GREP --color=always -i -C 1 "the|in" $BUSTLE > "$OUT_DIR/bustle.txt.i.C1.color"
+$GREP --color=always -c The    $BUSTLE $FOX > "$OUT_DIR/all.the.c.color"
+$GREP --color=always -l The    $BUSTLE $FOX > "$OUT_DIR/all.the.l.color"
@@ -0,0 +1,515 @@
+use std::{
+    collections::VecDeque,
+    env,
+    error::Error,
+    ffi::OsString,
+    fs::{self, File},
+    io::{self, BufRead, BufReader, BufWriter, IsTerminal, Write},
+    path::Path,
+    process::ExitCode,
+};
+
+use clap::{Parser, ValueEnum};
+use regex::bytes::{Regex, RegexBuilder};
+use walkdir::WalkDir;
+
+type MyResult<T> = Result<T, Box<dyn Error>>;
+
+// GNU grep の既定の GREP_COLORS と同じ色
+const MATCH_COLOR: &str = "01;31";
+const FILE_NAME_COLOR: &str = "35";
+const LINE_NUMBER_COLOR: &str = "32";
+const SEPARATOR_COLOR: &str = "36";
+
+#[derive(Debug)]
+pub struct Config {
+    pattern: Regex,
+    files: Vec<String>,
+    recursive: bool,
+    count: bool,
+    invert_match: bool,
+    line_number: bool,
+    files_with_matches: bool,
+    before_context: usize,
+    after_context: usize,
+    color: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+enum ColorWhen {
+    Auto,
+    Always,
+    Never,
+}
+
+#[derive(Parser, Debug)]
+#[command(version)]
+#[command(about = "Rust grep")]
+struct Cli {
+    /// Search pattern, unless given with -e.
+    #[arg(required_unless_present = "regexps")]
+    pattern: Option<String>,
+
+    /// Input files or directories.
+    files: Vec<String>,
+
+    /// Use PATTERN for matching; may be repeated.
+    #[arg(short('e'), long("regexp"), value_name = "PATTERN")]
+    regexps: Vec<String>,
+
+    /// PATTERNs are strings, not regular expressions.
+    #[arg(short('F'), long)]
+    fixed_strings: bool,
+
+    /// Ignore case distinctions in patterns and data.
+    #[arg(short, long)]
+    ignore_case: bool,
+
+    /// Select non-matching lines.
+    #[arg(short('v'), long)]
+    invert_match: bool,
+
+    /// Print only a count of selected lines per file.
+    #[arg(short, long)]
+    count: bool,
+
+    /// Print line numbers with output lines.
+    #[arg(short('n'), long)]
+    line_number: bool,
+
+    /// Print only names of files with selected lines.
+    #[arg(short('l'), long)]
+    files_with_matches: bool,
+
+    /// Search directories recursively.
+    #[arg(short, long)]
+    recursive: bool,
+
+    /// Print NUM lines of trailing context.
+    #[arg(short('A'), long, value_name = "NUM")]
+    after_context: Option<usize>,
+
+    /// Print NUM lines of leading context.
+    #[arg(short('B'), long, value_name = "NUM")]
+    before_context: Option<usize>,
+
+    /// Print NUM lines of output context.
+    #[arg(short('C'), long, value_name = "NUM")]
+    context: Option<usize>,
+
+    /// Highlight matches, file names and line numbers.
+    #[arg(
+        long,
+        value_name = "WHEN",
+        value_enum,
+        default_value_t = ColorWhen::Auto,
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "auto"
+    )]
+    color: ColorWhen,
+}
+
+pub fn get_args() -> MyResult<Config> {
+    get_args_from(env::args_os())
+}
+
+pub fn get_args_from<I, T>(args: I) -> MyResult<Config>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<OsString> + Clone,
+{
+    let args = Cli::parse_from(args);
+
+    // -e がある場合、最初の位置引数はパターンではなくファイル
+    let mut files = args.files;
+    let patterns = if args.regexps.is_empty() {
+        args.pattern.into_iter().collect()
+    } else {
+        files.splice(0..0, args.pattern);
+        args.regexps
+    };
+    let color = match args.color {
+        ColorWhen::Auto => io::stdout().is_terminal(),
+        ColorWhen::Always => true,
+        ColorWhen::Never => false,
+    };
+
+    Ok(Config {
+        pattern: build_pattern(&patterns, args.fixed_strings, args.ignore_case)?,
+        files,
+        recursive: args.recursive,
+        count: args.count,
+        invert_match: args.invert_match,
+        line_number: args.line_number,
+        files_with_matches: args.files_with_matches,
+        before_context: args.before_context.or(args.context).unwrap_or(0),
+        after_context: args.after_context.or(args.context).unwrap_or(0),
+        color,
+    })
+}
+
+/// Search the files and return the exit status GNU grep would: 0 if a line was selected, 1 if
+/// none was, and 2 if an input could not be read, even when lines were selected. Callers exit
+/// with 2 on an `Err` as well.
+pub fn run(config: Config) -> MyResult<ExitCode> {
+    let files = find_files(&config.files, config.recursive);
+    let mut printer = Printer {
+        config: &config,
+        out: BufWriter::new(io::stdout().lock()),
+        show_names: config.recursive || config.files.len() > 1,
+        printed_group: false,
+        selected: false,
+        failed: false,
+    };
+    for file in files {
+        match file {
+            Err(err) => {
+                eprintln!("{}", err);
+                printer.failed = true;
+            }
+            Ok(name) => match open(&name) {
+                Err(err) => {
+                    eprintln!("{}: {}", name, err);
+                    printer.failed = true;
+                }
+                Ok(read_buf) => printer.search(&name, read_buf)?,
+            },
+        }
+    }
+    printer.out.flush()?;
+
+    Ok(ExitCode::from(match (printer.failed, printer.selected) {
+        (true, _) => 2,
+        (false, true) => 0,
+        (false, false) => 1,
+    }))
+}
+
+/// Join `patterns` into one regex that matches a line if any of them does. As with GNU grep, a
+/// pattern containing newlines is a list of patterns.
+fn build_pattern(patterns: &[String], fixed_strings: bool, ignore_case: bool) -> MyResult<Regex> {
+    let mut alternatives = vec![];
+    for pattern in patterns.iter().flat_map(|pattern| pattern.split('\n')) {
+        if fixed_strings {
+            alternatives.push(regex::escape(pattern));
+            continue;
+        }
+        // どのパターンが不正かを示すため、一つずつ確認する
+        if Regex::new(pattern).is_err() {
+            return Err(format!("Invalid pattern \"{}\"", pattern).into());
+        }
+        alternatives.push(format!("(?:{})", pattern));
+    }
+
+    Ok(RegexBuilder::new(&alternatives.join("|"))
+        .case_insensitive(ignore_case)
+        .build()?)
+}
+
+/// Expand `files` into the files to search, walking directories in name order with `-r`.
+/// Without any files, `-r` searches the working directory and `-` is stdin otherwise.
+fn find_files(files: &[String], recursive: bool) -> Vec<MyResult<String>> {
+    if files.is_empty() {
+        if !recursive {
+            return vec![Ok("-".to_string())];
+        }
+        // GNU grep と同様に、作業ディレクトリのファイル名には ./ を付けない
+        return walk(".")
+            .map(|path| {
+                path.map(|path| {
+                    let path = Path::new(&path);
+                    path.strip_prefix(".").unwrap_or(path).display().to_string()
+                })
+            })
+            .collect();
+    }
+
+    let mut results = vec![];
+    for name in files {
+        if name == "-" {
+            results.push(Ok(name.to_string()));
+            continue;
+        }
+        match fs::metadata(name) {
+            Err(err) => results.push(Err(format!("{}: {}", name, err).into())),
+            Ok(meta) if meta.is_dir() => {
+                if recursive {
+                    results.extend(walk(name));
+                } else {
+                    results.push(Err(format!("{}: Is a directory", name).into()));
+                }
+            }
+            Ok(_) => results.push(Ok(name.to_string())),
+        }
+    }
+    results
+}
+
+fn walk(dir: &str) -> impl Iterator<Item = MyResult<String>> {
+    WalkDir::new(dir)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_map(|entry| match entry {
+            Err(err) => Some(Err(err.into())),
+            Ok(entry) if entry.file_type().is_dir() => None,
+            Ok(entry) => Some(Ok(entry.path().display().to_string())),
+        })
+}
+
+fn open(file_name: &str) -> MyResult<Box<dyn BufRead>> {
+    match file_name {
+        "-" => Ok(Box::new(BufReader::new(io::stdin()))),
+        _ => Ok(Box::new(BufReader::new(File::open(file_name)?))),
+    }
+}
+
+struct Printer<'a, W> {
+    config: &'a Config,
+    out: W,
+    show_names: bool,
+    /// Whether a group of lines has been printed, so the next one needs a `--` before it.
+    printed_group: bool,
+    /// Whether any line has been selected, in any file.
+    selected: bool,
+    /// Whether an input could not be read.
+    failed: bool,
+}
+
+impl<W: Write> Printer<'_, W> {
+    fn search(&mut self, name: &str, mut read_buf: impl BufRead) -> MyResult<()> {
+        let name = if name == "-" {
+            "(standard input)"
+        } else {
+            name
+        };
+        let config = self.config;
+        let has_context = config.before_context > 0 || config.after_context > 0;
+
+        let mut count = 0;
+        let mut before = VecDeque::new();
+        let mut after_left = 0;
+        let mut last_printed = None;
+        let mut line = Vec::new();
+        let mut line_number = 0;
+        loop {
+            line.clear();
+            match read_buf.read_until(b'\n', &mut line) {
+                Ok(0) => break,
+                Ok(_) => {}
+                // 読めなかった以降は飛ばし、それまでの結果は出力する
+                Err(err) => {
+                    eprintln!("{}: {}", name, err);
+                    self.failed = true;
+                    break;
+                }
+            }
+            line_number += 1;
+            let text = line.strip_suffix(b"\n").unwrap_or(&line);
+
+            if config.pattern.is_match(text) == config.invert_match {
+                // 選ばれなかった行は、直前の一致の後ろか次の一致の前の文脈になりうる
+                if after_left > 0 {
+                    after_left -= 1;
+                    self.write_line(name, line_number, b'-', text)?;
+                    last_printed = Some(line_number);
+                } else if config.before_context > 0 {
+                    if before.len() == config.before_context {
+                        before.pop_front();
+                    }
+                    before.push_back((line_number, text.to_vec()));
+                }
+                continue;
+            }
+
+            count += 1;
+            self.selected = true;
+            if config.files_with_matches {
+                break;
+            }
+            if config.count {
+                continue;
+            }
+            // 前に出力した行と続いていなければ区切りを入れる
+            let first = before.front().map_or(line_number, |(number, _)| *number);
+            if has_context && self.printed_group && last_printed.is_none_or(|last| first > last + 1)
+            {
+                self.write_colored(b"--", SEPARATOR_COLOR)?;
+                self.out.write_all(b"\n")?;
+            }
+            for (number, text) in before.drain(..) {
+                self.write_line(name, number, b'-', &text)?;
+            }
+            self.write_line(name, line_number, b':', text)?;
+            last_printed = Some(line_number);
+            after_left = config.after_context;
+            self.printed_group = true;
+        }
+
+        if config.files_with_matches {
+            if count > 0 {
+                self.write_colored(name.as_bytes(), FILE_NAME_COLOR)?;
+                self.out.write_all(b"\n")?;
+            }
+        } else if config.count {
+            if self.show_names {
+                self.write_colored(name.as_bytes(), FILE_NAME_COLOR)?;
+                self.write_colored(b":", SEPARATOR_COLOR)?;
+            }
+            writeln!(self.out, "{}", count)?;
+        }
+        Ok(())
+    }
+
+    /// Print a line with its prefixes; `separator` is `:` for selected lines and `-` for context.
+    fn write_line(
+        &mut self,
+        name: &str,
+        line_number: usize,
+        separator: u8,
+        text: &[u8],
+    ) -> io::Result<()> {
+        if self.show_names {
+            self.write_colored(name.as_bytes(), FILE_NAME_COLOR)?;
+            self.write_colored(&[separator], SEPARATOR_COLOR)?;
+        }
+        if self.config.line_number {
+            self.write_colored(line_number.to_string().as_bytes(), LINE_NUMBER_COLOR)?;
+            self.write_colored(&[separator], SEPARATOR_COLOR)?;
+        }
+
+        let mut start = 0;
+        if self.config.color {
+            for found in self.config.pattern.find_iter(text) {
+                if found.is_empty() {
+                    continue;
+                }
+                self.out.write_all(&text[start..found.start()])?;
+                self.write_colored(found.as_bytes(), MATCH_COLOR)?;
+                start = found.end();
+            }
+        }
+        self.out.write_all(&text[start..])?;
+        self.out.write_all(b"\n")
+    }
+
+    fn write_colored(&mut self, text: &[u8], color: &str) -> io::Result<()> {
+        if !self.config.color {
+            return self.out.write_all(text);
+        }
+        write!(self.out, "\x1b[{}m\x1b[K", color)?;
+        self.out.write_all(text)?;
+        self.out.write_all(b"\x1b[m\x1b[K")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn config(pattern: &str, before_context: usize, after_context: usize) -> Config {
+        Config {
+            pattern: build_pattern(&[pattern.to_string()], false, false).unwrap(),
+            files: vec![],
+            recursive: false,
+            count: false,
+            invert_match: false,
+            line_number: true,
+            files_with_matches: false,
+            before_context,
+            after_context,
+            color: false,
+        }
+    }
+
+    fn search(config: &Config, inputs: &[&str]) -> String {
+        let mut printer = Printer {
+            config,
+            out: Vec::new(),
+            show_names: false,
+            printed_group: false,
+            selected: false,
+            failed: false,
+        };
+        for input in inputs {
+            printer.search("-", input.as_bytes()).unwrap();
+        }
+        String::from_utf8(printer.out).unwrap()
+    }
+
+    #[test]
+    fn test_build_pattern() {
+        let patterns = |patterns: &[&str], fixed_strings, ignore_case| {
+            let patterns: Vec<_> = patterns.iter().map(|p| p.to_string()).collect();
+            build_pattern(&patterns, fixed_strings, ignore_case)
+        };
+
+        let re = patterns(&["fox", "^The"], false, false).unwrap();
+        assert!(re.is_match(b"a fox"));
+        assert!(re.is_match(b"The end"));
+        assert!(!re.is_match(b"the end"));
+
+        let re = patterns(&["a.c\nx"], true, true).unwrap();
+        assert!(re.is_match(b"A.C"));
+        assert!(re.is_match(b"X"));
+        assert!(!re.is_match(b"abc"));
+
+        let re = patterns(&[""], false, false).unwrap();
+        assert!(re.is_match(b""));
+
+        let err = patterns(&["ok", "*foo"], false, false).unwrap_err();
+        assert_eq!(err.to_string(), "Invalid pattern \"*foo\"");
+        assert!(patterns(&["*foo"], true, false).is_ok());
+    }
+
+    #[test]
+    fn test_context() {
+        let input = "a fox\nb\nc fox\nd\ne\nf\ng fox\n";
+        assert_eq!(
+            search(&config("fox", 0, 0), &[input]),
+            "1:a fox\n3:c fox\n7:g fox\n"
+        );
+        assert_eq!(
+            search(&config("fox", 1, 1), &[input]),
+            "1:a fox\n2-b\n3:c fox\n4-d\n--\n6-f\n7:g fox\n"
+        );
+        assert_eq!(
+            search(&config("fox", 0, 2), &[input]),
+            "1:a fox\n2-b\n3:c fox\n4-d\n5-e\n--\n7:g fox\n"
+        );
+        // 別の入力との間にも区切りを入れる
+        assert_eq!(
+            search(&config("c", 1, 0), &[input, input]),
+            "2-b\n3:c fox\n--\n2-b\n3:c fox\n"
+        );
+        assert_eq!(
+            search(&config("c", 0, 0), &[input, input]),
+            "3:c fox\n3:c fox\n"
+        );
+    }
+
+    #[test]
+    fn test_find_files() {
+        let files = find_files(&["tests/inputs/fox.txt".to_string()], false);
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].as_ref().unwrap(), "tests/inputs/fox.txt");
+
+        let files = find_files(&["tests/inputs".to_string()], false);
+        assert_eq!(
+            files[0].as_ref().unwrap_err().to_string(),
+            "tests/inputs: Is a directory"
+        );
+
+        let files: Vec<_> = find_files(&["tests/inputs".to_string()], true)
+            .into_iter()
+            .map(Result::unwrap)
+            .collect();
+        assert!(files.contains(&"tests/inputs/fox.txt".to_string()));
+        let mut sorted = files.clone();
+        sorted.sort();
+        assert_eq!(files, sorted);
+
+        let files = find_files(&[], false);
+        assert_eq!(files[0].as_ref().unwrap(), "-");
+    }
+}
@@ -0,0 +1,12 @@
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    match num9_grepr::get_args().and_then(num9_grepr::run) {
+        Ok(status) => status,
+        // GNU grep と同様に、不正なパターンや出力のエラーも選ばれた行がない場合と区別する
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::from(2)
+        }
+    }
+}
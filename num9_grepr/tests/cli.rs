@@ -0,0 +1,298 @@
+use anyhow::Result;
+use assert_cmd::Command;
+use predicates::prelude::*;
+use pretty_assertions::assert_eq;
+use rand::{distr::Alphanumeric, Rng};
+use std::fs;
+
+const PRG: &str = "num9_grepr";
+const BUSTLE: &str = "tests/inputs/bustle.txt";
+const EMPTY: &str = "tests/inputs/empty.txt";
+const FOX: &str = "tests/inputs/fox.txt";
+const NOBODY: &str = "tests/inputs/nobody.txt";
+const NOEOL: &str = "tests/inputs/noeol.txt";
+const INPUTS_DIR: &str = "tests/inputs";
+
+// --------------------------------------------------
+fn gen_bad_file() -> String {
+    loop {
+        let filename: String = rand::rng()
+            .sample_iter(&Alphanumeric)
+            .take(7)
+            .map(char::from)
+            .collect();
+
+        if fs::metadata(&filename).is_err() {
+            return filename;
+        }
+    }
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_no_args() -> Result<()> {
+    Command::cargo_bin(PRG)?
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Usage"));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_pattern() -> Result<()> {
+    Command::cargo_bin(PRG)?
+        .args(["*foo", FOX])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains(r#"Invalid pattern "*foo""#));
+    Command::cargo_bin(PRG)?
+        .args(["-e", "fox", "-e", "(", FOX])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains(r#"Invalid pattern "(""#))
+        .stdout("");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn warns_bad_file() -> Result<()> {
+    let bad = gen_bad_file();
+    let expected = format!("{bad}: .* [(]os error 2[)]");
+    Command::cargo_bin(PRG)?
+        .args(["fox", &bad, FOX])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::is_match(expected)?)
+        .stdout(format!("{FOX}:{}", fs::read_to_string(FOX)?));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn warns_dir_not_recursive() -> Result<()> {
+    Command::cargo_bin(PRG)?
+        .args(["fox", INPUTS_DIR, FOX])
+        .assert()
+        .code(2)
+        .stderr(format!("{INPUTS_DIR}: Is a directory\n"))
+        .stdout(format!("{FOX}:{}", fs::read_to_string(FOX)?));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn exit_status() -> Result<()> {
+    // 選ばれた行があれば 0、なければ 1、読めない入力があれば 2
+    let bad = gen_bad_file();
+    let cases: &[(&[&str], i32)] = &[
+        (&["fox", FOX], 0),
+        (&["-c", "fox", FOX], 0),
+        (&["zzz", FOX], 1),
+        (&["-c", "zzz", FOX], 1),
+        (&["-l", "zzz", FOX, BUSTLE], 1),
+        (&["-v", "", FOX], 1),
+        (&["foo", EMPTY], 1),
+        (&["zzz", &bad], 2),
+        (&["fox", &bad, FOX], 2),
+    ];
+    for (args, code) in cases {
+        Command::cargo_bin(PRG)?.args(*args).assert().code(*code);
+    }
+    Ok(())
+}
+
+// --------------------------------------------------
+fn run(args: &[&str], expected_file: &str) -> Result<()> {
+    run_code(args, expected_file, 0)
+}
+
+fn run_code(args: &[&str], expected_file: &str, code: i32) -> Result<()> {
+    let expected = fs::read(expected_file)?;
+    let output = Command::cargo_bin(PRG)?.args(args).output()?;
+    assert_eq!(output.status.code(), Some(code), "{args:?}");
+    assert_eq!(output.stdout, expected, "{args:?}");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn expected() -> Result<()> {
+    let all = [BUSTLE, EMPTY, FOX, NOBODY];
+    let cases: &[(&[&str], &[&str], &str)] = &[
+        (&[""], &[FOX], "empty_regex.fox"),
+        (&["The"], &[BUSTLE], "bustle.txt.the.capitalized"),
+        (&["the"], &[BUSTLE], "bustle.txt.the.lowercase"),
+        (
+            &["--ignore-case", "the"],
+            &[BUSTLE],
+            "bustle.txt.the.lowercase.insensitive",
+        ),
+        (&["-n", "-v", "the"], &[BUSTLE], "bustle.txt.the.n.v"),
+        (&["Nobody"], &[NOBODY], "nobody.txt"),
+        (&["-v", "Nobody"], &[NOBODY], "nobody.txt.v"),
+        (&["-c", "Nobody"], &[NOBODY], "nobody.txt.c"),
+        (
+            &["--count", "--invert-match", "Nobody"],
+            &[NOBODY],
+            "nobody.txt.c.v",
+        ),
+        (&["fox"], &[NOEOL], "noeol.txt"),
+        (&["The"], &all, "all.the"),
+        (&["-n", "-i", "the"], &all, "all.the.n.i"),
+        (&["-c", "The"], &all, "all.the.c"),
+        (&["-l", "The"], &all, "all.the.l"),
+        (&["-l", "-v", "The"], &all, "all.the.l.v"),
+        (
+            &["-e", "fox", "--regexp", "Nobody"],
+            &[BUSTLE, FOX, NOBODY],
+            "all.e.fox.nobody",
+        ),
+        (
+            &["-F", "-e", "you?", "-e", "us!"],
+            &[NOBODY],
+            "nobody.txt.F",
+        ),
+        (&["-n", "-A", "1", "How"], &[NOBODY], "nobody.txt.A1"),
+        (
+            &["-n", "--before-context", "2", "^To"],
+            &[NOBODY],
+            "nobody.txt.B2",
+        ),
+        (&["-n", "-C", "1", "tell|Bog"], &[NOBODY], "nobody.txt.C1"),
+        (
+            &["-n", "--context", "1", "The"],
+            &[BUSTLE, NOBODY],
+            "all.C1",
+        ),
+        (
+            &["-v", "-A", "1", "-B", "2", "e"],
+            &[BUSTLE],
+            "bustle.txt.v.A1.B2",
+        ),
+        (
+            &["--color=always", "-n", "the"],
+            &[BUSTLE, FOX],
+            "all.the.n.color",
+        ),
+        (
+            &["--color=always", "-i", "-C", "1", "the|in"],
+            &[BUSTLE],
+            "bustle.txt.i.C1.color",
+        ),
+        (
+            &["--color=always", "-c", "The"],
+            &[BUSTLE, FOX],
+            "all.the.c.color",
+        ),
+        (
+            &["--color=always", "-l", "The"],
+            &[BUSTLE, FOX],
+            "all.the.l.color",
+        ),
+    ];
+    for (args, files, name) in cases {
+        run(&[*args, *files].concat(), &format!("tests/expected/{name}"))?;
+    }
+    // 選ばれた行がなければ 1 で終了する
+    run_code(&["foo", EMPTY], "tests/expected/foo.empty", 1)
+}
+
+// --------------------------------------------------
+#[test]
+fn context_overrides() -> Result<()> {
+    // -A と -B は -C より優先する
+    run(
+        &["-n", "-C", "5", "-A", "1", "-B", "0", "How", NOBODY],
+        "tests/expected/nobody.txt.A1",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn color_never() -> Result<()> {
+    // 端末に出力しない場合、--color と --color=auto は色を付けない
+    for color in ["--color", "--color=auto", "--color=never"] {
+        run(
+            &[color, "-n", "-i", "the", BUSTLE, EMPTY, FOX, NOBODY],
+            "tests/expected/all.the.n.i",
+        )?;
+    }
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn stdin() -> Result<()> {
+    let input = fs::read_to_string(BUSTLE)?;
+    let expected =
+        fs::read_to_string("tests/expected/bustle.txt.the.capitalized")?;
+    Command::cargo_bin(PRG)?
+        .arg("The")
+        .write_stdin(input.clone())
+        .assert()
+        .success()
+        .stdout(expected.clone());
+
+    // 複数の入力があれば標準入力は (standard input) と表示する
+    let expected = format!(
+        "{FOX}:{}{}",
+        fs::read_to_string(FOX)?,
+        expected
+            .lines()
+            .map(|line| format!("(standard input):{line}\n"))
+            .collect::<String>()
+    );
+    Command::cargo_bin(PRG)?
+        .args(["-e", "The", FOX, "-"])
+        .write_stdin(input)
+        .assert()
+        .success()
+        .stdout(expected);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn recursive() -> Result<()> {
+    // ディレクトリはファイル名の順にたどる
+    let expected = [
+        "tests/inputs/bustle.txt:The bustle in a house",
+        "tests/inputs/bustle.txt:The morning after death",
+        "tests/inputs/bustle.txt:The sweeping up the heart,",
+        "tests/inputs/fox.txt:The quick brown fox jumps over the lazy dog.",
+        "tests/inputs/nobody.txt:Then there's a pair of us!",
+    ];
+    Command::cargo_bin(PRG)?
+        .args(["-r", "The", INPUTS_DIR])
+        .assert()
+        .success()
+        .stdout(expected.map(|line| format!("{line}\n")).concat());
+
+    Command::cargo_bin(PRG)?
+        .args(["--recursive", "-l", "-i", "nobody|fox", INPUTS_DIR])
+        .assert()
+        .success()
+        .stdout(
+            "tests/inputs/fox.txt\n\
+             tests/inputs/nobody.txt\n\
+             tests/inputs/noeol.txt\n",
+        );
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn recursive_working_dir() -> Result<()> {
+    // ファイルを指定しなければ作業ディレクトリをたどり、./ を付けずに表示する
+    Command::cargo_bin(PRG)?
+        .current_dir(INPUTS_DIR)
+        .args(["-r", "-c", "fox"])
+        .assert()
+        .success()
+        .stdout(
+            "bustle.txt:0\nempty.txt:0\nfox.txt:1\nnobody.txt:0\nnoeol.txt:1\n",
+        );
+    Ok(())
+}
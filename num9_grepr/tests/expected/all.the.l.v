@@ -0,0 +1,2 @@
+tests/inputs/bustle.txt
+tests/inputs/nobody.txt
@@ -0,0 +1,7 @@
+tests/inputs/bustle.txt:1:The bustle in a house
+tests/inputs/bustle.txt:2:The morning after death
+tests/inputs/bustle.txt:6:The sweeping up the heart,
+tests/inputs/fox.txt:1:The quick brown fox jumps over the lazy dog.
+tests/inputs/nobody.txt:3:Then there's a pair of us!
+tests/inputs/nobody.txt:4:Don't tell! they'd advertise—you know!
+tests/inputs/nobody.txt:8:To tell one's name—the livelong June—
@@ -0,0 +1,8 @@
+1:The bustle in a house
+2:The morning after death
+3:Is solemnest of industries
+4:Enacted upon earth,—
+5:
+7:And putting love away
+8:We shall not want to use again
+9:Until eternity.
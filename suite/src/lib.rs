@@ -0,0 +1,229 @@
+use std::{
+    env,
+    error::Error,
+    ffi::OsString,
+    fs,
+    io::{self, ErrorKind, Write},
+    path::Path,
+    process::{ExitCode, Termination},
+};
+
+type MyResult<T> = Result<T, Box<dyn Error>>;
+
+type Tool = fn(Vec<OsString>) -> ExitCode;
+
+const NAME: &str = "suite";
+
+/// Bundled tools, by the name they are invoked as. Each receives its own `argv`.
+///
+/// Every tool crate exposes `get_args_from(args)` beside `get_args`. It parses `args` the same way
+/// `get_args` parses the process arguments, so the first item is the program name shown in usage
+/// and errors.
+const TOOLS: &[(&str, Tool)] = &[
+    ("calr", |args| {
+        report(num14_calr::get_args_from(args).and_then(num14_calr::run))
+    }),
+    ("catr", |args| {
+        report(num3_catr::get_args_from(args).and_then(num3_catr::run))
+    }),
+    ("commr", |args| {
+        report(num11_commr::get_args_from(args).and_then(num11_commr::run))
+    }),
+    ("cutr", |args| {
+        report(num8_cutr::get_args_from(args).and_then(num8_cutr::run))
+    }),
+    ("echor", |args| {
+        report(num2_echor::get_args_from(args).and_then(num2_echor::run))
+    }),
+    ("false", num1_hello::false_main),
+    ("findr", |args| {
+        report(num10_findr::get_args_from(args).and_then(num10_findr::run))
+    }),
+    ("fortuner", |args| {
+        report(num15_fortuner::get_args_from(args).and_then(num15_fortuner::run))
+    }),
+    ("grepr", |args| {
+        report_or(
+            num9_grepr::get_args_from(args).and_then(num9_grepr::run),
+            ExitCode::from(2),
+        )
+    }),
+    ("headr", |args| {
+        report(num4_headr::get_args_from(args).and_then(num4_headr::run))
+    }),
+    ("hello", |args| {
+        report(num1_hello::get_args_from(args).and_then(num1_hello::run))
+    }),
+    ("lsr", |args| {
+        report(num13_lsr::get_args_from(args).and_then(num13_lsr::run))
+    }),
+    ("nlr", |args| {
+        report(num16_nlr::get_args_from(args).and_then(num16_nlr::run))
+    }),
+    ("sortr", |args| {
+        report(num12_sortr::get_args_from(args).and_then(num12_sortr::run))
+    }),
+    ("tailr", |args| {
+        report(num6_tailr::get_args_from(args).and_then(num6_tailr::run))
+    }),
+    ("true", num1_hello::true_main),
+    ("uniqr", |args| {
+        report(num7_uniqr::get_args_from(args).and_then(num7_uniqr::run))
+    }),
+    ("wcr", |args| {
+        report(num5_wcr::get_args_from(args).and_then(num5_wcr::run))
+    }),
+];
+
+/// Run the tool named by `args[0]`, or handle `suite <tool> args...` and the suite's own options.
+pub fn main(mut args: Vec<OsString>) -> ExitCode {
+    if let Some(tool) = args.first().and_then(find_tool) {
+        return tool(args);
+    }
+
+    // suite として呼ばれた場合は先頭の引数をツール名として扱う
+    if !args.is_empty() {
+        args.remove(0);
+    }
+    let result = match args.first().and_then(|arg| arg.to_str()) {
+        None => {
+            eprint!("{}", usage());
+            return ExitCode::FAILURE;
+        }
+        Some("--help") => write_stdout(&usage()),
+        Some("--version") => write_stdout(&format!("{} {}\n", NAME, env!("CARGO_PKG_VERSION"))),
+        Some("--list") => write_stdout(
+            &tool_names()
+                .map(|name| format!("{}\n", name))
+                .collect::<String>(),
+        ),
+        Some("--install") => match args.get(1) {
+            Some(dir) if args.len() == 2 => install(Path::new(dir)),
+            _ => Err(From::from("--install requires exactly one directory")),
+        },
+        Some(_) => match find_tool(&args[0]) {
+            Some(tool) => return tool(args),
+            None => Err(From::from(format!(
+                "unknown tool '{}' (see '{} --list')",
+                args[0].to_string_lossy(),
+                NAME
+            ))),
+        },
+    };
+    report(result.map_err(|e| format!("{}: {}", NAME, e).into()))
+}
+
+/// Look up a tool by the file name of `arg`, so that `/usr/local/bin/catr` finds `catr`.
+fn find_tool(arg: &OsString) -> Option<Tool> {
+    let name = Path::new(arg).file_stem()?.to_str()?;
+    TOOLS
+        .iter()
+        .find(|(tool_name, _)| *tool_name == name)
+        .map(|(_, tool)| *tool)
+}
+
+fn tool_names() -> impl Iterator<Item = &'static str> {
+    TOOLS.iter().map(|(name, _)| *name)
+}
+
+/// Create a symlink to this executable in `dir` for every tool.
+fn install(dir: &Path) -> MyResult<()> {
+    let exe = env::current_exe()?.canonicalize()?;
+    fs::create_dir_all(dir)?;
+
+    for name in tool_names() {
+        let link = dir.join(name);
+        // 既存のシンボリックリンクは張り直すが、通常のファイルは上書きしない
+        match fs::symlink_metadata(&link) {
+            Ok(meta) if meta.file_type().is_symlink() => fs::remove_file(&link)?,
+            Ok(_) => return Err(From::from(format!("{}: File exists", link.display()))),
+            Err(e) if e.kind() == ErrorKind::NotFound => {}
+            Err(e) => return Err(From::from(format!("{}: {}", link.display(), e))),
+        }
+        symlink(&exe, &link).map_err(|e| format!("{}: {}", link.display(), e))?;
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn symlink(original: &Path, link: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(original, link)
+}
+
+#[cfg(windows)]
+fn symlink(original: &Path, link: &Path) -> io::Result<()> {
+    std::os::windows::fs::symlink_file(original, link)
+}
+
+fn usage() -> String {
+    format!(
+        "Rust multicall binary
+
+Usage: {name} TOOL [ARGUMENT]...
+       TOOL [ARGUMENT]...
+       {name} --install DIR
+       {name} --list
+
+A tool also runs when this binary is invoked through a link named after it.
+--install creates such links for every tool in DIR.
+
+Tools: {tools}
+",
+        name = NAME,
+        tools = tool_names().collect::<Vec<_>>().join(" "),
+    )
+}
+
+fn write_stdout(text: &str) -> MyResult<()> {
+    io::stdout().write_all(text.as_bytes())?;
+    Ok(())
+}
+
+/// Exit status for a tool's result. Tools with their own statuses, such as grepr, return an
+/// `ExitCode` from `run`.
+fn report(result: MyResult<impl Termination>) -> ExitCode {
+    report_or(result, ExitCode::FAILURE)
+}
+
+/// Like `report`, but exit with `failure` on an error, as grepr does with 2.
+fn report_or(result: MyResult<impl Termination>, failure: ExitCode) -> ExitCode {
+    match result {
+        Ok(status) => status.report(),
+        Err(e) => {
+            // echor はヘルプとバージョンを clap のエラーとして返すので、clap と同じく表示する
+            if let Some(err) = e.downcast_ref::<clap::Error>() {
+                let _ = err.print();
+                return ExitCode::from(err.exit_code() as u8);
+            }
+            eprintln!("{}", e);
+            failure
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_find_tool() {
+        for (arg, found) in [
+            ("catr", true),
+            ("/usr/local/bin/headr", true),
+            ("./wcr", true),
+            ("suite", false),
+            ("cat", false),
+            ("", false),
+        ] {
+            assert_eq!(find_tool(&OsString::from(arg)).is_some(), found, "{}", arg);
+        }
+    }
+
+    #[test]
+    fn test_tools_sorted() {
+        let names: Vec<_> = tool_names().collect();
+        let mut sorted = names.clone();
+        sorted.sort();
+        assert_eq!(names, sorted);
+    }
+}
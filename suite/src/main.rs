@@ -0,0 +1,5 @@
+use std::{env, process::ExitCode};
+
+fn main() -> ExitCode {
+    suite::main(env::args_os().collect())
+}
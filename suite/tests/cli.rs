@@ -0,0 +1,229 @@
+use std::fs;
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+const PRG: &str = "suite";
+
+type TestResult = Result<(), Box<dyn std::error::Error>>;
+
+const TOOLS: &[&str] = &[
+    "calr", "catr", "commr", "cutr", "echor", "false", "findr", "fortuner", "grepr", "headr",
+    "hello", "lsr", "nlr", "sortr", "tailr", "true", "uniqr", "wcr",
+];
+
+#[test]
+fn dies_no_args() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Usage: suite TOOL"));
+    Ok(())
+}
+
+#[test]
+fn dies_unknown_tool() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .arg("cat")
+        .assert()
+        .code(1)
+        .stderr("suite: unknown tool 'cat' (see 'suite --list')\n");
+    Ok(())
+}
+
+#[test]
+fn lists_tools() -> TestResult {
+    let expected: String = TOOLS.iter().map(|name| format!("{}\n", name)).collect();
+    Command::cargo_bin(PRG)?
+        .arg("--list")
+        .assert()
+        .success()
+        .stdout(expected);
+    Ok(())
+}
+
+#[test]
+fn runs_subcommands() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["echor", "-n", "Hello", "there"])
+        .assert()
+        .success()
+        .stdout("Hello there");
+    Command::cargo_bin(PRG)?
+        .args(["echor", "--version"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("echor "));
+    Command::cargo_bin(PRG)?
+        .args(["echor", "--help"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Usage: echor"));
+    Command::cargo_bin(PRG)?
+        .args(["catr", "-n"])
+        .write_stdin("a\nb\n")
+        .assert()
+        .success()
+        .stdout("     1\ta\n     2\tb\n");
+    Command::cargo_bin(PRG)?
+        .args(["headr", "-n", "1"])
+        .write_stdin("a\nb\n")
+        .assert()
+        .success()
+        .stdout("a\n");
+    Command::cargo_bin(PRG)?
+        .args(["wcr", "-l"])
+        .write_stdin("a\nb\n")
+        .assert()
+        .success()
+        .stdout("       2\n");
+    Command::cargo_bin(PRG)?
+        .args(["hello", "Rust"])
+        .env_remove("LC_ALL")
+        .env_remove("LC_MESSAGES")
+        .env_remove("LANG")
+        .assert()
+        .success()
+        .stdout("Hello, Rust!\n");
+    Command::cargo_bin(PRG)?
+        .args(["tailr", "-n", "+2"])
+        .write_stdin("a\nb\n")
+        .assert()
+        .success()
+        .stdout("b\n");
+    Command::cargo_bin(PRG)?
+        .args(["uniqr", "-c"])
+        .write_stdin("a\na\nb")
+        .assert()
+        .success()
+        .stdout("      2 a\n      1 b");
+    Command::cargo_bin(PRG)?
+        .args(["cutr", "-d", ":", "-f", "2"])
+        .write_stdin("a:b:c\n")
+        .assert()
+        .success()
+        .stdout("b\n");
+    Command::cargo_bin(PRG)?
+        .args(["grepr", "-n", "b"])
+        .write_stdin("a\nb\n")
+        .assert()
+        .success()
+        .stdout("2:b\n");
+    Command::cargo_bin(PRG)?
+        .args(["grepr", "z"])
+        .write_stdin("a\nb\n")
+        .assert()
+        .code(1)
+        .stdout("");
+    Command::cargo_bin(PRG)?
+        .args(["grepr", "("])
+        .write_stdin("a\n")
+        .assert()
+        .code(2);
+    Command::cargo_bin(PRG)?
+        .args(["findr", "../num10_findr/tests/inputs/f", "-type", "f"])
+        .assert()
+        .success()
+        .stdout("../num10_findr/tests/inputs/f/f.txt\n");
+    Command::cargo_bin(PRG)?
+        .args(["commr", "-12", "-", "../num11_commr/tests/inputs/file1.txt"])
+        .write_stdin("b\nc\n")
+        .assert()
+        .success()
+        .stdout("b\nc\n");
+    Command::cargo_bin(PRG)?
+        .args(["sortr", "-n", "-r"])
+        .write_stdin("9\n10\n")
+        .assert()
+        .success()
+        .stdout("10\n9\n");
+    Command::cargo_bin(PRG)?
+        .args(["lsr", "../num13_lsr/tests/inputs/dir"])
+        .assert()
+        .success()
+        .stdout("spiders.txt\n");
+    Command::cargo_bin(PRG)?
+        .args(["calr", "--lang", "en", "-m", "2", "2021"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("   February 2021      \n"));
+    Command::cargo_bin(PRG)?
+        .args([
+            "fortuner",
+            "../num15_fortuner/tests/inputs/more/proverbs",
+            "-m",
+            "bold",
+        ])
+        .assert()
+        .success()
+        .stdout("Fortune favors the bold.\n%\n")
+        .stderr("(../num15_fortuner/tests/inputs/more/proverbs)\n%\n");
+    Command::cargo_bin(PRG)?
+        .args(["nlr", "-b", "a"])
+        .write_stdin("a\n\nb\n")
+        .assert()
+        .success()
+        .stdout("     1\ta\n     2\t\n     3\tb\n");
+    Command::cargo_bin(PRG)?.arg("true").assert().code(0);
+    Command::cargo_bin(PRG)?
+        .args(["false", "--help"])
+        .assert()
+        .code(1);
+    Ok(())
+}
+
+#[test]
+fn reports_tool_errors() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["wcr", "--bogus"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Usage: wcr"));
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn installs_links() -> TestResult {
+    let dir = tempfile::tempdir()?;
+    let bin = dir.path().join("bin");
+    let exe = fs::canonicalize(assert_cmd::cargo::cargo_bin(PRG))?;
+
+    // 2 回目はリンクを張り直すだけで成功する
+    for _ in 0..2 {
+        Command::cargo_bin(PRG)?
+            .arg("--install")
+            .arg(&bin)
+            .assert()
+            .success()
+            .stdout("");
+    }
+    for name in TOOLS {
+        assert_eq!(fs::read_link(bin.join(name))?, exe);
+    }
+
+    Command::new(bin.join("echor"))
+        .args(["-e", r"a\tb"])
+        .assert()
+        .success()
+        .stdout("a\tb\n");
+    Command::new(bin.join("false")).assert().code(1);
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn install_keeps_regular_files() -> TestResult {
+    let dir = tempfile::tempdir()?;
+    let catr = dir.path().join("catr");
+    fs::write(&catr, "keep")?;
+
+    Command::cargo_bin(PRG)?
+        .arg("--install")
+        .arg(dir.path())
+        .assert()
+        .failure()
+        .stderr(format!("suite: {}: File exists\n", catr.display()));
+    assert_eq!(fs::read_to_string(&catr)?, "keep");
+    Ok(())
+}